@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+
+//Whether the direction is in the cap around the center, at most the angle away from it
+pub fn is_in_cap(direction: Vec3, center: Vec3, angle: f32) -> bool {
+    direction.angle_between(center) <= angle
+}
+
+//Whether the direction is inside the lasso through the directions. The lasso is projected onto the plane
+//touching the globe in its middle, so it has to fit on one half of the globe.
+pub fn is_in_lasso(direction: Vec3, lasso: &[Vec3]) -> bool {
+    if lasso.len() < 3 {
+        return false;
+    }
+    let middle = lasso.iter().copied().sum::<Vec3>().normalize_or_zero();
+    if middle == Vec3::ZERO {
+        return false;
+    }
+
+    let u = middle.any_orthonormal_vector();
+    let v = middle.cross(u);
+    let project = |point: Vec3| {
+        let height = point.dot(middle);
+        (height > 0.0).then(|| {
+            let on_plane = point / height;
+            Vec2::new(on_plane.dot(u), on_plane.dot(v))
+        })
+    };
+    let Some(polygon) = lasso.iter().map(|point| project(*point)).collect::<Option<Vec<Vec2>>>() else { return false; };
+    let Some(point) = project(direction) else { return false; };
+
+    //Even-odd rule, a ray from the point crosses the edges an odd number of times when it is inside
+    let mut is_inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+    for current in polygon {
+        if (current.y > point.y) != (previous.y > point.y)
+            && point.x < (previous.x - current.x) * (point.y - current.y) / (previous.y - current.y) + current.x {
+            is_inside = !is_inside;
+        }
+        previous = current;
+    }
+    is_inside
+}
@@ -1,29 +1,57 @@
 use bevy::prelude::*;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use shared::domain::dtos::appearance_dto::AppearanceDto;
 
-// Wrapper struct for Color that implements Eq and Hash
+// Wrapper struct for the color and appearance of a ball that implements Eq and Hash
 #[derive(Clone, Debug)]
-pub struct ColorKey(pub Color);
+pub struct MaterialKey(pub Color, pub AppearanceDto);
 
-impl PartialEq for ColorKey {
+impl PartialEq for MaterialKey {
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+        self.0 == other.0 && self.1 == other.1
     }
 }
 
 #[derive(Resource)]
 pub struct ColorMaterialMap {
-    pub map: HashMap<ColorKey, Handle<StandardMaterial>>,
+    pub map: HashMap<MaterialKey, Handle<StandardMaterial>>,
 }
 
-impl Eq for ColorKey {}
+impl ColorMaterialMap {
+    // Balls that look the same share one material
+    pub fn get_or_insert(&mut self, color: Color, appearance: &AppearanceDto, materials: &mut Assets<StandardMaterial>) -> Handle<StandardMaterial> {
+        self.map
+            .entry(MaterialKey(color, *appearance))
+            .or_insert_with(|| materials.add(ball_material(color, appearance)))
+            .clone()
+    }
+}
 
-impl Hash for ColorKey {
+// The default appearance gives the same material as a plain color
+fn ball_material(color: Color, appearance: &AppearanceDto) -> StandardMaterial {
+    let base_color = color.with_a(color.a() * (1.0 - appearance.transparency));
+    StandardMaterial {
+        base_color,
+        emissive: color * appearance.emissive,
+        metallic: appearance.metallic,
+        perceptual_roughness: appearance.roughness,
+        alpha_mode: if base_color.a() < 1.0 { AlphaMode::Blend } else { AlphaMode::Opaque },
+        ..default()
+    }
+}
+
+impl Eq for MaterialKey {}
+
+impl Hash for MaterialKey {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.0.r().to_bits().hash(state);
         self.0.g().to_bits().hash(state);
         self.0.b().to_bits().hash(state);
         self.0.a().to_bits().hash(state);
+        self.1.emissive.to_bits().hash(state);
+        self.1.metallic.to_bits().hash(state);
+        self.1.roughness.to_bits().hash(state);
+        self.1.transparency.to_bits().hash(state);
     }
-}
\ No newline at end of file
+}
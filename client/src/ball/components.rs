@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use uuid::Uuid;
+use shared::domain::dtos::ball_transaction_dto::BallTransactionDto;
 
 /*#[derive(Component, Default, Reflect)]
 #[reflect(Component)]
@@ -20,6 +21,10 @@ pub struct Upserted;
 #[derive(Component)]
 pub struct BallUuid(pub Uuid);
 
+//Log entry an object of the shared simulation was spawned from. Placeholders and previews have none.
+#[derive(Component)]
+pub struct LogEntry(pub BallTransactionDto);
+
 #[derive(Component)]
 pub struct CapsuleDepth(pub f32);
 
@@ -27,4 +32,37 @@ pub struct CapsuleDepth(pub f32);
 pub struct CapsuleRotation(pub Quat);
 
 #[derive(Component)]
-pub struct SpeedMarker;
\ No newline at end of file
+pub struct SpeedMarker;
+
+//Link between the balls with these uuids, drawn as an arc over the globe.
+//The rope joint is only there when one of the balls is moving.
+#[derive(Component)]
+pub struct Link {
+    pub from_uuid: Uuid,
+    pub to_uuid: Uuid,
+    pub color: Color,
+    pub joint: Option<Entity>,
+}
+
+impl Link {
+    pub fn is_link_to(&self, uuid: &Uuid) -> bool {
+        self.from_uuid == *uuid || self.to_uuid == *uuid
+    }
+}
+
+//Text pinned to the globe. The text is a UI node kept over the anchor, so it faces the camera.
+#[derive(Component)]
+pub struct Annotation {
+    pub anchor: Vec3,
+}
+
+//Annotation being written with the annotation tool, sent when it is done
+#[derive(Component)]
+pub struct AnnotationDraft {
+    pub text: String,
+    pub font_size: f32,
+}
+
+//Symmetric copy that the server would reject, shown but never sent
+#[derive(Component)]
+pub struct RejectedCopy;
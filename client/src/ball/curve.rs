@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+//Angle between two directions whose points at this height are the spacing apart
+pub fn step_angle(spacing: f32, height: f32) -> f32 {
+    2.0 * (spacing / (2.0 * height)).min(1.0).asin()
+}
+
+//Directions evenly spaced along the shorter great-circle arc from one direction to another,
+//so the balls at this height are at least the spacing apart. Both ends are included when they fit.
+pub fn great_circle_arc(from: Vec3, to: Vec3, spacing: f32, height: f32) -> Vec<Vec3> {
+    let angle = from.angle_between(to);
+    let step = step_angle(spacing, height);
+    let axis = from.cross(to);
+    //Same or opposite directions have no single arc between them
+    if angle < step || axis.length_squared() < f32::EPSILON {
+        return vec![from];
+    }
+
+    let intervals = (angle / step).floor();
+    let axis = axis.normalize();
+    (0..=intervals as u32)
+        .map(|i| (Quat::from_axis_angle(axis, angle * i as f32 / intervals) * from).normalize())
+        .collect()
+}
+
+//Directions evenly spaced around the circle on the sphere through three directions, so the balls at this height
+//are at least the spacing apart. A great circle when the plane of the three goes through the center.
+//None when the directions are on one line, or two of them are the same.
+pub fn circle_through(a: Vec3, b: Vec3, c: Vec3, spacing: f32, height: f32) -> Option<Vec<Vec3>> {
+    let normal = (b - a).cross(c - a);
+    if normal.length_squared() < f32::EPSILON {
+        return None;
+    }
+
+    //Axis of the circle on the side of the three directions
+    let mut axis = normal.normalize();
+    if axis.dot(a) < 0.0 {
+        axis = -axis;
+    }
+    let circle_angle = axis.angle_between(a);
+    let ring_radius = height * circle_angle.sin();
+    if spacing >= 2.0 * ring_radius {
+        return Some(vec![a]);
+    }
+
+    let count = (TAU / (2.0 * (spacing / (2.0 * ring_radius)).asin())).floor().max(1.0) as u32;
+    let u = (a - axis * axis.dot(a)).normalize();
+    let v = axis.cross(u);
+    Some((0..count)
+        .map(|i| {
+            let around = TAU * i as f32 / count as f32;
+            (axis * circle_angle.cos() + (u * around.cos() + v * around.sin()) * circle_angle.sin()).normalize()
+        })
+        .collect())
+}
@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+//Deletes the balls selected with the cap eraser or the lasso
+#[derive(Event)]
+pub struct DeleteSelectionEvent;
+
+//Puts back the balls deleted last with the cap eraser or the lasso
+#[derive(Event)]
+pub struct UndoDeleteSelectionEvent;
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::f32::consts::FRAC_PI_2;
+
+//Vertices of an icosphere with radius 1, and the edges between them
+#[derive(Default)]
+pub struct Geodesic {
+    pub vertices: Vec<Vec3>,
+    pub edges: Vec<(usize, usize)>,
+}
+
+//Icosahedron with each triangle split in four, subdivisions times
+pub fn geodesic_grid(subdivisions: u32) -> Geodesic {
+    let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+    let mut vertices: Vec<Vec3> = [
+        (-1.0, t, 0.0), (1.0, t, 0.0), (-1.0, -t, 0.0), (1.0, -t, 0.0),
+        (0.0, -1.0, t), (0.0, 1.0, t), (0.0, -1.0, -t), (0.0, 1.0, -t),
+        (t, 0.0, -1.0), (t, 0.0, 1.0), (-t, 0.0, -1.0), (-t, 0.0, 1.0),
+    ].into_iter().map(|(x, y, z)| Vec3::new(x, y, z).normalize()).collect();
+    let mut faces: Vec<[usize; 3]> = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        //Triangles next to each other share the middle of their edge
+        let mut middles: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut middle = |a: usize, b: usize, vertices: &mut Vec<Vec3>| {
+            *middles.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                vertices.push((vertices[a] + vertices[b]).normalize());
+                vertices.len() - 1
+            })
+        };
+        faces = faces.into_iter().flat_map(|[a, b, c]| {
+            let ab = middle(a, b, &mut vertices);
+            let bc = middle(b, c, &mut vertices);
+            let ca = middle(c, a, &mut vertices);
+            [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+        }).collect();
+    }
+
+    let mut edges: Vec<(usize, usize)> = faces.iter()
+        .flat_map(|[a, b, c]| [(*a, *b), (*b, *c), (*c, *a)])
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .collect();
+    edges.sort();
+    edges.dedup();
+    Geodesic { vertices, edges }
+}
+
+//The vertex closest to the direction
+pub fn snap_to_vertices(direction: Vec3, vertices: &[Vec3]) -> Vec3 {
+    vertices.iter()
+        .copied()
+        .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+        .unwrap_or(direction)
+}
+
+//Latitude and longitude in radians, with the y axis through the poles and longitude 0 along the x axis
+pub fn to_lat_long(direction: Vec3) -> (f32, f32) {
+    let direction = direction.normalize();
+    (direction.y.clamp(-1.0, 1.0).asin(), (-direction.z).atan2(direction.x))
+}
+
+pub fn from_lat_long(latitude: f32, longitude: f32) -> Vec3 {
+    Vec3::new(latitude.cos() * longitude.cos(), latitude.sin(), -latitude.cos() * longitude.sin())
+}
+
+//The closest crossing of the parallels and meridians every step radians
+pub fn snap_to_lat_long(direction: Vec3, step: f32) -> Vec3 {
+    let (latitude, longitude) = to_lat_long(direction);
+    let latitude = ((latitude / step).round() * step).clamp(-FRAC_PI_2, FRAC_PI_2);
+    let longitude = (longitude / step).round() * step;
+    from_lat_long(latitude, longitude)
+}
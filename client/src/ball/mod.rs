@@ -1,19 +1,25 @@
 use bevy::prelude::*;
 
 use crate::AppState;
+use crate::simulation_clock::SimulationStep;
+use bevy_rapier3d::prelude::PhysicsSet;
+use resources::{ScheduledTransactions, PendingLinks, ReceivedLog, SimulationCheckpoints, LinkStart, BrushStroke, CurveDraft, ClickPlacement, AreaSelection, BallRecords, DeletedSelections, GeodesicGrid, AliveSetHashCheck, PhysicsProfile};
 
 pub mod components;
 pub mod resources;
 pub mod systems;
 pub mod spawn;
 pub mod color_material_map;
+pub mod curve;
+pub mod area;
+pub mod grid;
+pub mod events;
 
 use systems::*;
 use color_material_map::*;
+use events::{DeleteSelectionEvent, UndoDeleteSelectionEvent};
 use std::collections::HashMap;
 
-pub const BALL_RADIUS: f32 = 0.05;
-
 pub struct BallPlugin;
 
 impl Plugin for BallPlugin {
@@ -23,15 +29,60 @@ impl Plugin for BallPlugin {
                 map: HashMap::new(),
             })
             .add_systems(PreStartup, init_ball_resources)
-            .add_systems(Update, push_ball_against_globe)
-            .add_systems(Update, handle_ball_collision)
+            .insert_resource(ScheduledTransactions::default())
+            .insert_resource(PendingLinks::default())
+            .insert_resource(ReceivedLog::default())
+            .insert_resource(SimulationCheckpoints::default())
+            .insert_resource(LinkStart::default())
+            .insert_resource(BrushStroke::default())
+            .insert_resource(CurveDraft::default())
+            .insert_resource(ClickPlacement::default())
+            .insert_resource(AreaSelection::default())
+            .insert_resource(BallRecords::default())
+            .insert_resource(DeletedSelections::default())
+            .insert_resource(GeodesicGrid::default())
+            .add_event::<DeleteSelectionEvent>()
+            .add_event::<UndoDeleteSelectionEvent>()
+            .insert_resource(AliveSetHashCheck::default())
+            .insert_resource(PhysicsProfile::default())
+            .add_systems(SimulationStep, (apply_due_transactions, spawn_due_links, apply_physics_profile, push_ball_against_globe).chain().before(PhysicsSet::SyncBackend))
+            .add_systems(SimulationStep, handle_ball_collision.after(PhysicsSet::StepSimulation))
+            .add_systems(SimulationStep, take_checkpoint.after(PhysicsSet::Writeback).after(handle_ball_collision))
             .add_systems(Update, handle_delete_state.run_if(in_state(AppState::EditUpsert)))
             .add_systems(Update, handle_delete_state.run_if(in_state(AppState::EditDelete)))
             .add_systems(Update, edit_upsert_ball_on_globe.run_if(in_state(AppState::EditUpsert)))
             .add_systems(Update, edit_upsert_set_speed.run_if(in_state(AppState::EditUpsertSetSpeed)))
-            .add_systems(Update, finalize_upsert_ball_on_globe.run_if(in_state(AppState::EditUpsertSetSpeed)))
+            .add_systems(Update, (finalize_upsert_ball_on_globe, place_symmetric_copies).chain().run_if(in_state(AppState::EditUpsertSetSpeed)))
+            .add_systems(Update, preview_rejected_click_copies.run_if(in_state(AppState::EditUpsertSetSpeed)))
+            .add_systems(OnExit(AppState::EditUpsertSetSpeed), despawn_rejected_copies)
+            .add_systems(OnExit(AppState::EditBrush), despawn_rejected_copies)
+            .add_systems(OnExit(AppState::EditCurve), despawn_rejected_copies)
+            .add_systems(Update, handle_area_state.run_if(in_state(AppState::EditUpsert)))
+            .add_systems(Update, handle_area_state.run_if(in_state(AppState::EditArea)))
+            .add_systems(Update, (draw_area_selection, delete_area_selection, draw_area_gizmos).chain().run_if(in_state(AppState::EditArea)))
+            .add_systems(Update, undo_delete_selection.run_if(in_state(AppState::EditArea)))
+            .add_systems(Update, handle_selection_change_answers)
+            .add_systems(Update, record_balls)
+            .add_systems(Update, (update_geodesic_grid, draw_snap_grid).chain())
             .add_systems(Update, edit_delete_ball.run_if(in_state(AppState::EditDelete)))
-            .add_systems(Update, receive_ball_transactions_event_listener);
+            .add_systems(Update, handle_link_state.run_if(in_state(AppState::EditUpsert)))
+            .add_systems(Update, handle_link_state.run_if(in_state(AppState::EditLink)))
+            .add_systems(Update, edit_link_ball.run_if(in_state(AppState::EditLink)))
+            .add_systems(Update, handle_annotation_state.run_if(in_state(AppState::EditUpsert)))
+            .add_systems(Update, handle_annotation_state.run_if(in_state(AppState::EditAnnotation)))
+            .add_systems(Update, (edit_annotation_on_globe, type_annotation_text).chain().run_if(in_state(AppState::EditAnnotation)))
+            .add_systems(Update, edit_delete_annotation.run_if(in_state(AppState::EditDelete)))
+            .add_systems(Update, handle_brush_state.run_if(in_state(AppState::EditUpsert)))
+            .add_systems(Update, handle_brush_state.run_if(in_state(AppState::EditBrush)))
+            .add_systems(Update, (paint_brush_stroke, finish_brush_stroke).chain().run_if(in_state(AppState::EditBrush)))
+            .add_systems(Update, handle_curve_state.run_if(in_state(AppState::EditUpsert)))
+            .add_systems(Update, handle_curve_state.run_if(in_state(AppState::EditCurve)))
+            .add_systems(Update, (pick_curve_points, preview_curve).chain().run_if(in_state(AppState::EditCurve)))
+            .add_systems(Update, receive_ball_transactions_event_listener)
+            .add_systems(Update, receive_physics_profile_event_listener)
+            .add_systems(Update, receive_physics_profile_changes)
+            .add_systems(Update, draw_links)
+            .add_systems(Update, position_annotations);
         
     }
 }
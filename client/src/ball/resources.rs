@@ -1,7 +1,192 @@
 use bevy::prelude::*;
+use uuid::Uuid;
+use shared::domain::dtos::ball_transaction_dto::BallTransactionDto;
+use shared::domain::dtos::physics_profile_dto::PhysicsProfileDto;
+use shared::domain::dtos::body_dto::ShapeDto;
+use crate::ui::spawn::{CurveTool, AreaTool};
+use shared::domain::dtos::ball_dto::BallDto;
+use std::collections::{HashMap, VecDeque};
+use bevy_rapier3d::prelude::Velocity;
+use super::grid::Geodesic;
 
+//Meshes of the shapes with radius 1, balls are scaled to their radius
 #[derive(Resource)]
 pub struct HandleForBallMesh {
-    pub handle: Handle<Mesh>,
+    pub sphere: Handle<Mesh>,
+    pub cube: Handle<Mesh>,
+    pub capsule: Handle<Mesh>,
+}
+
+impl HandleForBallMesh {
+    pub fn get(&self, shape: ShapeDto) -> Handle<Mesh> {
+        match shape {
+            ShapeDto::Sphere => self.sphere.clone(),
+            ShapeDto::Cube => self.cube.clone(),
+            ShapeDto::Capsule => self.capsule.clone(),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct HandleForAnnotationFont {
+    pub handle: Handle<Font>,
+}
+
+#[derive(Resource)]
+pub struct HandleForRejectedCopyMaterial {
+    pub handle: Handle<StandardMaterial>,
+}
+
+//Log entry received from the server, waiting for the simulation step of its transaction
+#[derive(Clone)]
+pub struct ScheduledTransaction {
+    pub step: Option<u64>,
+    pub ball_transaction: BallTransactionDto,
+    //Set when the object is put back from a checkpoint
+    pub restored: Option<RestoredState>,
+}
+
+//What a checkpoint keeps of an object besides the log entry it was spawned from
+#[derive(Clone, Copy)]
+pub enum RestoredState {
+    MovingBall {
+        transform: Transform,
+        velocity: Velocity,
+        speed: f32,
+    },
+    //The rope of a link keeps the length it got when the link was spawned
+    Link {
+        rope_length: f32,
+    },
+}
+
+//Log entries not applied to the shared simulation yet, in the order of the log.
+//Every client applies them at the same steps and in the same order.
+#[derive(Resource, Default)]
+pub struct ScheduledTransactions(pub Vec<ScheduledTransaction>);
+
+//Links due at this step, spawned once the balls of the step are
+#[derive(Resource, Default)]
+pub struct PendingLinks(pub Vec<ScheduledTransaction>);
+
+//Objects of the shared simulation at the end of a step, in the order of the log
+pub struct SimulationCheckpoint {
+    pub step: u64,
+    pub objects: Vec<(BallTransactionDto, Option<RestoredState>)>,
+}
+
+//Recent checkpoints, the oldest first. An entry that arrives after its step makes the simulation
+//run again from the last checkpoint before that step.
+#[derive(Resource, Default)]
+pub struct SimulationCheckpoints(pub VecDeque<SimulationCheckpoint>);
+
+//Entries received after the oldest checkpoint in the order of the log, to run the simulation again from a checkpoint.
+//Until the first checkpoint it has every entry since the log or the snapshot was loaded.
+#[derive(Resource, Default)]
+pub struct ReceivedLog {
+    pub ball_transactions: Vec<ScheduledTransaction>,
+    //Entries from before the oldest checkpoint were dropped
+    pub is_trimmed: bool,
+}
+
+//Ball clicked first with the link tool, the link goes from it to the next ball clicked
+#[derive(Resource, Default)]
+pub struct LinkStart(pub Option<Uuid>);
+
+//Balls previewed by the brush while the pointer is held down, sent together when it is released.
+//last_point is where the stroke was last sampled, also when no ball fit there.
+#[derive(Resource, Default)]
+pub struct BrushStroke {
+    pub balls: Vec<(Uuid, Vec3)>,
+    pub last_point: Option<Vec3>,
+}
+
+//Line or circle being drawn, from the points picked so far. Its balls are previewed until it is placed.
+#[derive(Resource, Default)]
+pub struct CurveDraft {
+    pub tool: Option<CurveTool>,
+    pub points: Vec<Vec3>,
+    pub balls: Vec<(Uuid, Vec3)>,
+    pub rejected: Vec<Vec3>,
+}
+
+//Area drawn with the cap eraser or the lasso, and the balls in it
+#[derive(Resource, Default)]
+pub struct AreaSelection {
+    pub tool: Option<AreaTool>,
+    pub is_drawing: bool,
+    //Center and angle of the cap
+    pub cap: Option<(Vec3, f32)>,
+    pub lasso: Vec<Vec3>,
+    pub selected: Vec<Uuid>,
+}
+
+//Icosphere balls snap to, built for the subdivisions picked
+#[derive(Resource, Default)]
+pub struct GeodesicGrid {
+    pub subdivisions: Option<u32>,
+    pub geodesic: Geodesic,
+}
+
+//Balls as they were inserted, so deleted ones can be put back
+#[derive(Resource, Default)]
+pub struct BallRecords(pub HashMap<Uuid, BallDto>);
+
+//Balls deleted together with the cap eraser or the lasso, the latest last.
+//A delete is only added and an undo only taken once the server has committed it.
+#[derive(Resource, Default)]
+pub struct DeletedSelections {
+    pub deleted: Vec<Vec<BallDto>>,
+    pub in_flight: Option<SelectionChange>,
+}
+
+//Delete or undo of a selection that has been sent, but not answered by the server yet
+pub enum SelectionChange {
+    Delete { uuids: Vec<Uuid>, deleted_balls: Vec<BallDto> },
+    Undo { uuids: Vec<Uuid> },
+}
+
+impl SelectionChange {
+    pub fn uuids(&self) -> &[Uuid] {
+        match self {
+            SelectionChange::Delete { uuids, .. } | SelectionChange::Undo { uuids } => uuids,
+        }
+    }
+}
+
+//Position and impulse of the ball just placed with a click, for placing its symmetric copies
+#[derive(Resource, Default)]
+pub struct ClickPlacement(pub Option<(Vec3, Option<Vec3>)>);
+
+//Physics rules of the globe. Changes are kept with the time they were made, and the simulation
+//uses the one in force at its step, so every client switches at the same step.
+#[derive(Resource, Default)]
+pub struct PhysicsProfile {
+    pub current: PhysicsProfileDto,
+    //Sorted by updated_at
+    pub changes: Vec<PhysicsProfileDto>,
+}
+
+impl PhysicsProfile {
+    //The newest profile, the one to show and edit
+    pub fn latest(&self) -> &PhysicsProfileDto {
+        self.changes.last().unwrap_or(&self.current)
+    }
+
+    //Changes come from the log, from polling and from setting one, each is kept once
+    pub fn add_change(&mut self, physics_profile: PhysicsProfileDto) {
+        if self.changes.iter().any(|change| change.updated_at == physics_profile.updated_at) {
+            return;
+        }
+        let index = self.changes.partition_point(|change| change.updated_at < physics_profile.updated_at);
+        self.changes.insert(index, physics_profile);
+    }
+}
+
+//Consecutive responses where the objects of the shared simulation, with the entries waiting for their step,
+//did not match the server's alive set. Resync only when it lasts.
+#[derive(Resource, Default)]
+pub struct AliveSetHashCheck {
+    pub mismatches: u32,
 }
 
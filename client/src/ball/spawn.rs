@@ -1,43 +1,49 @@
 use bevy::prelude::*;
 use bevy::math::*;
+use bevy::ecs::system::EntityCommands;
 use bevy_rapier3d::prelude::*;
 use uuid::Uuid;
+use shared::domain::dtos::link_dto::LinkDto;
+use shared::domain::dtos::body_dto::{BodyDto, ShapeDto, CUBE_HALF_SIZE, CAPSULE_RADIUS, CAPSULE_HALF_HEIGHT};
+use shared::domain::dtos::appearance_dto::AppearanceDto;
 
-use super::BALL_RADIUS;
 use super::components::*;
 use super::resources::*;
 use super::color_material_map::*;
 
+//Light of a glowing ball per unit of emissive, it reaches the balls around it
+const GLOW_LIGHT_LUMENS: f32 = 2_000.0;
+const GLOW_LIGHT_RANGE: f32 = 0.5;
+
 pub fn spawn_static_ball(
     commands: &mut Commands, 
     ball_mesh_resource: &Res<HandleForBallMesh>,
     ball_materials_resource: &mut ResMut<ColorMaterialMap>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
     color: Color,
+    body: &BodyDto,
+    appearance: &AppearanceDto,
     point_on_sphere: (f32, f32, f32),
     upserted: bool,
     uuid: Option<Uuid>,
-) {
+) -> Entity {
     //bevy::log::info!("spawn_static_ball, ball_materials_resource length = {:?}", ball_materials_resource.map.len());
     
     // Decide on the UUID to use: either the one provided, or generate a new one
     let ball_uuid = uuid.unwrap_or_else(Uuid::new_v4);
 
-    let material_handle = ball_materials_resource
-        .map
-        .entry(ColorKey(color))
-        .or_insert_with(|| materials.add(color))
-        .clone();
+    let material_handle = ball_materials_resource.get_or_insert(color, appearance, materials);
 
     let mut spawned_entity = commands.spawn(PbrBundle {
-        mesh: ball_mesh_resource.handle.clone(),
+        mesh: ball_mesh_resource.get(body.shape),
         material: material_handle,
         ..default()
     });
+    insert_glow_light(&mut spawned_entity, color, appearance);
 
     spawned_entity.insert((
-        TransformBundle::from(Transform::from_xyz(point_on_sphere.0, point_on_sphere.1, point_on_sphere.2)),
-        Collider::ball(BALL_RADIUS),
+        TransformBundle::from(ball_transform(body, point_on_sphere)),
+        body_collider(body.shape),
         Friction::coefficient(0.0),
         Restitution::coefficient(1.0),
         RigidBody::Fixed,
@@ -49,9 +55,66 @@ pub fn spawn_static_ball(
         BallUuid(ball_uuid)  // Use the decided UUID
     ));
 
+    if let Some(mass) = body.mass {
+        spawned_entity.insert(ColliderMassProperties::Mass(mass));
+    }
     if upserted {
         spawned_entity.insert(Upserted);
     }
+    spawned_entity.id()
+}
+
+//Glowing balls light up what is near them, the light goes with the ball
+fn insert_glow_light(spawned_entity: &mut EntityCommands, color: Color, appearance: &AppearanceDto) {
+    if appearance.emissive <= 0.0 {
+        return;
+    }
+    spawned_entity.with_children(|parent| {
+        parent.spawn(PointLightBundle {
+            point_light: PointLight {
+                color,
+                intensity: appearance.emissive * GLOW_LIGHT_LUMENS,
+                range: GLOW_LIGHT_RANGE,
+                shadows_enabled: false,
+                ..default()
+            },
+            ..default()
+        });
+    });
+}
+
+//Balls are scaled to their radius, the collider is scaled with them
+fn ball_transform(body: &BodyDto, point_on_sphere: (f32, f32, f32)) -> Transform {
+    Transform::from_xyz(point_on_sphere.0, point_on_sphere.1, point_on_sphere.2)
+        .with_scale(Vec3::splat(body.radius))
+}
+
+//Marker for a symmetric copy the server would reject, without physics so nothing hits it
+pub fn spawn_rejected_copy(
+    commands: &mut Commands,
+    ball_mesh_resource: &Res<HandleForBallMesh>,
+    rejected_material_resource: &Res<HandleForRejectedCopyMaterial>,
+    body: &BodyDto,
+    position: Vec3,
+) {
+    commands.spawn((
+        PbrBundle {
+            mesh: ball_mesh_resource.get(body.shape),
+            material: rejected_material_resource.handle.clone(),
+            transform: ball_transform(body, (position.x, position.y, position.z)),
+            ..default()
+        },
+        RejectedCopy,
+    ));
+}
+
+//The same shapes the server simulates, with radius 1
+fn body_collider(shape: ShapeDto) -> Collider {
+    match shape {
+        ShapeDto::Sphere => Collider::ball(1.0),
+        ShapeDto::Cube => Collider::cuboid(CUBE_HALF_SIZE, CUBE_HALF_SIZE, CUBE_HALF_SIZE),
+        ShapeDto::Capsule => Collider::capsule_y(CAPSULE_HALF_HEIGHT, CAPSULE_RADIUS),
+    }
 }
 
 pub fn spawn_moving_ball(commands: &mut Commands, 
@@ -59,33 +122,32 @@ pub fn spawn_moving_ball(commands: &mut Commands,
     ball_materials_resource: &mut ResMut<ColorMaterialMap>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
     color: Color,
+    body: &BodyDto,
+    appearance: &AppearanceDto,
     point_on_sphere: (f32, f32, f32),
     impulse: Vec3,
     uuid: Option<Uuid>,
- ) {
+ ) -> Entity {
     //bevy::log::info!("spawn_moving_ball");
     // Decide on the UUID to use: either the one provided, or generate a new one
     let ball_uuid = uuid.unwrap_or_else(Uuid::new_v4);
 
     //ball
-    //get material for color and spawn
-    let material_handle = ball_materials_resource
-        .map
-        .entry(ColorKey(color))
-        .or_insert_with(|| materials.add(color))
-        .clone();
+    //get material for color and appearance and spawn
+    let material_handle = ball_materials_resource.get_or_insert(color, appearance, materials);
 
     let mut spawned_entity = commands.spawn(PbrBundle {
-        mesh: ball_mesh_resource.handle.clone(),
+        mesh: ball_mesh_resource.get(body.shape),
         material: material_handle,
         ..default()
     });
+    insert_glow_light(&mut spawned_entity, color, appearance);
     
     spawned_entity.insert((
-        TransformBundle::from(Transform::from_xyz(point_on_sphere.0, point_on_sphere.1, point_on_sphere.2)),
+        TransformBundle::from(ball_transform(body, point_on_sphere)),
         Sleeping::disabled(),
         Ccd::enabled(),
-        Collider::ball(BALL_RADIUS),
+        body_collider(body.shape),
         Friction::coefficient(0.0),
         RigidBody::Dynamic,
         CollisionGroups {
@@ -115,7 +177,74 @@ pub fn spawn_moving_ball(commands: &mut Commands,
     ));
 
     spawned_entity.insert(Speed(0.0));  
-       
+    //Set from the physics profile of the globe
+    spawned_entity.insert(Damping {
+        linear_damping: 0.0,
+        angular_damping: 0.0,
+    });
+    if let Some(mass) = body.mass {
+        spawned_entity.insert(ColliderMassProperties::Mass(mass));
+    }
+    spawned_entity.id()
+}
+
+//The joint is a child of the to ball, so a ball can have many links.
+//The rope lets the balls move closer, but not further apart than they are now, or than rope_length when it is given.
+pub fn spawn_link(
+    commands: &mut Commands,
+    uuid: Uuid,
+    link: &LinkDto,
+    from_ball: (Entity, Vec3),
+    to_ball: (Entity, Vec3),
+    is_moving: bool,
+    color: Color,
+    rope_length: Option<f32>,
+) -> Entity {
+    let joint = is_moving.then(|| {
+        let rope = RopeJointBuilder::new(rope_length.unwrap_or_else(|| from_ball.1.distance(to_ball.1)));
+        commands.spawn((TransformBundle::default(), ImpulseJoint::new(from_ball.0, rope)))
+            .set_parent(to_ball.0)
+            .id()
+    });
+
+    commands.spawn((
+        Link {
+            from_uuid: link.from_uuid,
+            to_uuid: link.to_uuid,
+            color,
+            joint,
+        },
+        BallUuid(uuid),
+    )).id()
+}
+
+//Drawn behind the menus
+pub fn spawn_annotation(
+    commands: &mut Commands,
+    annotation_font_resource: &Res<HandleForAnnotationFont>,
+    anchor: Vec3,
+    text: &str,
+    font_size: f32,
+    color: Color,
+) -> Entity {
+    commands.spawn(TextBundle {
+        text: Text::from_section(text, TextStyle {
+            font: annotation_font_resource.handle.clone(),
+            font_size,
+            color,
+        })
+        .with_justify(JustifyText::Center),
+        style: Style {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        z_index: ZIndex::Global(-1),
+        //Shown when it is placed over the anchor
+        visibility: Visibility::Hidden,
+        ..default()
+    })
+    .insert(Annotation { anchor })
+    .id()
 }
 
 pub fn spawn_speed_marker(
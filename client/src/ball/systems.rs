@@ -4,44 +4,97 @@ use shared::domain::dtos::ball_transaction_dto::BallTransactionDto;
 use uuid::Uuid;
 use crate::query_server::LastReceivedTransaction;
 use crate::query_server::SendTransactionsRequestEvent;
+use crate::query_server::SendSnapshotRequestEvent;
+use crate::simulation_clock::SimulationClock;
 use crate::ui::spawn::SelectedColor;
+use crate::ui::spawn::SelectedBody;
+use crate::ui::spawn::SelectedAppearance;
+use shared::domain::dtos::appearance_dto::AppearanceDto;
 use crate::ui::spawn::SelectedDelete;
+use crate::ui::spawn::SelectedLink;
+use crate::ui::spawn::SelectedAnnotation;
+use crate::ui::spawn::SelectedBrush;
+use crate::ui::spawn::{SelectedCurve, CurveTool};
+use crate::ui::spawn::Symmetry;
+use crate::ui::spawn::{SelectedArea, AreaTool};
+use crate::ui::spawn::{SnapGrid, SnapMode};
 
-use super::BALL_RADIUS;
 use super::components::*;
 use super::resources::*;
 use super::spawn::*;
 use super::color_material_map::*;
+use super::curve;
+use super::area;
+use super::grid;
+use super::events::{DeleteSelectionEvent, UndoDeleteSelectionEvent};
 use crate::AppState;
 use crate::globe;
 use shared::domain::dtos::ball_dto::BallDto;
 use shared::domain::dtos::position_dto::PositionDto;
 use shared::domain::dtos::impulse_dto::ImpulseDto;
-use std::collections::HashSet;
+use shared::domain::dtos::link_dto::LinkDto;
+use shared::domain::dtos::annotation_dto::{AnnotationDto, DEFAULT_FONT_SIZE, MIN_FONT_SIZE, MAX_FONT_SIZE, MAX_ANNOTATION_LENGTH, MAX_ANCHOR_HEIGHT};
+use shared::domain::dtos::body_dto::{BodyDto, CUBE_HALF_SIZE, CAPSULE_RADIUS, CAPSULE_HALF_HEIGHT};
+use shared::domain::alive_set_hash::alive_set_hash;
+use shared::domain::dtos::batch_request_dto::MAX_BATCH_SIZE;
+use std::collections::{HashMap, HashSet};
 use bevy::math::Vec3;
-use rand::Rng;
 
 const SPEED_MARKER_MAX_LENGTH: f32 = 0.5;
+//Line segments in the arc drawn for a link
+const LINK_ARC_SEGMENTS: u32 = 24;
+//Grid lines are drawn this far from the center, over the globe
+const SNAP_GRID_HEIGHT: f32 = 1.002;
+const SNAP_GRID_SEGMENTS: usize = 64;
+//Lasso points closer together than this angle are not added
+const LASSO_POINT_ANGLE: f32 = 0.01;
+//Extra room between balls placed by the brush and curve tools, so rounding never brings them under the server's minimum distance
+const SPACING_MARGIN: f32 = 1.01;
+
+//Balls previewed by the brush and curve tools, not yet sent
+type PreviewBalls<'w, 's> = Query<'w, 's, (Entity, &'static BallUuid), (With<Upserted>, With<StaticBall>)>;
+//Fixed balls already on the globe, that placed balls must keep their distance to
+type FixedBalls<'w, 's> = Query<'w, 's, &'static Transform, (With<StaticBall>, Without<Upserted>)>;
 
 //add mesh and material for ball and add to resource
 pub fn init_ball_resources(mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    //mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
 
-    let ball_mesh_handle: Handle<Mesh> = meshes.add(Mesh::from(Sphere {
-        radius: BALL_RADIUS,
+    let sphere_mesh_handle: Handle<Mesh> = meshes.add(Mesh::from(Sphere {
+        radius: 1.0,
         ..default()
     }));
+    let cube_mesh_handle: Handle<Mesh> = meshes.add(Mesh::from(Cuboid::from_size(Vec3::splat(2.0 * CUBE_HALF_SIZE))));
+    let capsule_mesh_handle: Handle<Mesh> = meshes.add(Mesh::from(Capsule3d {
+        radius: CAPSULE_RADIUS,
+        half_length: CAPSULE_HALF_HEIGHT,
+    }));
 
-    commands.insert_resource(HandleForBallMesh { handle: ball_mesh_handle });     
+    commands.insert_resource(HandleForBallMesh {
+        sphere: sphere_mesh_handle,
+        cube: cube_mesh_handle,
+        capsule: capsule_mesh_handle,
+    });
+    commands.insert_resource(HandleForAnnotationFont { handle: asset_server.load("fonts/FiraSans-Bold.ttf") });
+    commands.insert_resource(HandleForRejectedCopyMaterial {
+        handle: materials.add(StandardMaterial {
+            base_color: Color::rgba(1.0, 0.0, 0.0, 0.35),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        }),
+    });
 }
 
 pub fn push_ball_against_globe(
     mut query_balls: Query<(&mut ExternalForce, &Transform, &ReadMassProperties), With<MovingBall>>,
     globe_pos: Res<globe::GlobePos>,
+    physics_profile: Res<PhysicsProfile>,
 ) {
-    let gravity = 9.8;
+    let gravity = physics_profile.current.gravity;
 
     for (mut ball_force, ball_transform, ball_mass_props) in query_balls.iter_mut() {
         let force = gravity * ball_mass_props.mass;
@@ -57,7 +110,16 @@ pub fn push_ball_against_globe(
 pub fn handle_ball_collision(
     mut query_balls: Query<(Entity, &mut Velocity, &mut Speed), With<MovingBall>>,
     mut contact_events: EventReader<CollisionEvent>,
+    physics_profile: Res<PhysicsProfile>,
 ) {
+    if !physics_profile.current.preserve_speed {
+        contact_events.clear();
+        //Start over from the current speed if it is turned on again
+        for (_, _, mut speed) in query_balls.iter_mut() {
+            speed.0 = 0.0;
+        }
+        return;
+    }
 
     for contact_event in contact_events.read() {
         //Keep incoming speed in Speed component
@@ -93,6 +155,8 @@ pub fn edit_upsert_ball_on_globe(
     mut ball_material_resource: ResMut<ColorMaterialMap>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     selected_color_resource: Res<SelectedColor>,
+    selected_body_resource: Res<SelectedBody>,
+    selected_appearance_resource: Res<SelectedAppearance>,
     cameras: Query<(&Camera, &GlobalTransform)>,
     rapier_context: Res<RapierContext>,
     mouse: Res<ButtonInput<MouseButton>>,
@@ -100,6 +164,8 @@ pub fn edit_upsert_ball_on_globe(
     windows: Query<&Window>,
     query_globe: Query<Entity, With<globe::Globe>>,
     mut next_state: ResMut<NextState<AppState>>,
+    snap_grid: Res<SnapGrid>,
+    geodesic_grid: Res<GeodesicGrid>,
 ) {
     // Check if the left mouse button was just pressed or if there is a touch input
     if !mouse.just_pressed(MouseButton::Left) && touches.iter().next().is_none() {
@@ -116,7 +182,8 @@ pub fn edit_upsert_ball_on_globe(
     if let Some(cursor_position) = input_position {
         for (camera, camera_transform) in &cameras {
             if let Some(ray) = camera.viewport_to_world(camera_transform, cursor_position) {
-                let ball_shape = Collider::ball(BALL_RADIUS);
+                //The ball ends up resting on the globe
+                let ball_shape = Collider::ball(selected_body_resource.0.radius);
                 let shape_rot = Quat::from_rotation_z(0.0);
 
                 if let Some((entity, hit)) = rapier_context.cast_shape(
@@ -131,11 +198,14 @@ pub fn edit_upsert_ball_on_globe(
                     for entity_globe in query_globe.iter() {
                         if entity_globe == entity {
                             let hit_point = ray.origin + ray.direction * hit.toi;
+                            let hit_point = snap_to_grid(hit_point.normalize(), &snap_grid, &geodesic_grid) * hit_point.length();
                             spawn_static_ball(&mut commands, 
                                 &ball_mesh_resource,
                                 &mut ball_material_resource,
                                 &mut materials,
                                 selected_color_resource.0,
+                                &selected_body_resource.0,
+                                &selected_appearance_resource.0,
                                 (hit_point.x, hit_point.y, hit_point.z),
                                 true,
                                 None
@@ -152,6 +222,60 @@ pub fn edit_upsert_ball_on_globe(
 }
 
 
+//Direction of the grid point closest to the direction, when snapping is on
+fn snap_to_grid(direction: Vec3, snap_grid: &SnapGrid, geodesic_grid: &GeodesicGrid) -> Vec3 {
+    match snap_grid.mode {
+        SnapMode::Off => direction,
+        SnapMode::Geodesic => grid::snap_to_vertices(direction, &geodesic_grid.geodesic.vertices),
+        SnapMode::LatLong => grid::snap_to_lat_long(direction, snap_grid.step_degrees.to_radians()),
+    }
+}
+
+//Builds the icosphere again when the subdivisions are changed
+pub fn update_geodesic_grid(
+    snap_grid: Res<SnapGrid>,
+    mut geodesic_grid: ResMut<GeodesicGrid>,
+) {
+    if snap_grid.mode == SnapMode::Geodesic && geodesic_grid.subdivisions != Some(snap_grid.subdivisions) {
+        geodesic_grid.geodesic = grid::geodesic_grid(snap_grid.subdivisions);
+        geodesic_grid.subdivisions = Some(snap_grid.subdivisions);
+    }
+}
+
+//Draws the grid balls snap to, a little over the globe
+pub fn draw_snap_grid(
+    mut gizmos: Gizmos,
+    snap_grid: Res<SnapGrid>,
+    geodesic_grid: Res<GeodesicGrid>,
+) {
+    let color = Color::rgba(1.0, 1.0, 1.0, 0.3);
+    match snap_grid.mode {
+        SnapMode::Off => {},
+        SnapMode::Geodesic => {
+            let vertices = &geodesic_grid.geodesic.vertices;
+            for (a, b) in geodesic_grid.geodesic.edges.iter() {
+                gizmos.line(vertices[*a] * SNAP_GRID_HEIGHT, vertices[*b] * SNAP_GRID_HEIGHT, color);
+            }
+        },
+        SnapMode::LatLong => {
+            let step = snap_grid.step_degrees.to_radians();
+            let lines = (std::f32::consts::PI / step).round() as i32;
+            for line in 0..lines {
+                //Parallels, without the poles
+                let latitude = -std::f32::consts::FRAC_PI_2 + step * line as f32;
+                if line > 0 {
+                    gizmos.circle(Vec3::Y * latitude.sin() * SNAP_GRID_HEIGHT, Direction3d::Y, latitude.cos() * SNAP_GRID_HEIGHT, color)
+                        .segments(SNAP_GRID_SEGMENTS);
+                }
+                //Meridians, each circle is two of them
+                if let Ok(normal) = Direction3d::new(grid::from_lat_long(0.0, step * line as f32 + std::f32::consts::FRAC_PI_2)) {
+                    gizmos.circle(Vec3::ZERO, normal, SNAP_GRID_HEIGHT, color).segments(SNAP_GRID_SEGMENTS);
+                }
+            }
+        },
+    }
+}
+
 //Use mouse to set speed and direction of ball
 //Draw speed marker as long as left mouse button is pressed down.
 pub fn edit_upsert_set_speed(
@@ -275,10 +399,9 @@ pub fn edit_upsert_set_speed(
 //Is active when in EditUpsertSetSpeed state and when left mouse button is just released
 pub fn finalize_upsert_ball_on_globe(
     mut commands: Commands,
-    ball_mesh_resource: Res<HandleForBallMesh>,
-    mut ball_material_resource: ResMut<ColorMaterialMap>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
     selected_color_resource: Res<SelectedColor>,
+    selected_body_resource: Res<SelectedBody>,
+    selected_appearance_resource: Res<SelectedAppearance>,
     cameras: Query<(&Camera, &GlobalTransform)>,
     rapier_context: Res<RapierContext>,
     mouse: Res<ButtonInput<MouseButton>>,
@@ -287,7 +410,7 @@ pub fn finalize_upsert_ball_on_globe(
     mut query_upsert_ball: Query<(Entity, &Transform, &Handle<StandardMaterial>, &BallUuid), With<Upserted>>,
     query_speed_marker: Query<(Entity, &CapsuleDepth, &CapsuleRotation), With<SpeedMarker>>,
     mut next_state: ResMut<NextState<AppState>>,
-    mut send_insert_ball_events: EventWriter<crate::query_server::SendInsertBallEvent>,
+    mut send_insert_ball_events: EventWriter<crate::query_server::SendBallChangeEvent>,
 ) {
     //if !mouse.just_released(MouseButton::Left) {
     //    return
@@ -344,9 +467,9 @@ pub fn finalize_upsert_ball_on_globe(
                     let ball_position = upsert_ball.1.translation;
                     
                     if capsule_depth.0 > 0.05{
-                        //spawn dynamic
-                        //despawn upsert ball
-                        commands.entity(upsert_ball.0).despawn();
+                        //Keep the upsert ball as a placeholder that does not collide. The moving ball is spawned
+                        //from the log at the step of its transaction, the same step on every client.
+                        commands.entity(upsert_ball.0).remove::<Upserted>().insert(ColliderDisabled);
 
                         //compute impulse
                         let forward_direction = capsule_rotation.0.mul_vec3(Vec3::Y).normalize();
@@ -354,26 +477,21 @@ pub fn finalize_upsert_ball_on_globe(
                         //let impulse = forward_direction * impulse_magnitude;
                         let impulse = forward_direction * impulse_magnitude;
 
-                        spawn_moving_ball(&mut commands, 
-                            &ball_mesh_resource,
-                            &mut ball_material_resource,
-                            &mut materials,
-                            selected_color_resource.0,
-                            (ball_position.x, ball_position.y, ball_position.z),
-                            impulse,
-                            Some(upsert_ball.3.0) );
-                        send_insert_ball_event(&mut send_insert_ball_events, upsert_ball.3.0, ball_position, Some(impulse), &selected_color_resource);
+                        send_insert_ball_event(&mut send_insert_ball_events, upsert_ball.3.0, ball_position, Some(impulse), &selected_color_resource, &selected_body_resource, &selected_appearance_resource);
+                        commands.insert_resource(ClickPlacement(Some((ball_position, Some(impulse)))));
                     }
                     else{
-                        //Remove Upsert component on ball. The ball is then permanent static.
-                        commands.entity(upsert_ball.0).remove::<Upserted>();
-                        send_insert_ball_event(&mut send_insert_ball_events, upsert_ball.3.0, ball_position, None, &selected_color_resource);
+                        //Remove Upsert component on ball. It stays a placeholder until its log entry is applied.
+                        commands.entity(upsert_ball.0).remove::<Upserted>().insert(ColliderDisabled);
+                        send_insert_ball_event(&mut send_insert_ball_events, upsert_ball.3.0, ball_position, None, &selected_color_resource, &selected_body_resource, &selected_appearance_resource);
+                        commands.insert_resource(ClickPlacement(Some((ball_position, None))));
                     }
                 }
                 else{
                     //Mouse did not hit globe so ball will be fixed.
-                    commands.entity(upsert_ball.0).remove::<Upserted>();
-                    send_insert_ball_event(&mut send_insert_ball_events, upsert_ball.3.0, upsert_ball.1.translation, None, &selected_color_resource);
+                    commands.entity(upsert_ball.0).remove::<Upserted>().insert(ColliderDisabled);
+                    send_insert_ball_event(&mut send_insert_ball_events, upsert_ball.3.0, upsert_ball.1.translation, None, &selected_color_resource, &selected_body_resource, &selected_appearance_resource);
+                    commands.insert_resource(ClickPlacement(Some((upsert_ball.1.translation, None))));
                 }
             }
         }
@@ -383,15 +501,70 @@ pub fn finalize_upsert_ball_on_globe(
 }
 
 
-pub fn edit_delete_ball(
+//Shows the symmetric copies of the ball being placed with a click that the server would reject
+pub fn preview_rejected_click_copies(
+    mut commands: Commands,
+    ball_mesh_resource: Res<HandleForBallMesh>,
+    rejected_material_resource: Res<HandleForRejectedCopyMaterial>,
+    selected_body_resource: Res<SelectedBody>,
+    symmetry: Res<Symmetry>,
+    query_fixed_balls: FixedBalls,
+    query_upsert_ball: Query<&Transform, Added<Upserted>>,
+) {
+    for transform in query_upsert_ball.iter() {
+        let position = transform.translation;
+        let (_, rejected_copies) = symmetric_copies(position, selected_body_resource.0.radius, &symmetry, &query_fixed_balls, &[]);
+        for copy in rejected_copies {
+            spawn_rejected_copy(&mut commands, &ball_mesh_resource, &rejected_material_resource, &selected_body_resource.0, copy * position);
+        }
+    }
+}
+
+//Places the symmetric copies of the ball just placed with a click, moving ones with the impulse mirrored or turned
+//the same way. They are sent in the same frame as the ball, so they all go to the server as one batch.
+pub fn place_symmetric_copies(
     mut commands: Commands,
+    ball_mesh_resource: Res<HandleForBallMesh>,
+    mut ball_material_resource: ResMut<ColorMaterialMap>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    selected_color_resource: Res<SelectedColor>,
+    selected_body_resource: Res<SelectedBody>,
+    selected_appearance_resource: Res<SelectedAppearance>,
+    symmetry: Res<Symmetry>,
+    query_fixed_balls: FixedBalls,
+    mut click_placement: ResMut<ClickPlacement>,
+    mut send_insert_ball_events: EventWriter<crate::query_server::SendBallChangeEvent>,
+) {
+    let Some((position, impulse)) = click_placement.0.take() else { return; };
+    let (copies, _) = symmetric_copies(position, selected_body_resource.0.radius, &symmetry, &query_fixed_balls, &[]);
+    for copy in copies {
+        let ball_uuid = Uuid::new_v4();
+        let copy_position = copy * position;
+        let copy_impulse = impulse.map(|impulse| copy * impulse);
+        //A placeholder like the placed ball, replaced when the log entry of the copy is applied
+        let entity_copy = spawn_static_ball(&mut commands,
+            &ball_mesh_resource,
+            &mut ball_material_resource,
+            &mut materials,
+            selected_color_resource.0,
+            &selected_body_resource.0,
+            &selected_appearance_resource.0,
+            (copy_position.x, copy_position.y, copy_position.z),
+            false,
+            Some(ball_uuid));
+        commands.entity(entity_copy).insert(ColliderDisabled);
+        send_insert_ball_event(&mut send_insert_ball_events, ball_uuid, copy_position, copy_impulse, &selected_color_resource, &selected_body_resource, &selected_appearance_resource);
+    }
+}
+
+pub fn edit_delete_ball(
     cameras: Query<(&Camera, &GlobalTransform)>,
     rapier_context: Res<RapierContext>,
     mouse: Res<ButtonInput<MouseButton>>,
     touches: Res<Touches>,
     windows: Query<&mut Window>,
     query_globe: Query<Entity, With<globe::Globe>>,
-    mut send_delete_ball_events: EventWriter<crate::query_server::SendDeleteBallEvent>,
+    mut send_delete_ball_events: EventWriter<crate::query_server::SendBallChangeEvent>,
     query_balls: Query<(Entity, &BallUuid)>,
 ) {
     // Check if the left mouse button was just pressed or if there is a touch input
@@ -431,12 +604,10 @@ pub fn edit_delete_ball(
             ){
                 let entity_globe = query_globe.single();
                 if entity_globe != entity {
-                    //Despawn if not globe, then it should be a ball
-                    commands.entity(entity).despawn();
-
+                    //Not despawned here, the log removes the ball at the step of the delete on every client
                     for (entity_ball, uuid_ball) in query_balls.iter() {
                         if entity == entity_ball {
-                            send_delete_ball_events.send(crate::query_server::SendDeleteBallEvent {uuid: uuid_ball.0});
+                            send_delete_ball_events.send(crate::query_server::SendBallChangeEvent::delete(uuid_ball.0));
                         }
                     }  
                 }
@@ -462,213 +633,1512 @@ pub fn handle_delete_state(
         }
     }
 }
-fn color_to_hex(color: Color) -> String {
-    let rgba = color.as_rgba_u8();
-    format!("#{:02X}{:02X}{:02X}{:02X}", rgba[0], rgba[1], rgba[2], rgba[3])
-}
 
-fn send_insert_ball_event(
-    send_insert_ball_events: &mut EventWriter<crate::query_server::SendInsertBallEvent>,
-    ball_uuid: Uuid,
-    ball_position: Vec3,
-    ball_impulse: Option<Vec3>,
-    selected_color_resource: &Res<SelectedColor>,
+//First click picks the ball the link starts at, second click the ball it goes to
+pub fn edit_link_ball(
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    rapier_context: Res<RapierContext>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    windows: Query<&mut Window>,
+    query_balls: Query<&BallUuid, Without<Upserted>>,
+    mut link_start: ResMut<LinkStart>,
+    selected_color_resource: Res<SelectedColor>,
+    mut send_insert_ball_events: EventWriter<crate::query_server::SendBallChangeEvent>,
 ) {
-    // is_fixed is true if ball_impulse is None, false otherwise
-    let is_fixed = ball_impulse.is_none();
-    send_insert_ball_events.send(crate::query_server::SendInsertBallEvent {
-        ball: BallDto {
-            is_fixed,
-            is_insert: true,
-            uuid: ball_uuid,
-            //color: Some("#ff0000".to_string()),
-            color: Some(color_to_hex(selected_color_resource.0)),
-            position: Some(PositionDto {
-                x: ball_position.x,
-                y: ball_position.y,
-                z: ball_position.z,
-            }),
-            impulse: ball_impulse.map(|impulse| ImpulseDto { // Use map to convert Option<Vec3> to Option<ImpulseDto>
-                x: impulse.x,
-                y: impulse.y,
-                z: impulse.z,
-            }),
+    // Check if the left mouse button was just pressed or if there is a touch input
+    if !mouse.just_pressed(MouseButton::Left) && touches.iter_just_pressed().next().is_none() {
+        return;
+    }
+
+    // Determine input position from either mouse or touch
+    let input_position = if mouse.just_pressed(MouseButton::Left) {
+        windows.get_single().ok().and_then(|window| window.cursor_position())
+    } else {
+        touches.iter_just_pressed().next().map(|touch| touch.position())
+    };
+
+    let Some(cursor_position) = input_position else { return; };
+    for (camera, camera_transform) in &cameras {
+        let Some(ray) = camera.viewport_to_world(camera_transform, cursor_position) else { return; };
+        //Hit balls and the globe, so balls behind the globe are not picked
+        let filter = QueryFilter {
+            groups: Some(
+                CollisionGroups {
+                    memberships: Group::GROUP_2,
+                    filters: (Group::GROUP_1 | Group::GROUP_2)
+                }
+            ),
+            ..default()
+        };
+
+        let Some((entity, _)) = rapier_context.cast_ray(ray.origin, *ray.direction, f32::MAX, true, filter) else { continue; };
+        //Clicking the globe starts over
+        let Ok(uuid_ball) = query_balls.get(entity) else {
+            link_start.0 = None;
+            continue;
+        };
+
+        match link_start.0.take() {
+            None => link_start.0 = Some(uuid_ball.0),
+            Some(from_uuid) if from_uuid == uuid_ball.0 => {},
+            Some(from_uuid) => {
+                send_insert_ball_events.send(crate::query_server::SendBallChangeEvent {
+                    ball: BallDto {
+                        is_fixed: true,
+                        is_insert: true,
+                        uuid: Uuid::new_v4(),
+                        color: Some(color_to_hex(selected_color_resource.0)),
+                        position: None,
+                        impulse: None,
+                        created_at: None,
+                        link: Some(LinkDto {
+                            from_uuid,
+                            to_uuid: uuid_ball.0,
+                        }),
+                        body: None,
+                        annotation: None,
+                        appearance: None,
+                        geo_position: None,
+                        physics_profile: None,
+                    }
+                });
+            },
         }
-    });
+    }
 }
 
-pub fn receive_ball_transactions_event_listener(
-    mut commands: Commands, 
-    mut events: EventReader<crate::query_server::ReceivedTransactionsEvent>, 
-    ball_mesh_resource: Res<HandleForBallMesh>,
-    mut ball_material_resource: ResMut<ColorMaterialMap>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    query_balls: Query<(Entity, &BallUuid, &Transform)>,
-    mut last_received_transaction: ResMut<LastReceivedTransaction>,
-    mut send_transactions_request_event: EventWriter<SendTransactionsRequestEvent>,
+//Keeps the annotation texts over their anchors, and hides the ones on the far side of the globe
+pub fn position_annotations(
+    cameras: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    mut query_annotations: Query<(&Annotation, &Node, &mut Style, &mut Visibility)>,
 ) {
-    for event in events.read() {
-        if !event.ball_transactions.is_empty() {
-            if let Some(last_element) = event.ball_transactions.last() {
-                last_received_transaction.0 = last_element.transaction_id.to_string();
-                // Send a new request immediately
-                send_transactions_request_event.send(SendTransactionsRequestEvent);
-            }
+    let Ok((camera, camera_transform)) = cameras.get_single() else { return; };
+    let camera_position = camera_transform.translation();
+
+    for (annotation, node, mut style, mut visibility) in query_annotations.iter_mut() {
+        let is_facing_camera = annotation.anchor.dot(camera_position - annotation.anchor) > 0.0;
+        match camera.world_to_viewport(camera_transform, annotation.anchor) {
+            Some(screen_position) if is_facing_camera => {
+                //The text stands on the anchor
+                style.left = Val::Px(screen_position.x - node.size().x / 2.0);
+                style.top = Val::Px(screen_position.y - node.size().y);
+                *visibility = Visibility::Inherited;
+            },
+            _ => *visibility = Visibility::Hidden,
         }
+    }
+}
+
+//Click the globe to place the text, then type it. Enter sends it, shift+enter is a new line,
+//arrow up and down change the size and escape throws it away.
+pub fn edit_annotation_on_globe(
+    mut commands: Commands,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    rapier_context: Res<RapierContext>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    windows: Query<&Window>,
+    annotation_font_resource: Res<HandleForAnnotationFont>,
+    selected_color_resource: Res<SelectedColor>,
+    mut query_draft: Query<&mut Annotation, With<AnnotationDraft>>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) && touches.iter_just_pressed().next().is_none() {
+        return;
+    }
 
-        let mut balls_to_insert = HashSet::new();
-        let mut balls_to_delete = HashSet::new();
+    let input_position = if mouse.just_pressed(MouseButton::Left) {
+        windows.get_single().ok().and_then(|window| window.cursor_position())
+    } else {
+        touches.iter_just_pressed().next().map(|touch| touch.position())
+    };
 
-        // First pass: Determine which balls to insert and delete
-        for ball_transaction in &event.ball_transactions {
-            let uuid = ball_transaction.ball_dto.uuid;
-            if ball_transaction.ball_dto.is_insert {
-                balls_to_insert.insert(uuid);
-            } else {
-                balls_to_insert.remove(&uuid);
-                balls_to_delete.insert(uuid);
-            }
+    let Some(cursor_position) = input_position else { return; };
+    for (camera, camera_transform) in &cameras {
+        let Some(ray) = camera.viewport_to_world(camera_transform, cursor_position) else { continue; };
+        //Only hit globe, globe is only member of CollisionGroup GROUP_1
+        let filter = QueryFilter {
+            groups: Some(
+                CollisionGroups {
+                    memberships: Group::GROUP_2,
+                    filters: Group::GROUP_1,
+                }
+            ),
+            ..default()
+        };
+        let Some((_, toi)) = rapier_context.cast_ray(ray.origin, *ray.direction, f32::MAX, true, filter) else { continue; };
+
+        //A little over the surface, within what the server accepts
+        let anchor = (ray.origin + ray.direction * toi).normalize() * (1.0 + MAX_ANCHOR_HEIGHT / 2.0);
+        if let Ok(mut draft_annotation) = query_draft.get_single_mut() {
+            draft_annotation.anchor = anchor;
+        } else {
+            let entity_draft = spawn_annotation(
+                &mut commands,
+                &annotation_font_resource,
+                anchor,
+                "|",
+                DEFAULT_FONT_SIZE,
+                selected_color_resource.0,
+            );
+            commands.entity(entity_draft).insert(AnnotationDraft {
+                text: String::new(),
+                font_size: DEFAULT_FONT_SIZE,
+            });
         }
+    }
+}
 
-        // Second pass: Handle insertions
-        let mut inserted_positions: Vec<Vec3> = Vec::new();  // Track positions of inserted balls in this event
-        let min_distance = 0.1; // Set your minimum distance
-
-        for uuid in balls_to_insert {
-            // Check if a ball with this UUID already exists
-            let ball_already_exists = query_balls.iter().any(|(_, uuid_ball, _)| uuid_ball.0 == uuid);
-
-            if !ball_already_exists {
-                if let Some(ball_transaction) = event.ball_transactions.iter().find(|bt| bt.ball_dto.uuid == uuid) {
-                    let is_moving_ball = !ball_transaction.ball_dto.is_fixed;
-
-                    let temp_position = match &ball_transaction.ball_dto.position {
-                        Some(pos) => {
-                            let mut temp_pos = Vec3::new(pos.x, pos.y, pos.z);
-                            if is_moving_ball {
-                                while query_balls.iter().any(|(_, _, transform)| transform.translation.distance(temp_pos) < min_distance)
-                                    || inserted_positions.iter().any(|&existing_pos| existing_pos.distance(temp_pos) < min_distance) {
-                                        temp_pos = generate_valid_position(&query_balls, &inserted_positions, min_distance);
-                                }
-                            }
-                            temp_pos
-                        },
-                        None => {
-                            bevy::log::error!("Received ball transaction without position. UUID: {}", uuid);
-                            continue; // Skip this transaction as it has no position
-                        }
-                    };
-
-                    // Create new ball transaction with updated position
-                    let mut new_ball_transaction = ball_transaction.clone();
-                    new_ball_transaction.ball_dto.position = Some(PositionDto {
-                        x: temp_position.x,
-                        y: temp_position.y,
-                        z: temp_position.z,
-                    });
+pub fn type_annotation_text(
+    mut commands: Commands,
+    mut received_characters: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    selected_color_resource: Res<SelectedColor>,
+    mut query_draft: Query<(Entity, &Annotation, &mut AnnotationDraft, &mut Text)>,
+    mut send_insert_ball_events: EventWriter<crate::query_server::SendBallChangeEvent>,
+) {
+    let Ok((entity_draft, annotation, mut draft, mut text)) = query_draft.get_single_mut() else {
+        received_characters.clear();
+        return;
+    };
 
-                    handle_insert_ball_transaction(
-                        &mut commands,
-                        &ball_mesh_resource,
-                        &mut ball_material_resource,
-                        &mut materials,
-                        //&selected_color_resource,
-                        &new_ball_transaction,
-                    );
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        commands.entity(entity_draft).despawn();
+        return;
+    }
+    let is_shift_pressed = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if keyboard_input.just_pressed(KeyCode::Enter) && !is_shift_pressed {
+        received_characters.clear();
+        if draft.text.trim().is_empty() {
+            return;
+        }
+        send_insert_ball_events.send(crate::query_server::SendBallChangeEvent {
+            ball: BallDto {
+                is_fixed: true,
+                is_insert: true,
+                uuid: Uuid::new_v4(),
+                color: Some(color_to_hex(selected_color_resource.0)),
+                position: Some(PositionDto {
+                    x: annotation.anchor.x,
+                    y: annotation.anchor.y,
+                    z: annotation.anchor.z,
+                }),
+                impulse: None,
+                created_at: None,
+                link: None,
+                body: None,
+                annotation: Some(AnnotationDto {
+                    text: draft.text.clone(),
+                    font_size: draft.font_size,
+                }),
+                appearance: None,
+                geo_position: None,
+                physics_profile: None,
+            }
+        });
+        //The annotation is shown when it comes back in the log
+        commands.entity(entity_draft).despawn();
+        return;
+    }
 
-                    // Add the position to the set of inserted positions
-                    inserted_positions.push(temp_position);
-                }
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        draft.text.push('\n');
+    }
+    if keyboard_input.just_pressed(KeyCode::Backspace) {
+        draft.text.pop();
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        draft.font_size = (draft.font_size + 2.0).min(MAX_FONT_SIZE);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        draft.font_size = (draft.font_size - 2.0).max(MIN_FONT_SIZE);
+    }
+    for received_character in received_characters.read() {
+        for character in received_character.char.chars().filter(|character| !character.is_control()) {
+            if draft.text.chars().count() < MAX_ANNOTATION_LENGTH {
+                draft.text.push(character);
             }
         }
+    }
 
-        // Handle deletions
-        for uuid in balls_to_delete {
-            if let Some((entity_ball, _, _)) = query_balls.iter().find(|(_, uuid_ball, _)| uuid_ball.0 == uuid) {
-                commands.entity(entity_ball).despawn();
-            }
+    if draft.is_changed() {
+        text.sections[0].value = format!("{}|", draft.text);
+        text.sections[0].style.font_size = draft.font_size;
+        text.sections[0].style.color = selected_color_resource.0;
+    }
+}
+
+pub fn handle_annotation_state(
+    mut commands: Commands,
+    current_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    selected_annotation: Res<SelectedAnnotation>,
+    query_draft: Query<Entity, With<AnnotationDraft>>,
+) {
+    if selected_annotation.0 {
+        if *current_state == AppState::EditUpsert {
+            next_state.set(AppState::EditAnnotation);
+        }
+    } else if *current_state == AppState::EditAnnotation {
+        for entity_draft in query_draft.iter() {
+            commands.entity(entity_draft).despawn();
         }
+        next_state.set(AppState::EditUpsert);
     }
 }
 
-fn handle_insert_ball_transaction(
-    commands: &mut Commands,
-    ball_mesh_resource: &Res<HandleForBallMesh>,
-    ball_material_resource: &mut ResMut<ColorMaterialMap>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
-    //selected_color_resource: &Res<SelectedColor>,
-    ball_transaction: &BallTransactionDto,
+//Annotations are deleted by clicking the text
+pub fn edit_delete_annotation(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    windows: Query<&Window>,
+    query_annotations: Query<(Entity, &BallUuid, &Node, &GlobalTransform, &InheritedVisibility), With<Annotation>>,
+    mut send_delete_ball_events: EventWriter<crate::query_server::SendBallChangeEvent>,
 ) {
-    let position = match &ball_transaction.ball_dto.position {
-        Some(pos) => pos,
-        None => {
-            // Log error or handle the case of missing position
-            bevy::log::error!("Missing position! Not good.");
-            return;
-        },
-    };
+    if !mouse.just_pressed(MouseButton::Left) && touches.iter_just_pressed().next().is_none() {
+        return;
+    }
 
-    let color = if let Some(hex_color) = &ball_transaction.ball_dto.color {
-        Color::hex(&hex_color).unwrap()
+    let input_position = if mouse.just_pressed(MouseButton::Left) {
+        windows.get_single().ok().and_then(|window| window.cursor_position())
     } else {
-        Color::WHITE // Default color if None
+        touches.iter_just_pressed().next().map(|touch| touch.position())
     };
 
-    if ball_transaction.ball_dto.is_fixed {
-        spawn_static_ball(
-            commands, 
-            ball_mesh_resource,
-            ball_material_resource,
-            materials,
-            color,
-            (position.x, position.y, position.z),
-            false,
-            Some(ball_transaction.ball_dto.uuid)
-        );
-    } else {
-        let impulse = match &ball_transaction.ball_dto.impulse {
-            Some(imp) => imp,
-            None => {
-                bevy::log::error!("Missing impulse! Not good on a moving ball.");
-                return;
-            },
-        };
-
-        spawn_moving_ball(
-            commands, 
-            ball_mesh_resource,
-            ball_material_resource,
-            materials,
-            color,
-            (position.x, position.y, position.z),
-            Vec3::new(impulse.x, impulse.y, impulse.z),
-            Some(ball_transaction.ball_dto.uuid)
-        );
+    let Some(cursor_position) = input_position else { return; };
+    for (entity_annotation, uuid_annotation, node, global_transform, visibility) in query_annotations.iter() {
+        let node_rect = Rect::from_center_size(global_transform.translation().truncate(), node.size());
+        if visibility.get() && node_rect.contains(cursor_position) {
+            commands.entity(entity_annotation).despawn();
+            send_delete_ball_events.send(crate::query_server::SendBallChangeEvent::delete(uuid_annotation.0));
+        }
     }
 }
 
+pub fn handle_link_state(
+    current_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    selected_link: Res<SelectedLink>,
+    mut link_start: ResMut<LinkStart>,
+) {
+    if selected_link.0 {
+        if *current_state == AppState::EditUpsert {
+            next_state.set(AppState::EditLink);
+        }
+    } else if *current_state == AppState::EditLink {
+        link_start.0 = None;
+        next_state.set(AppState::EditUpsert);
+    }
+}
 
+pub fn color_to_hex(color: Color) -> String {
+    let rgba = color.as_rgba_u8();
+    format!("#{:02X}{:02X}{:02X}{:02X}", rgba[0], rgba[1], rgba[2], rgba[3])
+}
 
-fn generate_valid_position(
-    query_balls: &Query<(Entity, &BallUuid, &Transform)>,
-    inserted_positions: &[Vec3],
-    min_distance: f32,
-) -> Vec3 {
-    //bevy::log::info!("generate_valid_position START.");
-    let mut rng = rand::thread_rng();
-    loop {
-        //bevy::log::info!("generate_valid_position loop start.");
-        let new_position = Vec3::new(
-            rng.gen_range(-1.0..1.0),
-            rng.gen_range(-1.0..1.0),
-            rng.gen_range(-1.0..1.0),
-        ).normalize(); // Ensure the point is on the surface of the globe
+pub fn handle_brush_state(
+    mut commands: Commands,
+    current_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    selected_brush: Res<SelectedBrush>,
+    mut brush_stroke: ResMut<BrushStroke>,
+    query_previews: PreviewBalls,
+) {
+    if selected_brush.0 {
+        if *current_state == AppState::EditUpsert {
+            next_state.set(AppState::EditBrush);
+        }
+    } else if *current_state == AppState::EditBrush {
+        despawn_previews(&mut commands, &query_previews, &brush_stroke.balls);
+        *brush_stroke = BrushStroke::default();
+        next_state.set(AppState::EditUpsert);
+    }
+}
 
-        let too_close = query_balls.iter().any(|(_, _, transform)| transform.translation.distance(new_position) < min_distance)
-                          || inserted_positions.iter().any(|&pos| pos.distance(new_position) < min_distance);
+//While the pointer is held down on the globe, previews a ball each time it has moved the spacing from the last one.
+//Balls that would be too close to a fixed ball or to the stroke are left out, as the server would reject them.
+pub fn paint_brush_stroke(
+    mut commands: Commands,
+    ball_mesh_resource: Res<HandleForBallMesh>,
+    mut ball_material_resource: ResMut<ColorMaterialMap>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    selected_color_resource: Res<SelectedColor>,
+    selected_body_resource: Res<SelectedBody>,
+    selected_appearance_resource: Res<SelectedAppearance>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    rapier_context: Res<RapierContext>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    windows: Query<&Window>,
+    query_fixed_balls: FixedBalls,
+    mut brush_stroke: ResMut<BrushStroke>,
+    symmetry: Res<Symmetry>,
+    rejected_material_resource: Res<HandleForRejectedCopyMaterial>,
+) {
+    let input_position = if mouse.pressed(MouseButton::Left) {
+        windows.get_single().ok().and_then(|window| window.cursor_position())
+    } else {
+        touches.iter().next().map(|touch| touch.position())
+    };
 
-        if !too_close {
-            return new_position;
+    let Some(cursor_position) = input_position else { return; };
+    let Some(target) = pointer_on_globe(&cameras, &rapier_context, cursor_position) else { return; };
+
+    let radius = selected_body_resource.0.radius;
+    let height = resting_height(radius);
+    let spacing = ball_spacing(radius);
+    let step_angle = curve::step_angle(spacing, height);
+
+    let mut candidates = Vec::new();
+    match brush_stroke.last_point {
+        None => candidates.push(target),
+        Some(last_point) => {
+            //Fill in along the arc when the pointer moved more than one spacing since the last sample
+            let mut from = last_point;
+            while from.angle_between(target) >= step_angle {
+                let axis = from.cross(target);
+                if axis.length_squared() < f32::EPSILON {
+                    break;
+                }
+                from = (Quat::from_axis_angle(axis.normalize(), step_angle) * from).normalize();
+                candidates.push(from);
+            }
         }
     }
-}
\ No newline at end of file
+
+    for direction in candidates {
+        brush_stroke.last_point = Some(direction);
+        let position = direction * height;
+        let stroke_positions: Vec<Vec3> = brush_stroke.balls.iter().map(|(_, stroke_position)| *stroke_position).collect();
+        let too_close_to_stroke = stroke_positions.iter()
+            .any(|stroke_position| stroke_position.distance(position) < spacing);
+        if too_close_to_stroke || !is_clear_of_fixed_balls(position, radius, &query_fixed_balls) {
+            continue;
+        }
+
+        let (copies, rejected_copies) = symmetric_copies(position, radius, &symmetry, &query_fixed_balls, &stroke_positions);
+        //The ball and its copies have to fit in the batch
+        if brush_stroke.balls.len() + 1 + copies.len() > MAX_BATCH_SIZE {
+            return;
+        }
+        for ball_position in std::iter::once(position).chain(copies.iter().map(|copy| *copy * position)) {
+            let ball_uuid = Uuid::new_v4();
+            spawn_static_ball(&mut commands,
+                &ball_mesh_resource,
+                &mut ball_material_resource,
+                &mut materials,
+                selected_color_resource.0,
+                &selected_body_resource.0,
+                &selected_appearance_resource.0,
+                (ball_position.x, ball_position.y, ball_position.z),
+                true,
+                Some(ball_uuid)
+            );
+            brush_stroke.balls.push((ball_uuid, ball_position));
+        }
+        for copy in rejected_copies {
+            spawn_rejected_copy(&mut commands, &ball_mesh_resource, &rejected_material_resource, &selected_body_resource.0, copy * position);
+        }
+    }
+}
+
+//Point on the globe under the pointer, as a direction from the center
+fn pointer_on_globe(
+    cameras: &Query<(&Camera, &GlobalTransform)>,
+    rapier_context: &RapierContext,
+    cursor_position: Vec2,
+) -> Option<Vec3> {
+    for (camera, camera_transform) in cameras {
+        let Some(ray) = camera.viewport_to_world(camera_transform, cursor_position) else { continue; };
+        //Only hit globe, globe is only member of CollisionGroup GROUP_1
+        let filter = QueryFilter {
+            groups: Some(
+                CollisionGroups {
+                    memberships: Group::GROUP_2,
+                    filters: Group::GROUP_1,
+                }
+            ),
+            ..default()
+        };
+        if let Some((_, toi)) = rapier_context.cast_ray(ray.origin, *ray.direction, f32::MAX, true, filter) {
+            return Some((ray.origin + ray.direction * toi).normalize());
+        }
+    }
+    None
+}
+
+//Distance from the center of a ball resting on the globe, like a ball placed with a click
+fn resting_height(radius: f32) -> f32 {
+    1.0 + radius
+}
+
+//Closest two balls of this radius are placed to each other
+fn ball_spacing(radius: f32) -> f32 {
+    2.0 * radius * SPACING_MARGIN
+}
+
+//The server rejects a ball closer to a fixed ball than their radiuses together
+fn is_clear_of_fixed_balls(
+    position: Vec3,
+    radius: f32,
+    query_fixed_balls: &FixedBalls,
+) -> bool {
+    query_fixed_balls.iter()
+        .all(|transform| transform.translation.distance(position) >= (radius + transform.scale.x) * SPACING_MARGIN)
+}
+
+//Maps to the symmetric copies of a ball placed at the position, and maps to the copies the server would reject.
+//A copy is rejected when it is too close to a fixed ball, to the ball, to a ball placed before or to an earlier copy.
+fn symmetric_copies(
+    position: Vec3,
+    radius: f32,
+    symmetry: &Symmetry,
+    query_fixed_balls: &FixedBalls,
+    placed: &[Vec3],
+) -> (Vec<Mat3>, Vec<Mat3>) {
+    let spacing = ball_spacing(radius);
+    let mut copies = Vec::new();
+    let mut rejected_copies = Vec::new();
+    let mut copy_positions = vec![position];
+    for copy in symmetry.copies() {
+        let copy_position = copy * position;
+        let too_close = placed.iter().chain(copy_positions.iter())
+            .any(|other_position| other_position.distance(copy_position) < spacing);
+        if too_close || !is_clear_of_fixed_balls(copy_position, radius, query_fixed_balls) {
+            rejected_copies.push(copy);
+        } else {
+            copies.push(copy);
+            copy_positions.push(copy_position);
+        }
+    }
+    (copies, rejected_copies)
+}
+
+pub fn despawn_rejected_copies(
+    mut commands: Commands,
+    query_rejected: Query<Entity, With<RejectedCopy>>,
+) {
+    clear_rejected_copies(&mut commands, &query_rejected);
+}
+
+fn clear_rejected_copies(
+    commands: &mut Commands,
+    query_rejected: &Query<Entity, With<RejectedCopy>>,
+) {
+    for entity_rejected in query_rejected.iter() {
+        commands.entity(entity_rejected).despawn();
+    }
+}
+
+//When the pointer is released the previewed balls are kept and sent. They are sent in the same frame,
+//so they go to the server as one batch. Escape drops the stroke.
+pub fn finish_brush_stroke(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    selected_color_resource: Res<SelectedColor>,
+    selected_body_resource: Res<SelectedBody>,
+    selected_appearance_resource: Res<SelectedAppearance>,
+    mut brush_stroke: ResMut<BrushStroke>,
+    query_previews: PreviewBalls,
+    query_rejected: Query<Entity, With<RejectedCopy>>,
+    mut send_insert_ball_events: EventWriter<crate::query_server::SendBallChangeEvent>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        despawn_previews(&mut commands, &query_previews, &brush_stroke.balls);
+        clear_rejected_copies(&mut commands, &query_rejected);
+        *brush_stroke = BrushStroke::default();
+        return;
+    }
+    if !mouse.just_released(MouseButton::Left) && touches.iter_just_released().next().is_none() {
+        return;
+    }
+
+    send_previews(
+        &mut commands,
+        &query_previews,
+        &brush_stroke.balls,
+        &mut send_insert_ball_events,
+        &selected_color_resource,
+        &selected_body_resource,
+        &selected_appearance_resource,
+    );
+    *brush_stroke = BrushStroke::default();
+    clear_rejected_copies(&mut commands, &query_rejected);
+}
+
+fn despawn_previews(
+    commands: &mut Commands,
+    query_previews: &PreviewBalls,
+    balls: &[(Uuid, Vec3)],
+) {
+    for (entity_preview, uuid_preview) in query_previews.iter() {
+        if balls.iter().any(|(uuid, _)| *uuid == uuid_preview.0) {
+            commands.entity(entity_preview).despawn_recursive();
+        }
+    }
+}
+
+//Keeps the previewed balls and sends them. They are all sent in the same frame, so they go to the server as one batch.
+fn send_previews(
+    commands: &mut Commands,
+    query_previews: &PreviewBalls,
+    balls: &[(Uuid, Vec3)],
+    send_insert_ball_events: &mut EventWriter<crate::query_server::SendBallChangeEvent>,
+    selected_color_resource: &Res<SelectedColor>,
+    selected_body_resource: &Res<SelectedBody>,
+    selected_appearance_resource: &Res<SelectedAppearance>,
+) {
+    let positions: HashMap<Uuid, Vec3> = balls.iter().copied().collect();
+    for (entity_preview, uuid_preview) in query_previews.iter() {
+        let Some(position) = positions.get(&uuid_preview.0) else { continue; };
+        commands.entity(entity_preview).remove::<Upserted>().insert(ColliderDisabled);
+        send_insert_ball_event(
+            send_insert_ball_events,
+            uuid_preview.0,
+            *position,
+            None,
+            selected_color_resource,
+            selected_body_resource,
+            selected_appearance_resource,
+        );
+    }
+}
+
+pub fn handle_curve_state(
+    mut commands: Commands,
+    current_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    selected_curve: Res<SelectedCurve>,
+    mut curve_draft: ResMut<CurveDraft>,
+    query_previews: PreviewBalls,
+) {
+    if selected_curve.0.is_some() {
+        if *current_state == AppState::EditUpsert {
+            next_state.set(AppState::EditCurve);
+        }
+    } else if *current_state == AppState::EditCurve {
+        despawn_previews(&mut commands, &query_previews, &curve_draft.balls);
+        *curve_draft = CurveDraft::default();
+        next_state.set(AppState::EditUpsert);
+    }
+}
+
+//Each click picks a point of the line or circle. Once all points are picked, a click or Enter places the
+//previewed balls and Escape drops them.
+pub fn pick_curve_points(
+    mut commands: Commands,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    rapier_context: Res<RapierContext>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window>,
+    selected_curve: Res<SelectedCurve>,
+    selected_color_resource: Res<SelectedColor>,
+    selected_body_resource: Res<SelectedBody>,
+    selected_appearance_resource: Res<SelectedAppearance>,
+    mut curve_draft: ResMut<CurveDraft>,
+    query_previews: PreviewBalls,
+    query_rejected: Query<Entity, With<RejectedCopy>>,
+    mut send_insert_ball_events: EventWriter<crate::query_server::SendBallChangeEvent>,
+) {
+    let Some(tool) = selected_curve.0 else { return; };
+    //Switching between line and circle starts over
+    if keyboard_input.just_pressed(KeyCode::Escape) || curve_draft.tool != Some(tool) {
+        despawn_previews(&mut commands, &query_previews, &curve_draft.balls);
+        clear_rejected_copies(&mut commands, &query_rejected);
+        *curve_draft = CurveDraft { tool: Some(tool), ..default() };
+        return;
+    }
+
+    let is_complete = curve_draft.points.len() >= tool.points_needed();
+    let pressed = mouse.just_pressed(MouseButton::Left) || touches.iter_just_pressed().next().is_some();
+    if is_complete && (pressed || keyboard_input.just_pressed(KeyCode::Enter)) {
+        send_previews(
+            &mut commands,
+            &query_previews,
+            &curve_draft.balls,
+            &mut send_insert_ball_events,
+            &selected_color_resource,
+            &selected_body_resource,
+            &selected_appearance_resource,
+        );
+        clear_rejected_copies(&mut commands, &query_rejected);
+        *curve_draft = CurveDraft { tool: Some(tool), ..default() };
+        return;
+    }
+    if !pressed {
+        return;
+    }
+
+    let input_position = if mouse.just_pressed(MouseButton::Left) {
+        windows.get_single().ok().and_then(|window| window.cursor_position())
+    } else {
+        touches.iter_just_pressed().next().map(|touch| touch.position())
+    };
+    let Some(cursor_position) = input_position else { return; };
+    if let Some(point) = pointer_on_globe(&cameras, &rapier_context, cursor_position) {
+        curve_draft.points.push(point);
+    }
+}
+
+//Previews the balls of the line or circle, following the pointer for the last point until it is picked
+pub fn preview_curve(
+    mut commands: Commands,
+    ball_mesh_resource: Res<HandleForBallMesh>,
+    mut ball_material_resource: ResMut<ColorMaterialMap>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    selected_color_resource: Res<SelectedColor>,
+    selected_body_resource: Res<SelectedBody>,
+    selected_appearance_resource: Res<SelectedAppearance>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    rapier_context: Res<RapierContext>,
+    windows: Query<&Window>,
+    query_fixed_balls: FixedBalls,
+    query_previews: PreviewBalls,
+    mut curve_draft: ResMut<CurveDraft>,
+    symmetry: Res<Symmetry>,
+    rejected_material_resource: Res<HandleForRejectedCopyMaterial>,
+    query_rejected: Query<Entity, With<RejectedCopy>>,
+) {
+    let Some(tool) = curve_draft.tool else { return; };
+    let mut guide_points = curve_draft.points.clone();
+    if guide_points.len() < tool.points_needed() {
+        let hover_point = windows.get_single().ok()
+            .and_then(|window| window.cursor_position())
+            .and_then(|cursor_position| pointer_on_globe(&cameras, &rapier_context, cursor_position));
+        guide_points.extend(hover_point);
+    }
+
+    let radius = selected_body_resource.0.radius;
+    let height = resting_height(radius);
+    let spacing = ball_spacing(radius);
+    let directions = match (tool, guide_points.as_slice()) {
+        (CurveTool::Line, [from, to, ..]) => curve::great_circle_arc(*from, *to, spacing, height),
+        (CurveTool::Circle, [a, b, c, ..]) => curve::circle_through(*a, *b, *c, spacing, height).unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    //A ball of the curve too close to a copy of an earlier one is shown as rejected too
+    let mut positions: Vec<Vec3> = Vec::new();
+    let mut rejected_positions: Vec<Vec3> = Vec::new();
+    for position in directions.into_iter().map(|direction| direction * height) {
+        if !is_clear_of_fixed_balls(position, radius, &query_fixed_balls) {
+            continue;
+        }
+        if positions.iter().any(|placed_position| placed_position.distance(position) < spacing) {
+            rejected_positions.push(position);
+            continue;
+        }
+        let (copies, rejected_copies) = symmetric_copies(position, radius, &symmetry, &query_fixed_balls, &positions);
+        positions.push(position);
+        positions.extend(copies.iter().map(|copy| *copy * position));
+        rejected_positions.extend(rejected_copies.iter().map(|copy| *copy * position));
+    }
+    positions.truncate(MAX_BATCH_SIZE);
+
+    let previewed: Vec<Vec3> = curve_draft.balls.iter().map(|(_, position)| *position).collect();
+    if positions == previewed && rejected_positions == curve_draft.rejected {
+        return;
+    }
+    despawn_previews(&mut commands, &query_previews, &curve_draft.balls);
+    clear_rejected_copies(&mut commands, &query_rejected);
+    for rejected_position in rejected_positions.iter() {
+        spawn_rejected_copy(&mut commands, &ball_mesh_resource, &rejected_material_resource, &selected_body_resource.0, *rejected_position);
+    }
+    curve_draft.rejected = rejected_positions;
+    curve_draft.balls.clear();
+    for position in positions {
+        let ball_uuid = Uuid::new_v4();
+        spawn_static_ball(&mut commands,
+            &ball_mesh_resource,
+            &mut ball_material_resource,
+            &mut materials,
+            selected_color_resource.0,
+            &selected_body_resource.0,
+            &selected_appearance_resource.0,
+            (position.x, position.y, position.z),
+            true,
+            Some(ball_uuid)
+        );
+        curve_draft.balls.push((ball_uuid, position));
+    }
+}
+
+pub fn handle_area_state(
+    current_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    selected_area: Res<SelectedArea>,
+    mut area_selection: ResMut<AreaSelection>,
+) {
+    if selected_area.0.is_some() {
+        if *current_state == AppState::EditUpsert {
+            next_state.set(AppState::EditArea);
+        }
+    } else if *current_state == AppState::EditArea {
+        *area_selection = AreaSelection::default();
+        next_state.set(AppState::EditUpsert);
+    }
+}
+
+//Dragging on the globe draws the area. The cap eraser grows a cap from where the drag started,
+//the lasso follows the pointer. The balls in the area are selected while it is drawn.
+pub fn draw_area_selection(
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    rapier_context: Res<RapierContext>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window>,
+    selected_area: Res<SelectedArea>,
+    mut area_selection: ResMut<AreaSelection>,
+    query_balls: Query<(&BallUuid, &Transform), (Or<(With<StaticBall>, With<MovingBall>)>, Without<Upserted>)>,
+    query_links: Query<(&BallUuid, &Link)>,
+    query_buttons: Query<&Interaction, With<Button>>,
+) {
+    let Some(tool) = selected_area.0 else { return; };
+    //Switching between cap and lasso starts over
+    if keyboard_input.just_pressed(KeyCode::Escape) || area_selection.tool != Some(tool) {
+        *area_selection = AreaSelection { tool: Some(tool), ..default() };
+        return;
+    }
+
+    let just_pressed = mouse.just_pressed(MouseButton::Left) || touches.iter_just_pressed().next().is_some();
+    if just_pressed {
+        //Pressing a button, like the one that deletes the selection, does not start a new area
+        if query_buttons.iter().any(|interaction| *interaction != Interaction::None) {
+            return;
+        }
+        *area_selection = AreaSelection { tool: Some(tool), is_drawing: true, ..default() };
+    }
+    if !area_selection.is_drawing {
+        return;
+    }
+    if mouse.just_released(MouseButton::Left) || touches.iter_just_released().next().is_some() {
+        area_selection.is_drawing = false;
+        return;
+    }
+
+    let input_position = if mouse.pressed(MouseButton::Left) {
+        windows.get_single().ok().and_then(|window| window.cursor_position())
+    } else {
+        touches.iter().next().map(|touch| touch.position())
+    };
+    let Some(cursor_position) = input_position else { return; };
+    let Some(point) = pointer_on_globe(&cameras, &rapier_context, cursor_position) else { return; };
+
+    match tool {
+        AreaTool::Cap => {
+            let center = area_selection.cap.map_or(point, |(center, _)| center);
+            area_selection.cap = Some((center, center.angle_between(point)));
+        },
+        AreaTool::Lasso => {
+            if !area_selection.lasso.last().is_some_and(|last_point| last_point.angle_between(point) < LASSO_POINT_ANGLE) {
+                area_selection.lasso.push(point);
+            }
+        },
+    }
+
+    let mut candidates: Vec<Uuid> = query_balls.iter()
+        .filter(|(_, transform)| {
+            let direction = transform.translation.normalize_or_zero();
+            match tool {
+                AreaTool::Cap => area_selection.cap.is_some_and(|(center, angle)| area::is_in_cap(direction, center, angle)),
+                AreaTool::Lasso => area::is_in_lasso(direction, &area_selection.lasso),
+            }
+        })
+        .map(|(uuid_ball, _)| uuid_ball.0)
+        .collect();
+    candidates.sort();
+
+    //All of them are deleted in one batch, and the undo puts the balls and their links back in one batch
+    let mut selected = Vec::new();
+    let mut selected_links = HashSet::new();
+    for uuid in candidates {
+        let new_links: Vec<Uuid> = query_links.iter()
+            .filter(|(uuid_link, link)| link.is_link_to(&uuid) && !selected_links.contains(&uuid_link.0))
+            .map(|(uuid_link, _)| uuid_link.0)
+            .collect();
+        if selected.len() + 1 + selected_links.len() + new_links.len() > MAX_BATCH_SIZE {
+            continue;
+        }
+        selected.push(uuid);
+        selected_links.extend(new_links);
+    }
+    area_selection.selected = selected;
+}
+
+pub fn draw_area_gizmos(
+    mut gizmos: Gizmos,
+    area_selection: Res<AreaSelection>,
+    query_balls: Query<(&BallUuid, &Transform)>,
+) {
+    if let Some((center, angle)) = area_selection.cap {
+        if let Ok(normal) = Direction3d::new(center) {
+            gizmos.circle(center * angle.cos(), normal, angle.sin(), Color::RED);
+        }
+    }
+    if area_selection.lasso.len() > 1 {
+        //Closed when it is done
+        let closing_point = (!area_selection.is_drawing).then(|| area_selection.lasso[0]);
+        gizmos.linestrip(area_selection.lasso.iter().copied().chain(closing_point), Color::RED);
+    }
+    for (uuid_ball, transform) in query_balls.iter() {
+        if area_selection.selected.contains(&uuid_ball.0) {
+            if let Ok(normal) = Direction3d::new(transform.translation) {
+                gizmos.circle(transform.translation, normal, transform.scale.x * 1.5, Color::RED);
+            }
+        }
+    }
+}
+
+//Deletes the selected balls in one batch, and keeps them so the delete can be undone.
+//The server deletes the links to the balls with them, so the links are kept too and put back after the balls.
+//The balls go away when the delete comes back in the log, so nothing is despawned if the server rejects it.
+pub fn delete_area_selection(
+    mut delete_selection_events: EventReader<DeleteSelectionEvent>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut area_selection: ResMut<AreaSelection>,
+    ball_records: Res<BallRecords>,
+    mut deleted_selections: ResMut<DeletedSelections>,
+    query_balls: Query<&BallUuid, Without<Link>>,
+    query_links: Query<(&BallUuid, &Link)>,
+    mut send_delete_ball_events: EventWriter<crate::query_server::SendBallChangeEvent>,
+) {
+    let key_pressed = keyboard_input.just_pressed(KeyCode::Delete) || keyboard_input.just_pressed(KeyCode::Backspace);
+    if delete_selection_events.read().count() == 0 && !key_pressed {
+        return;
+    }
+    if area_selection.selected.is_empty() || deleted_selections.in_flight.is_some() {
+        return;
+    }
+
+    let mut uuids = Vec::new();
+    let mut deleted_balls = Vec::new();
+    for uuid_ball in query_balls.iter() {
+        if area_selection.selected.contains(&uuid_ball.0) {
+            send_delete_ball_events.send(crate::query_server::SendBallChangeEvent::delete(uuid_ball.0));
+            uuids.push(uuid_ball.0);
+            deleted_balls.extend(ball_records.0.get(&uuid_ball.0).cloned());
+        }
+    }
+    for (uuid_link, link) in query_links.iter() {
+        if area_selection.selected.iter().any(|uuid| link.is_link_to(uuid)) {
+            deleted_balls.extend(ball_records.0.get(&uuid_link.0).cloned());
+        }
+    }
+    if !uuids.is_empty() {
+        deleted_selections.in_flight = Some(SelectionChange::Delete { uuids, deleted_balls });
+    }
+    *area_selection = AreaSelection { tool: area_selection.tool, ..default() };
+}
+
+//Inserts the balls of the latest delete again, with the same uuids, in one batch.
+//They stay in the undo list until the server has committed the batch.
+pub fn undo_delete_selection(
+    mut undo_events: EventReader<UndoDeleteSelectionEvent>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut deleted_selections: ResMut<DeletedSelections>,
+    mut send_insert_ball_events: EventWriter<crate::query_server::SendBallChangeEvent>,
+) {
+    let control = keyboard_input.pressed(KeyCode::ControlLeft) || keyboard_input.pressed(KeyCode::ControlRight);
+    let key_pressed = control && keyboard_input.just_pressed(KeyCode::KeyZ);
+    if undo_events.read().count() == 0 && !key_pressed {
+        return;
+    }
+    if deleted_selections.in_flight.is_some() {
+        return;
+    }
+
+    let Some(deleted_balls) = deleted_selections.deleted.last() else { return; };
+    let uuids = deleted_balls.iter().map(|deleted_ball| deleted_ball.uuid).collect();
+    for deleted_ball in deleted_balls {
+        send_insert_ball_events.send(crate::query_server::SendBallChangeEvent {
+            ball: BallDto {
+                is_insert: true,
+                created_at: None,
+                ..deleted_ball.clone()
+            }
+        });
+    }
+    deleted_selections.in_flight = Some(SelectionChange::Undo { uuids });
+}
+
+//Adds a committed delete to the undo list, or takes a committed undo from it. A rejected one leaves the list as it was.
+pub fn handle_selection_change_answers(
+    mut ball_changes_answered_events: EventReader<crate::query_server::BallChangesAnsweredEvent>,
+    mut deleted_selections: ResMut<DeletedSelections>,
+) {
+    for event in ball_changes_answered_events.read() {
+        let is_answer = deleted_selections.in_flight.as_ref()
+            .is_some_and(|in_flight| in_flight.uuids().iter().all(|uuid| event.uuids.contains(uuid)));
+        if !is_answer {
+            continue;
+        }
+        match deleted_selections.in_flight.take() {
+            Some(SelectionChange::Delete { deleted_balls, .. }) if event.committed => deleted_selections.deleted.push(deleted_balls),
+            Some(SelectionChange::Undo { .. }) if event.committed => { deleted_selections.deleted.pop(); },
+            _ => {},
+        }
+    }
+}
+
+//Keeps each ball as it was inserted, from this client or from the log
+pub fn record_balls(
+    mut ball_change_events: EventReader<crate::query_server::SendBallChangeEvent>,
+    mut transactions_events: EventReader<crate::query_server::ReceivedTransactionsEvent>,
+    mut ball_records: ResMut<BallRecords>,
+) {
+    for ball_change_event in ball_change_events.read().filter(|ball_change_event| ball_change_event.ball.is_insert) {
+        ball_records.0.insert(ball_change_event.ball.uuid, ball_change_event.ball.clone());
+    }
+    for transactions_event in transactions_events.read() {
+        for ball_transaction in transactions_event.ball_transactions.iter().filter(|ball_transaction| ball_transaction.ball_dto.is_insert) {
+            ball_records.0.insert(ball_transaction.ball_dto.uuid, ball_transaction.ball_dto.clone());
+        }
+    }
+}
+
+fn send_insert_ball_event(
+    send_insert_ball_events: &mut EventWriter<crate::query_server::SendBallChangeEvent>,
+    ball_uuid: Uuid,
+    ball_position: Vec3,
+    ball_impulse: Option<Vec3>,
+    selected_color_resource: &Res<SelectedColor>,
+    selected_body_resource: &Res<SelectedBody>,
+    selected_appearance_resource: &Res<SelectedAppearance>,
+) {
+    // is_fixed is true if ball_impulse is None, false otherwise
+    let is_fixed = ball_impulse.is_none();
+    send_insert_ball_events.send(crate::query_server::SendBallChangeEvent {
+        ball: BallDto {
+            is_fixed,
+            is_insert: true,
+            uuid: ball_uuid,
+            //color: Some("#ff0000".to_string()),
+            color: Some(color_to_hex(selected_color_resource.0)),
+            position: Some(PositionDto {
+                x: ball_position.x,
+                y: ball_position.y,
+                z: ball_position.z,
+            }),
+            impulse: ball_impulse.map(|impulse| ImpulseDto { // Use map to convert Option<Vec3> to Option<ImpulseDto>
+                x: impulse.x,
+                y: impulse.y,
+                z: impulse.z,
+            }),
+            created_at: None,
+            link: None,
+            // Default spheres are sent without a body, as before balls had one
+            body: (selected_body_resource.0 != BodyDto::default()).then(|| selected_body_resource.0.clone()),
+            annotation: None,
+            appearance: (selected_appearance_resource.0 != AppearanceDto::default()).then_some(selected_appearance_resource.0),
+            geo_position: None,
+            physics_profile: None,
+        }
+    });
+}
+
+//Consecutive hash mismatches before the balls are replaced by a snapshot from the server
+const MISMATCHES_BEFORE_RESYNC: u32 = 3;
+//Steps between checkpoints, five seconds
+const CHECKPOINT_INTERVAL_STEPS: u64 = 300;
+//Checkpoints kept, a late entry up to a minute old is simulated again from one. An older one needs a snapshot.
+const MAX_CHECKPOINTS: usize = 12;
+
+//Alive objects with the balls they connect if they are links, changed by entries the way the server replays the log
+#[derive(Default)]
+struct AliveSet(HashMap<Uuid, Option<LinkDto>>);
+
+impl AliveSet {
+    fn apply(&mut self, ball_dto: &BallDto) {
+        if ball_dto.physics_profile.is_some() {
+            return;
+        }
+        if ball_dto.is_insert {
+            self.0.insert(ball_dto.uuid, ball_dto.link.clone());
+            return;
+        }
+        let uuid = ball_dto.uuid;
+        self.0.remove(&uuid);
+        //Links go with the balls they connect
+        self.0.retain(|_, link| !link.as_ref().is_some_and(|link| link.from_uuid == uuid || link.to_uuid == uuid));
+    }
+}
+
+//Entries are scheduled for the step of their transaction, the shared simulation applies them when it gets there.
+//An entry for a step already simulated means this client has gone a different way than the others,
+//so the simulation is run again from the last checkpoint before that step.
+pub fn receive_ball_transactions_event_listener(
+    mut commands: Commands,
+    mut events: EventReader<crate::query_server::ReceivedTransactionsEvent>,
+    query_objects: Query<(Entity, Option<&LogEntry>), (With<BallUuid>, Without<Upserted>)>,
+    mut last_received_transaction: ResMut<LastReceivedTransaction>,
+    mut send_transactions_request_event: EventWriter<SendTransactionsRequestEvent>,
+    mut send_snapshot_request_event: EventWriter<SendSnapshotRequestEvent>,
+    mut simulation_clock: ResMut<SimulationClock>,
+    mut scheduled_transactions: ResMut<ScheduledTransactions>,
+    mut pending_links: ResMut<PendingLinks>,
+    mut received_log: ResMut<ReceivedLog>,
+    mut simulation_checkpoints: ResMut<SimulationCheckpoints>,
+    mut alive_set_hash_check: ResMut<AliveSetHashCheck>,
+    time: Res<Time>,
+) {
+    for event in events.read() {
+        if event.is_snapshot {
+            //Only the alive set is in a snapshot, the simulation starts again from it
+            bevy::log::info!("receive_ball_transactions_event_listener: Replacing all balls with a snapshot.");
+            for (entity_object, _) in query_objects.iter() {
+                commands.entity(entity_object).despawn_recursive();
+            }
+            scheduled_transactions.0.clear();
+            pending_links.0.clear();
+            *received_log = ReceivedLog::default();
+            simulation_checkpoints.0.clear();
+            simulation_clock.step = None;
+        }
+        if event.next_cursor.is_some() {
+            last_received_transaction.0 = event.next_cursor.clone();
+        }
+        if event.has_more {
+            // Fetch the next page immediately
+            send_transactions_request_event.send(SendTransactionsRequestEvent);
+        }
+        if event.globe_epoch.is_some() {
+            simulation_clock.globe_epoch = event.globe_epoch;
+        }
+        simulation_clock.sync_server_time(event.server_time, time.elapsed());
+
+        let mut late_step: Option<u64> = None;
+        for ball_transaction in &event.ball_transactions {
+            // Profile changes are in the log with the balls, receive_physics_profile_changes takes them
+            if ball_transaction.ball_dto.physics_profile.is_some() {
+                continue;
+            }
+            let step = simulation_clock.transaction_step(&ball_transaction.transaction_id);
+            if let (Some(simulated_step), Some(step)) = (simulation_clock.step, step) {
+                if step <= simulated_step {
+                    bevy::log::warn!("receive_ball_transactions_event_listener: {} is for step {}, the simulation is at {}.", ball_transaction.ball_dto.uuid, step, simulated_step);
+                    late_step = Some(late_step.map_or(step, |late_step| late_step.min(step)));
+                }
+            }
+            let scheduled_transaction = ScheduledTransaction { step, ball_transaction: ball_transaction.clone(), restored: None };
+            received_log.ball_transactions.push(scheduled_transaction.clone());
+            scheduled_transactions.0.push(scheduled_transaction);
+        }
+
+        // Compare with the server's alive set at next_cursor, the objects here and the entries still waiting for their step
+        if let Some(server_hash) = event.alive_set_hash {
+            let mut alive_set = AliveSet::default();
+            for log_entry in query_objects.iter().filter_map(|(_, log_entry)| log_entry) {
+                alive_set.apply(&log_entry.0.ball_dto);
+            }
+            for scheduled_transaction in scheduled_transactions.0.iter() {
+                alive_set.apply(&scheduled_transaction.ball_transaction.ball_dto);
+            }
+            if alive_set_hash(alive_set.0.keys()) == server_hash {
+                alive_set_hash_check.mismatches = 0;
+            } else {
+                alive_set_hash_check.mismatches += 1;
+                bevy::log::warn!("receive_ball_transactions_event_listener: Alive set differs from the server ({} times).", alive_set_hash_check.mismatches);
+                if alive_set_hash_check.mismatches >= MISMATCHES_BEFORE_RESYNC {
+                    alive_set_hash_check.mismatches = 0;
+                    send_snapshot_request_event.send(SendSnapshotRequestEvent);
+                }
+            }
+        }
+
+        if let Some(late_step) = late_step {
+            // Checkpoints from the late step on do not have the entry
+            while simulation_checkpoints.0.back().is_some_and(|checkpoint| checkpoint.step >= late_step) {
+                simulation_checkpoints.0.pop_back();
+            }
+            if simulation_checkpoints.0.is_empty() && received_log.is_trimmed {
+                bevy::log::warn!("receive_ball_transactions_event_listener: No checkpoint before step {}, asking for a snapshot.", late_step);
+                send_snapshot_request_event.send(SendSnapshotRequestEvent);
+            } else {
+                // Placeholders stay until their entries are applied
+                for (entity_object, _) in query_objects.iter().filter(|(_, log_entry)| log_entry.is_some()) {
+                    commands.entity(entity_object).despawn_recursive();
+                }
+                pending_links.0.clear();
+                let checkpoint = simulation_checkpoints.0.back();
+                let checkpoint_step = checkpoint.map(|checkpoint| checkpoint.step);
+                // Put back the objects of the checkpoint, then the entries after it
+                let restored_objects = checkpoint.into_iter()
+                    .flat_map(|checkpoint| checkpoint.objects.iter())
+                    .map(|(ball_transaction, restored)| ScheduledTransaction {
+                        step: checkpoint_step,
+                        ball_transaction: ball_transaction.clone(),
+                        restored: *restored,
+                    });
+                let entries_after = received_log.ball_transactions.iter()
+                    .filter(|scheduled_transaction| checkpoint_step.is_none_or(|checkpoint_step| scheduled_transaction.step.is_some_and(|step| step > checkpoint_step)))
+                    .cloned();
+                scheduled_transactions.0 = restored_objects.chain(entries_after).collect();
+                // Without a checkpoint it starts again from the oldest moving ball below
+                simulation_clock.step = checkpoint_step;
+            }
+        }
+
+        // When the whole log is loaded, start the shared clock at the oldest moving ball or link.
+        // Entries before it are applied together in the first step, nothing moves until then.
+        // The simulation then fast-forwards from there to now.
+        if simulation_clock.step.is_none() && !event.has_more {
+            if let Some(target_step) = simulation_clock.target_step(time.elapsed()) {
+                let first_step = scheduled_transactions.0
+                    .iter()
+                    .filter(|scheduled_transaction| {
+                        let ball_dto = &scheduled_transaction.ball_transaction.ball_dto;
+                        ball_dto.is_insert && (!ball_dto.is_fixed || ball_dto.link.is_some())
+                    })
+                    .filter_map(|scheduled_transaction| scheduled_transaction.step)
+                    .min();
+                let start_step = first_step.map_or(target_step, |step| step.saturating_sub(1));
+                simulation_clock.step = Some(start_step.min(target_step));
+            }
+        }
+    }
+}
+
+//Runs last in every simulation step. Keeps the objects every CHECKPOINT_INTERVAL_STEPS,
+//and drops the received entries the oldest checkpoint already has.
+pub fn take_checkpoint(
+    simulation_clock: Res<SimulationClock>,
+    mut simulation_checkpoints: ResMut<SimulationCheckpoints>,
+    mut received_log: ResMut<ReceivedLog>,
+    query_objects: Query<(&LogEntry, Option<&Transform>, Option<&Velocity>, Option<&Speed>, Option<&Link>)>,
+    query_joints: Query<&ImpulseJoint>,
+) {
+    let Some(step) = simulation_clock.step else { return; };
+    if step % CHECKPOINT_INTERVAL_STEPS != 0 {
+        return;
+    }
+
+    let mut objects: Vec<(BallTransactionDto, Option<RestoredState>)> = query_objects.iter()
+        .map(|(log_entry, transform, velocity, speed, link)| {
+            let restored = match (transform.zip(velocity), link.and_then(|link| link.joint)) {
+                (Some((transform, velocity)), _) => Some(RestoredState::MovingBall {
+                    transform: *transform,
+                    velocity: *velocity,
+                    speed: speed.map_or(0.0, |speed| speed.0),
+                }),
+                (None, Some(joint)) => query_joints.get(joint).ok()
+                    .and_then(|joint| joint.data.limits(JointAxis::X))
+                    .map(|limits| RestoredState::Link { rope_length: limits.max }),
+                (None, None) => None,
+            };
+            (log_entry.0.clone(), restored)
+        })
+        .collect();
+    objects.sort_by(|(ball_transaction, _), (other_ball_transaction, _)| ball_transaction.transaction_id.cmp(&other_ball_transaction.transaction_id));
+    simulation_checkpoints.0.push_back(SimulationCheckpoint { step, objects });
+    while simulation_checkpoints.0.len() > MAX_CHECKPOINTS {
+        simulation_checkpoints.0.pop_front();
+    }
+
+    let Some(oldest_step) = simulation_checkpoints.0.front().map(|checkpoint| checkpoint.step) else { return; };
+    received_log.ball_transactions.retain(|scheduled_transaction| scheduled_transaction.step.is_some_and(|step| step > oldest_step));
+    received_log.is_trimmed = true;
+}
+
+//Profiles from the server wait for their step in PhysicsProfile
+pub fn receive_physics_profile_event_listener(
+    mut events: EventReader<crate::query_server::ReceivedPhysicsProfileEvent>,
+    mut physics_profile: ResMut<PhysicsProfile>,
+) {
+    for event in events.read() {
+        physics_profile.add_change(event.physics_profile.clone());
+    }
+}
+
+//Profile changes come in the log with the balls, snapshots included
+pub fn receive_physics_profile_changes(
+    mut events: EventReader<crate::query_server::ReceivedTransactionsEvent>,
+    mut physics_profile: ResMut<PhysicsProfile>,
+) {
+    for event in events.read() {
+        for ball_transaction in &event.ball_transactions {
+            if let Some(new_physics_profile) = &ball_transaction.ball_dto.physics_profile {
+                physics_profile.add_change(new_physics_profile.clone());
+            }
+        }
+    }
+}
+
+//Switches to the profile in force at the step, and gives balls the restitution and damping of the current profile
+pub fn apply_physics_profile(
+    mut physics_profile: ResMut<PhysicsProfile>,
+    simulation_clock: Res<SimulationClock>,
+    mut query_balls: Query<(Ref<BallUuid>, &mut Restitution, Option<&mut Damping>)>,
+) {
+    let in_force = match simulation_clock.step {
+        Some(step) => physics_profile.changes
+            .iter()
+            .rev()
+            .find(|change| change.updated_at
+                .and_then(|updated_at| simulation_clock.spawn_step(updated_at))
+                .is_none_or(|apply_step| apply_step <= step))
+            .cloned()
+            .unwrap_or_default(),
+        //Not in the shared simulation yet, nothing to keep in step with
+        None => physics_profile.latest().clone(),
+    };
+    if physics_profile.current != in_force {
+        physics_profile.current = in_force;
+    }
+
+    let is_profile_changed = physics_profile.is_changed();
+    for (ball_uuid, mut restitution, damping) in query_balls.iter_mut() {
+        if !is_profile_changed && !ball_uuid.is_added() {
+            continue;
+        }
+        restitution.coefficient = physics_profile.current.restitution;
+        if let Some(mut damping) = damping {
+            damping.linear_damping = physics_profile.current.linear_damping;
+        }
+    }
+}
+
+//Runs first in every simulation step. Applies the entries due at the step in the order of the log,
+//so balls are added to the physics world in the same order on every client.
+//A ball placed here keeps its placeholder until its entry is due, then it is replaced by the one from the log.
+pub fn apply_due_transactions(
+    mut commands: Commands,
+    ball_mesh_resource: Res<HandleForBallMesh>,
+    annotation_font_resource: Res<HandleForAnnotationFont>,
+    mut ball_material_resource: ResMut<ColorMaterialMap>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    simulation_clock: Res<SimulationClock>,
+    mut scheduled_transactions: ResMut<ScheduledTransactions>,
+    mut pending_links: ResMut<PendingLinks>,
+    query_objects: Query<(Entity, &BallUuid), Without<Upserted>>,
+    query_links: Query<(Entity, &BallUuid, &Link)>,
+) {
+    let (due_transactions, waiting_transactions): (Vec<_>, Vec<_>) = scheduled_transactions.0
+        .drain(..)
+        .partition(|scheduled_transaction| match (simulation_clock.step, scheduled_transaction.step) {
+            (Some(step), Some(transaction_step)) => transaction_step <= step,
+            //Wait for the shared clock to start
+            (None, Some(_)) => false,
+            //Transaction ids that are not times, apply right away
+            (_, None) => true,
+        });
+    scheduled_transactions.0 = waiting_transactions;
+    if due_transactions.is_empty() {
+        return;
+    }
+
+    //Spawned entities only show up in the queries after this step, so the entries are first reduced to what is spawned and despawned
+    let mut to_despawn: HashSet<Entity> = HashSet::new();
+    let mut to_spawn: Vec<ScheduledTransaction> = Vec::new();
+    for scheduled_transaction in due_transactions {
+        let ball_dto = &scheduled_transaction.ball_transaction.ball_dto;
+        let uuid = ball_dto.uuid;
+        to_spawn.retain(|spawned_transaction| spawned_transaction.ball_transaction.ball_dto.uuid != uuid);
+        pending_links.0.retain(|pending_link| pending_link.ball_transaction.ball_dto.uuid != uuid);
+        to_despawn.extend(query_objects.iter().filter(|(_, uuid_object)| uuid_object.0 == uuid).map(|(entity_object, _)| entity_object));
+        if ball_dto.is_insert {
+            to_spawn.push(scheduled_transaction);
+            continue;
+        }
+
+        // Links to a deleted ball are deleted with it, the same way as on the server
+        let is_link_to = |scheduled_transaction: &ScheduledTransaction| scheduled_transaction.ball_transaction.ball_dto.link.as_ref()
+            .is_some_and(|link| link.from_uuid == uuid || link.to_uuid == uuid);
+        to_spawn.retain(|spawned_transaction| !is_link_to(spawned_transaction));
+        pending_links.0.retain(|pending_link| !is_link_to(pending_link));
+        to_despawn.extend(query_links.iter().filter(|(_, _, link)| link.is_link_to(&uuid)).map(|(entity_link, _, _)| entity_link));
+    }
+
+    for entity_object in to_despawn {
+        despawn_ball_or_link(&mut commands, entity_object, &query_links);
+    }
+    for scheduled_transaction in to_spawn {
+        let ball_transaction = &scheduled_transaction.ball_transaction;
+        let ball_dto = &ball_transaction.ball_dto;
+        if let (Some(annotation), Some(position)) = (&ball_dto.annotation, &ball_dto.position) {
+            let color = ball_dto.color.as_ref()
+                .and_then(|hex_color| Color::hex(hex_color).ok())
+                .unwrap_or(Color::BLACK);
+            let entity_annotation = spawn_annotation(
+                &mut commands,
+                &annotation_font_resource,
+                Vec3::new(position.x, position.y, position.z),
+                &annotation.text,
+                annotation.font_size,
+                color,
+            );
+            commands.entity(entity_annotation).insert((BallUuid(ball_dto.uuid), LogEntry(ball_transaction.clone())));
+        } else if ball_dto.link.is_some() {
+            // A link can be to a ball of this step, it is spawned when the ball is
+            pending_links.0.push(scheduled_transaction);
+        } else if let Some(entity_ball) = handle_insert_ball_transaction(
+            &mut commands,
+            &ball_mesh_resource,
+            &mut ball_material_resource,
+            &mut materials,
+            ball_transaction,
+        ) {
+            commands.entity(entity_ball).insert(LogEntry(ball_transaction.clone()));
+            // Put back where it was at the checkpoint, the impulse was given long ago
+            if let Some(RestoredState::MovingBall { transform, velocity, speed }) = scheduled_transaction.restored {
+                commands.entity(entity_ball).insert((transform, velocity, Speed(speed), ExternalImpulse::default()));
+            }
+        }
+    }
+}
+
+//Runs after the balls of the step are spawned, a link can be to one of them
+pub fn spawn_due_links(
+    mut commands: Commands,
+    mut pending_links: ResMut<PendingLinks>,
+    query_balls: Query<(Entity, &BallUuid, &Transform, Has<MovingBall>), (Without<Upserted>, Without<Link>)>,
+) {
+    for pending_link in pending_links.0.drain(..) {
+        let ball_dto = &pending_link.ball_transaction.ball_dto;
+        let Some(link) = &ball_dto.link else { continue; };
+        let find_ball = |uuid: &Uuid| query_balls.iter().find(|(_, uuid_ball, _, _)| uuid_ball.0 == *uuid);
+
+        if let (Some((from_entity, _, from_transform, from_is_moving)), Some((to_entity, _, to_transform, to_is_moving))) = (find_ball(&link.from_uuid), find_ball(&link.to_uuid)) {
+            let color = ball_dto.color.as_ref()
+                .and_then(|hex_color| Color::hex(hex_color).ok())
+                .unwrap_or(Color::WHITE);
+            let rope_length = match pending_link.restored {
+                Some(RestoredState::Link { rope_length }) => Some(rope_length),
+                _ => None,
+            };
+            let entity_link = spawn_link(
+                &mut commands,
+                ball_dto.uuid,
+                link,
+                (from_entity, from_transform.translation),
+                (to_entity, to_transform.translation),
+                from_is_moving || to_is_moving,
+                color,
+                rope_length,
+            );
+            commands.entity(entity_link).insert(LogEntry(pending_link.ball_transaction.clone()));
+        } else {
+            bevy::log::warn!("spawn_due_links: Missing ball for link {}.", ball_dto.uuid);
+        }
+    }
+}
+
+//Links also despawn their rope joint
+fn despawn_ball_or_link(
+    commands: &mut Commands,
+    entity: Entity,
+    query_links: &Query<(Entity, &BallUuid, &Link)>,
+) {
+    if let Some(joint) = query_links.get(entity).ok().and_then(|(_, _, link)| link.joint) {
+        //The joint is gone already if its ball was deleted before the link
+        commands.add(move |world: &mut World| {
+            if let Some(joint) = world.get_entity_mut(joint) {
+                joint.despawn();
+            }
+        });
+    }
+    //Balls take their glow light and rope joints with them
+    commands.entity(entity).despawn_recursive();
+}
+
+//Links are drawn along the globe surface, following the balls when they move
+pub fn draw_links(
+    mut gizmos: Gizmos,
+    query_links: Query<&Link>,
+    query_balls: Query<(&BallUuid, &Transform), Without<Link>>,
+    link_start: Res<LinkStart>,
+) {
+    //Balls are scaled to their radius
+    let ball_transforms: HashMap<Uuid, &Transform> = query_balls.iter()
+        .map(|(uuid_ball, transform)| (uuid_ball.0, transform))
+        .collect();
+
+    for link in query_links.iter() {
+        if let (Some(from), Some(to)) = (ball_transforms.get(&link.from_uuid), ball_transforms.get(&link.to_uuid)) {
+            gizmos.linestrip(surface_arc(from.translation, to.translation), link.color);
+        }
+    }
+
+    if let Some(transform) = link_start.0.and_then(|uuid| ball_transforms.get(&uuid)) {
+        if let Ok(normal) = Direction3d::new(transform.translation) {
+            gizmos.circle(transform.translation, normal, transform.scale.x * 1.5, Color::WHITE);
+        }
+    }
+}
+
+//Points on the great circle arc between two points, at the height of the points over the globe
+fn surface_arc(from: Vec3, to: Vec3) -> impl Iterator<Item = Vec3> {
+    let rotation = Quat::from_rotation_arc(from.normalize(), to.normalize());
+    (0..=LINK_ARC_SEGMENTS).map(move |segment| {
+        let t = segment as f32 / LINK_ARC_SEGMENTS as f32;
+        let height = from.length() + (to.length() - from.length()) * t;
+        (Quat::IDENTITY.slerp(rotation, t) * from.normalize()) * height
+    })
+}
+
+fn handle_insert_ball_transaction(
+    commands: &mut Commands,
+    ball_mesh_resource: &Res<HandleForBallMesh>,
+    ball_material_resource: &mut ResMut<ColorMaterialMap>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    //selected_color_resource: &Res<SelectedColor>,
+    ball_transaction: &BallTransactionDto,
+) -> Option<Entity> {
+    let position = match &ball_transaction.ball_dto.position {
+        Some(pos) => pos,
+        None => {
+            // Log error or handle the case of missing position
+            bevy::log::error!("Missing position! Not good.");
+            return None;
+        },
+    };
+
+    let color = if let Some(hex_color) = &ball_transaction.ball_dto.color {
+        Color::hex(&hex_color).unwrap()
+    } else {
+        Color::WHITE // Default color if None
+    };
+    let body = ball_transaction.ball_dto.body.clone().unwrap_or_default();
+    let appearance = ball_transaction.ball_dto.appearance.unwrap_or_default();
+
+    if ball_transaction.ball_dto.is_fixed {
+        Some(spawn_static_ball(
+            commands, 
+            ball_mesh_resource,
+            ball_material_resource,
+            materials,
+            color,
+            &body,
+            &appearance,
+            (position.x, position.y, position.z),
+            false,
+            Some(ball_transaction.ball_dto.uuid)
+        ))
+    } else {
+        let impulse = match &ball_transaction.ball_dto.impulse {
+            Some(imp) => imp,
+            None => {
+                bevy::log::error!("Missing impulse! Not good on a moving ball.");
+                return None;
+            },
+        };
+
+        Some(spawn_moving_ball(
+            commands, 
+            ball_mesh_resource,
+            ball_material_resource,
+            materials,
+            color,
+            &body,
+            &appearance,
+            (position.x, position.y, position.z),
+            Vec3::new(impulse.x, impulse.y, impulse.z),
+            Some(ball_transaction.ball_dto.uuid)
+        ))
+    }
+}
@@ -1,13 +1,18 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use bevy::math::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use shared::domain::dtos::surface_dto::{SurfaceDto, SurfaceTextureDto};
 
 pub struct GlobePlugin;
 
 impl Plugin for GlobePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_globe)
+        app.add_systems(Startup, (init_surface_textures, spawn_globe))
+            .add_systems(Update, (receive_surface_event_listener, receive_surface_image_event_listener, update_globe_material).chain())
             .insert_resource(GlobeName(crate::get_query_param("globe")))
+            .insert_resource(GlobeSurface::default())
             .insert_resource(GlobePos(Vec3::new(0.0, 0.0, 0.0)))
             .insert_resource(GlobeRadius(1.0))
             .register_type::<Globe>();
@@ -27,6 +32,59 @@ pub struct GlobePos(pub Vec3);
 #[derive(Resource)]
 pub struct GlobeRadius(pub f32);
 
+//Texture of the globe as the server has it, with the uploaded image once it is loaded
+#[derive(Resource, Default)]
+pub struct GlobeSurface {
+    pub surface: SurfaceDto,
+    pub image: Option<Handle<Image>>,
+}
+
+//Textures that come with the client
+#[derive(Resource)]
+pub struct SurfaceTextures {
+    pub earth: Handle<Image>,
+    pub grid: Handle<Image>,
+    pub checkerboard: Handle<Image>,
+}
+
+//Equirectangular textures are twice as wide as high
+const SURFACE_TEXTURE_HEIGHT: u32 = 512;
+//Degrees between the grid lines and the checkerboard squares
+const SURFACE_TEXTURE_STEP: u32 = 15;
+const SURFACE_BACKGROUND: [u8; 4] = [16, 24, 48, 255];
+const SURFACE_FOREGROUND: [u8; 4] = [96, 128, 192, 255];
+
+fn init_surface_textures(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let pixels_per_step = SURFACE_TEXTURE_HEIGHT / (180 / SURFACE_TEXTURE_STEP);
+    let grid = surface_texture(|x, y| x % pixels_per_step < 2 || y % pixels_per_step < 2);
+    let checkerboard = surface_texture(|x, y| (x / pixels_per_step + y / pixels_per_step).is_multiple_of(2));
+    commands.insert_resource(SurfaceTextures {
+        earth: asset_server.load("earth.png"),
+        grid: images.add(grid),
+        checkerboard: images.add(checkerboard),
+    });
+}
+
+//Pixels where is_foreground holds get the foreground color
+fn surface_texture(is_foreground: impl Fn(u32, u32) -> bool) -> Image {
+    let (width, height) = (2 * SURFACE_TEXTURE_HEIGHT, SURFACE_TEXTURE_HEIGHT);
+    let data = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .flat_map(|(x, y)| if is_foreground(x, y) { SURFACE_FOREGROUND } else { SURFACE_BACKGROUND })
+        .collect();
+    Image::new(
+        Extent3d { width, height, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
 fn spawn_globe(mut commands: Commands, 
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
@@ -35,10 +93,10 @@ fn spawn_globe(mut commands: Commands,
 
     commands.spawn(
         PbrBundle {
-            mesh: meshes.add(Mesh::from(Sphere {
-                radius: globe_radius.0,
-                ..default()
-            })),
+            //The uv sphere has its poles on the z axis, turned so they are on the y axis
+            //and the middle of an equirectangular texture is at longitude 0 on the x axis
+            mesh: meshes.add(Sphere::new(globe_radius.0).mesh().uv(72, 36)
+                .rotated_by(Quat::from_mat3(&Mat3::from_cols(Vec3::NEG_X, Vec3::Z, Vec3::Y)))),
             material: materials.add(Color::BLACK),
             ..default()
         }
@@ -59,3 +117,70 @@ fn spawn_globe(mut commands: Commands,
         )
     );
 }
+
+pub fn receive_surface_event_listener(
+    mut events: EventReader<crate::query_server::ReceivedSurfaceEvent>,
+    mut globe_surface: ResMut<GlobeSurface>,
+    mut send_surface_image_request_events: EventWriter<crate::query_server::SendSurfaceImageRequestEvent>,
+) {
+    for event in events.read() {
+        if globe_surface.surface == event.surface {
+            continue;
+        }
+        if event.surface.image_version != globe_surface.surface.image_version {
+            globe_surface.image = None;
+            if event.surface.texture == SurfaceTextureDto::Uploaded {
+                send_surface_image_request_events.send(crate::query_server::SendSurfaceImageRequestEvent);
+            }
+        }
+        globe_surface.surface = event.surface.clone();
+    }
+}
+
+pub fn receive_surface_image_event_listener(
+    mut events: EventReader<crate::query_server::ReceivedSurfaceImageEvent>,
+    mut globe_surface: ResMut<GlobeSurface>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for event in events.read() {
+        let decoded = match image::load_from_memory(&event.image) {
+            Ok(decoded) => decoded.to_rgba8(),
+            Err(err) => {
+                bevy::log::error!("receive_surface_image_event_listener: Could not read image: {err}");
+                continue;
+            }
+        };
+        let (width, height) = decoded.dimensions();
+        globe_surface.image = Some(images.add(Image::new(
+            Extent3d { width, height, depth_or_array_layers: 1 },
+            TextureDimension::D2,
+            decoded.into_raw(),
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::RENDER_WORLD,
+        )));
+    }
+}
+
+//A globe without a texture is black, as it was before globes had surfaces
+pub fn update_globe_material(
+    globe_surface: Res<GlobeSurface>,
+    surface_textures: Res<SurfaceTextures>,
+    query_globe: Query<&Handle<StandardMaterial>, With<Globe>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !globe_surface.is_changed() {
+        return;
+    }
+    let texture = match globe_surface.surface.texture {
+        SurfaceTextureDto::Plain => None,
+        SurfaceTextureDto::Earth => Some(surface_textures.earth.clone()),
+        SurfaceTextureDto::Grid => Some(surface_textures.grid.clone()),
+        SurfaceTextureDto::Checkerboard => Some(surface_textures.checkerboard.clone()),
+        SurfaceTextureDto::Uploaded => globe_surface.image.clone(),
+    };
+    for material_handle in query_globe.iter() {
+        let Some(material) = materials.get_mut(material_handle) else { continue; };
+        material.base_color = if texture.is_some() { Color::WHITE } else { Color::BLACK };
+        material.base_color_texture = texture.clone();
+    }
+}
@@ -7,6 +7,7 @@ use ball::BallPlugin;
 use orbit_camera_controller::OrbitCameraControllerPlugin;
 use query_server::QueryServerPlugin;
 use ui::GridMenuPlugin;
+use simulation_clock::{SimulationClockPlugin, SimulationStep, SIMULATION_TIMESTEP_SECONDS};
 
 
 #[cfg(target_arch = "wasm32")]
@@ -23,6 +24,7 @@ mod ball;
 mod query_server;
 mod orbit_camera_controller;
 mod ui;
+mod simulation_clock;
 
 
 #[cfg(target_arch = "wasm32")]
@@ -141,7 +143,9 @@ fn main() {
                 }
             )
         )
-        .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+        //Physics is stepped by the shared simulation clock
+        .add_plugins(RapierPhysicsPlugin::<NoUserData>::default().in_schedule(SimulationStep))
+        .add_plugins(SimulationClockPlugin)
         .add_plugins(GlobePlugin)
         .add_plugins(BallPlugin)
         .add_plugins(OrbitCameraControllerPlugin)
@@ -163,6 +167,11 @@ pub enum AppState {
     EditUpsert,
     EditUpsertSetSpeed,
     EditDelete,
+    EditLink,
+    EditAnnotation,
+    EditBrush,
+    EditCurve,
+    EditArea,
     Orbiting,
     Zooming,
 }
@@ -197,6 +206,10 @@ fn setup_graphics(mut commands: Commands) {
 pub fn setup_physics(
     mut rapier_config: ResMut<RapierConfiguration>,) {
     rapier_config.gravity = Vec3::new(0.0, 0.0, 0.0);
+    rapier_config.timestep_mode = TimestepMode::Fixed {
+        dt: SIMULATION_TIMESTEP_SECONDS,
+        substeps: 1,
+    };
 }
 
 fn update_directional_light_direction(
@@ -1,11 +1,27 @@
 use bevy::{prelude::*, utils::Uuid};
 use bevy_mod_reqwest::bevy_eventlistener::callbacks::ListenerInput;
-use bevy_mod_reqwest::{*, reqwest::Url};
+use bevy_mod_reqwest::{*, reqwest::{StatusCode, Url}};
 use shared::domain::dtos::ball_dto::BallDto;
 use shared::domain::dtos::ball_transaction_dto::BallTransactionDto;
+use shared::domain::dtos::batch_request_dto::BatchRequestDto;
+use shared::domain::dtos::batch_response_dto::BatchResponseDto;
+use shared::domain::dtos::insert_ball_response_dto::InsertBallResponseDto;
+use shared::domain::dtos::get_snapshot_response_dto::GetSnapshotResponseDto;
+use shared::domain::dtos::physics_profile_dto::PhysicsProfileDto;
+use shared::domain::dtos::palette_dto::PaletteDto;
+use shared::domain::dtos::surface_dto::SurfaceDto;
+use shared::domain::dtos::globe_template_dto::GlobeTemplateDto;
 use url::ParseError;
+use std::collections::HashMap;
+use std::time::Duration;
 use crate::ball::components::{MovingBall, StaticBall};
-use crate::globe::GlobeName;
+use crate::globe::{GlobeName, GlobeSurface};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ball::resources::{ScheduledTransactions, PendingLinks, ReceivedLog, SimulationCheckpoints, PhysicsProfile};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::simulation_clock::SimulationClock;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ui::spawn::GlobePalette;
 
 pub struct QueryServerPlugin;
 
@@ -13,49 +29,180 @@ impl Plugin for QueryServerPlugin {
     fn build(&self, app: &mut App) {
         app
         .add_plugins(ReqwestPlugin::default()) 
-        .add_event::<SendInsertBallEvent>()
-        .add_event::<SendDeleteBallEvent>()
+        .add_event::<SendBallChangeEvent>()
         .add_event::<SendCreateNewGlobeEvent>()
+        .add_event::<SendForkGlobeEvent>()
         .add_event::<SendTransactionsRequestEvent>()
         .add_event::<ReceivedTransactionsEvent>()
+        .add_event::<SendSnapshotRequestEvent>()
+        .add_event::<BallChangesAnsweredEvent>()
         .add_event::<ReceivedGetNewGlobeIdResponseEvent>()
+        .add_event::<SendPhysicsProfileEvent>()
+        .add_event::<ReceivedPhysicsProfileEvent>()
+        .add_event::<SendPaletteEvent>()
+        .add_event::<ReceivedPaletteEvent>()
+        .add_event::<SendSurfaceEvent>()
+        .add_event::<ReceivedSurfaceEvent>()
+        .add_event::<SendSurfaceImageRequestEvent>()
+        .add_event::<ReceivedSurfaceImageEvent>()
         .add_systems(Update, send_transactions_requests)
-        .add_systems(Update, (insert_ball_event_listener, delete_ball_event_listener))
+        .add_systems(Update, ball_changes_event_listener)
+        .add_systems(Update, retry_pending_ball_requests)
+        .add_systems(Update, roll_back_rejected_ball_changes)
         .add_systems(Update, create_new_globe_event_listener)
+        .add_systems(Update, fork_globe_event_listener)
         .add_systems(Update, handle_received_new_globe_id_response_events)
         .add_systems(Update, send_transactions_request)
+        .add_systems(Update, send_snapshot_request)
+        .add_systems(Update, send_physics_profile_requests)
+        .add_systems(Update, physics_profile_changes_event_listener)
+        .add_systems(Update, send_palette_requests)
+        .add_systems(Update, palette_changes_event_listener)
+        .add_systems(Update, send_surface_requests)
+        .add_systems(Update, surface_changes_event_listener)
+        .add_systems(Update, send_surface_image_request)
         .insert_resource(ReqTimer(Timer::new(
             std::time::Duration::from_secs(1),//Check if server has new data every second
             TimerMode::Repeating,
         )))
-        .insert_resource(LastReceivedTransaction("0".to_string()))
+        .insert_resource(PhysicsProfileReqTimer(Timer::new(
+            std::time::Duration::from_secs(2),//Pick up changes other viewers make to the physics profile
+            TimerMode::Repeating,
+        )))
+        .insert_resource(PaletteReqTimer(Timer::new(
+            std::time::Duration::from_secs(2),//Pick up palettes other viewers make
+            TimerMode::Repeating,
+        )))
+        .insert_resource(SurfaceReqTimer(Timer::new(
+            std::time::Duration::from_secs(2),//Pick up surfaces other viewers pick
+            TimerMode::Repeating,
+        )))
+        .insert_resource(LastReceivedTransaction(None))
+        .insert_resource(PendingBallRequests::default())
+        .insert_resource(WireFormat::default())
         ;
     }
 }
 
+//An insert or a delete. Both go through the same event, so the server gets them in the order they were made.
 #[derive(Event)]
-pub struct SendInsertBallEvent {
+pub struct SendBallChangeEvent {
     pub ball:BallDto,
 }
 
-#[derive(Event)]
-pub struct SendCreateNewGlobeEvent;
+impl SendBallChangeEvent {
+    pub fn delete(uuid: Uuid) -> Self {
+        SendBallChangeEvent {
+            ball: BallDto {
+                is_insert: false,
+                uuid,
+                ..default()
+            },
+        }
+    }
+}
 
 #[derive(Event)]
-pub struct SendDeleteBallEvent {
-    pub uuid: Uuid,
+pub struct SendCreateNewGlobeEvent {
+    pub template: GlobeTemplateDto,
 }
 
+#[derive(Event)]
+pub struct SendForkGlobeEvent;
+
 #[derive(Resource)]
 struct ReqTimer(pub Timer);
 
+//Cursor into the globe's transaction log. None means start from the beginning.
 #[derive(Resource)]
-pub struct LastReceivedTransaction(pub String);
+pub struct LastReceivedTransaction(pub Option<String>);
+
+//Number of transactions to ask for in each request
+const TRANSACTIONS_PAGE_SIZE: usize = 100;
+
+//Insert, delete or batch of them that has been sent, but not answered by the server yet
+pub struct PendingBallRequest {
+    pub globe_name: String,
+    pub balls: Vec<BallDto>,
+    pub sent_at: Duration,
+    pub attempts: u32,
+}
+
+impl PendingBallRequest {
+    pub fn uuids(&self) -> Vec<Uuid> {
+        self.balls.iter().map(|ball| ball.uuid).collect()
+    }
+}
+
+//Pending inserts, deletes and batches by Idempotency-Key
+#[derive(Resource, Default)]
+pub struct PendingBallRequests(pub HashMap<String, PendingBallRequest>);
+
+const BALL_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_BALL_REQUEST_ATTEMPTS: u32 = 5;
 
 
 #[derive(Event)]
 pub struct SendTransactionsRequestEvent;
 
+//Ask for the alive balls instead of the log, when the local state has drifted from the server
+#[derive(Event)]
+pub struct SendSnapshotRequestEvent;
+
+//The server's answer to sent inserts and deletes, by the uuids in the request. Not committed if it was rejected or given up on.
+#[derive(Event)]
+pub struct BallChangesAnsweredEvent {
+    pub uuids: Vec<Uuid>,
+    pub committed: bool,
+}
+
+#[derive(Resource)]
+struct PhysicsProfileReqTimer(pub Timer);
+
+#[derive(Event)]
+pub struct SendPhysicsProfileEvent {
+    pub physics_profile: PhysicsProfileDto,
+}
+
+#[derive(Event)]
+pub struct ReceivedPhysicsProfileEvent {
+    pub physics_profile: PhysicsProfileDto,
+}
+
+#[derive(Resource)]
+struct PaletteReqTimer(pub Timer);
+
+#[derive(Event)]
+pub struct SendPaletteEvent {
+    pub palette: PaletteDto,
+}
+
+#[derive(Event)]
+pub struct ReceivedPaletteEvent {
+    pub palette: PaletteDto,
+}
+
+#[derive(Resource)]
+struct SurfaceReqTimer(pub Timer);
+
+#[derive(Event)]
+pub struct SendSurfaceEvent {
+    pub surface: SurfaceDto,
+}
+
+#[derive(Event)]
+pub struct ReceivedSurfaceEvent {
+    pub surface: SurfaceDto,
+}
+
+#[derive(Event)]
+pub struct SendSurfaceImageRequestEvent;
+
+#[derive(Event)]
+pub struct ReceivedSurfaceImageEvent {
+    pub image: Vec<u8>,
+}
+
 #[derive(Event)]
 pub struct ReceiveNewGlobeCreatedEvent {
     pub globe_name: String,
@@ -63,12 +210,21 @@ pub struct ReceiveNewGlobeCreatedEvent {
 
 #[derive(serde::Deserialize, Debug, Event)]
 pub struct ReceivedTransactionsEvent {
-    pub ball_transactions: Vec<BallTransactionDto>
+    pub ball_transactions: Vec<BallTransactionDto>,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+    pub globe_epoch: Option<i64>,
+    pub server_time: i64,
+    #[serde(default)]
+    pub alive_set_hash: Option<u64>,
+    //Replaces everything received before
+    #[serde(skip)]
+    pub is_snapshot: bool,
 }
 
 impl From<ListenerInput<ReqResponse>> for ReceivedTransactionsEvent {
     fn from(value: ListenerInput<ReqResponse>) -> Self {
-        let s = value.deserialize_json().unwrap();
+        let s = deserialize_response(&value).unwrap();
         s
     }
 }
@@ -80,11 +236,60 @@ pub struct ReceivedGetNewGlobeIdResponseEvent {
 
 impl From<ListenerInput<ReqResponse>> for ReceivedGetNewGlobeIdResponseEvent {
     fn from(value: ListenerInput<ReqResponse>) -> Self {
-        let s = value.deserialize_json().unwrap();
+        let s = deserialize_response(&value).unwrap();
         s
     }
 }
 
+const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+//Encoding of sync traffic. MessagePack is much smaller than JSON for large globes.
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub enum WireFormat {
+    Json,
+    MsgPack,
+}
+
+impl Default for WireFormat {
+    //The wasm client is the one used on mobile
+    fn default() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            WireFormat::MsgPack
+        } else {
+            WireFormat::Json
+        }
+    }
+}
+
+impl WireFormat {
+    fn content_type(&self) -> &'static str {
+        match self {
+            WireFormat::Json => "application/json",
+            WireFormat::MsgPack => MSGPACK_CONTENT_TYPE,
+        }
+    }
+
+    fn encode<T: serde::Serialize>(&self, value: &T) -> Vec<u8> {
+        match self {
+            WireFormat::Json => serde_json::to_vec(value).unwrap(),
+            WireFormat::MsgPack => rmp_serde::to_vec(value).unwrap(),
+        }
+    }
+}
+
+//The server answers in JSON if it does not support the asked format, and errors are always JSON
+fn deserialize_response<T: serde::de::DeserializeOwned>(response: &ReqResponse) -> Result<T, String> {
+    let is_msgpack = response.headers()
+        .get("Content-Type")
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with(MSGPACK_CONTENT_TYPE));
+    if is_msgpack {
+        response.deserialize_msgpack().map_err(|err| err.to_string())
+    } else {
+        response.deserialize_json().map_err(|err| err.to_string())
+    }
+}
+
 fn build_url(base_url: &str, path: &str) -> Result<Url, ParseError> {
     bevy::log::info!("build_url base_url: {}", base_url);
     bevy::log::info!("build_url path: {}", path);
@@ -108,67 +313,206 @@ fn build_url(base_url: &str, path: &str) -> Result<Url, ParseError> {
     Ok(full_url)
 }
 
-fn insert_ball_event_listener(
-    mut events: EventReader<SendInsertBallEvent>, 
+//Collects the inserts and deletes from this frame.
+//A single change uses the insert or delete endpoint, several changes are sent as one batch.
+//Either way it is kept until the server answers and resent with the same Idempotency-Key if it times out.
+fn ball_changes_event_listener(
+    mut ball_change_events: EventReader<SendBallChangeEvent>, 
     mut client: BevyReqwest,
+    mut pending_ball_requests: ResMut<PendingBallRequests>,
+    time: Res<Time>,
     globe_name: Res<GlobeName>,
     api_url: Res<crate::ApiURL>,
+    wire_format: Res<WireFormat>,
 ) {
-    for event in events.read() {
-        //if let Ok(url) = Url::parse("http://127.0.0.1:8080/globe1") {
-        //bevy::log::info!("Base URL: {}",api_url.0.as_str() );
-        if let Some(the_globe_name) = &globe_name.0 {
-            let url_string = build_url(api_url.0.as_str(), &the_globe_name).unwrap().to_string();
-            bevy::log::info!("insert_ball_event_listener url_string: {url_string}");
-            if let Ok(url) = Url::parse(url_string.as_str()) {
-                let body = serde_json::to_string(&event.ball).unwrap();
-                //bevy::log::info!("insert body: {body}");
-
-                //let req = reqwest.0.post(url).json(&body).build().unwrap();
-                let req = client.post(url)
-                .header("Content-Type", "application/json")
-                .body(body).build().unwrap();
-                client.send(
-                    req,
-                    On::run(|req: Listener<ReqResponse>| {
-                        if let Ok(string) = req.as_str() {
-                            bevy::log::info!("handle_insert_ball_responses: {string}");
-                        }
-                        else{
-                            bevy::log::error!("handle_insert_ball_responses: Received !Ok instead of a string.");
-                        }
-                    }),
-                );
-            }
+    //In the order they were made, the server applies a batch in order
+    let balls: Vec<BallDto> = ball_change_events.read().map(|event| event.ball.clone()).collect();
+
+    let Some(the_globe_name) = &globe_name.0 else { return; };
+
+    if balls.is_empty() { return; }
+
+    //Keep the request until the server answers, so it can be resent if it times out
+    let idempotency_key = Uuid::new_v4().to_string();
+    let pending_ball_request = PendingBallRequest {
+        globe_name: the_globe_name.clone(),
+        balls,
+        sent_at: time.elapsed(),
+        attempts: 1,
+    };
+    send_ball_request(&mut client, &api_url, *wire_format, &pending_ball_request, &idempotency_key);
+    pending_ball_requests.0.insert(idempotency_key, pending_ball_request);
+}
+
+//Resends inserts and deletes that got no response in time.
+//They are sent with the same Idempotency-Key, so the server does not apply them twice.
+fn retry_pending_ball_requests(
+    mut client: BevyReqwest,
+    mut pending_ball_requests: ResMut<PendingBallRequests>,
+    time: Res<Time>,
+    api_url: Res<crate::ApiURL>,
+    wire_format: Res<WireFormat>,
+    mut ball_changes_answered_events: EventWriter<BallChangesAnsweredEvent>,
+) {
+    let now = time.elapsed();
+    pending_ball_requests.0.retain(|idempotency_key, pending_ball_request| {
+        if now - pending_ball_request.sent_at < BALL_REQUEST_TIMEOUT {
+            return true;
         }
+        if pending_ball_request.attempts >= MAX_BALL_REQUEST_ATTEMPTS {
+            bevy::log::error!("retry_pending_ball_requests: Giving up on {} after {} attempts.", idempotency_key, pending_ball_request.attempts);
+            ball_changes_answered_events.send(BallChangesAnsweredEvent { uuids: pending_ball_request.uuids(), committed: false });
+            return false;
+        }
+
+        bevy::log::info!("retry_pending_ball_requests: Resending {}.", idempotency_key);
+        send_ball_request(&mut client, &api_url, *wire_format, pending_ball_request, idempotency_key);
+        pending_ball_request.sent_at = now;
+        pending_ball_request.attempts += 1;
+        true
+    });
+}
+
+fn send_ball_request(
+    client: &mut BevyReqwest,
+    api_url: &crate::ApiURL,
+    wire_format: WireFormat,
+    pending_ball_request: &PendingBallRequest,
+    idempotency_key: &str,
+) {
+    match pending_ball_request.balls.as_slice() {
+        [ball] if ball.is_insert => send_insert_ball_request(client, api_url, wire_format, &pending_ball_request.globe_name, ball, idempotency_key),
+        [ball] => send_delete_ball_request(client, api_url, &pending_ball_request.globe_name, ball.uuid, idempotency_key),
+        balls => send_batch_request(client, api_url, wire_format, &pending_ball_request.globe_name, balls, idempotency_key),
+    }
+}
+
+//A rejected change may already be shown here, so the balls are replaced with the server's alive set
+fn roll_back_rejected_ball_changes(
+    mut ball_changes_answered_events: EventReader<BallChangesAnsweredEvent>,
+    mut send_snapshot_request_event: EventWriter<SendSnapshotRequestEvent>,
+) {
+    if ball_changes_answered_events.read().any(|event| !event.committed) {
+        send_snapshot_request_event.send(SendSnapshotRequestEvent);
     }
 }
 
+fn send_insert_ball_request(
+    client: &mut BevyReqwest,
+    api_url: &crate::ApiURL,
+    wire_format: WireFormat,
+    globe_name: &str,
+    ball: &BallDto,
+    idempotency_key: &str,
+) {
+    let url_string = build_url(api_url.0.as_str(), globe_name).unwrap().to_string();
+    bevy::log::info!("send_insert_ball_request url_string: {url_string}");
+    if let Ok(url) = Url::parse(url_string.as_str()) {
+        let body = wire_format.encode(ball);
 
-fn delete_ball_event_listener(
-    mut events: EventReader<SendDeleteBallEvent>, 
-    mut client: BevyReqwest,
-    globe_name: Res<GlobeName>,
-    api_url: Res<crate::ApiURL>,    
+        let req = client.post(url)
+        .header("Content-Type", wire_format.content_type())
+        .header("Accept", wire_format.content_type())
+        .header("Idempotency-Key", idempotency_key)
+        .body(body).build().unwrap();
+        let idempotency_key = idempotency_key.to_string();
+        let uuid = ball.uuid;
+        client.send(
+            req,
+            On::run(move |req: Listener<ReqResponse>, mut pending_ball_requests: ResMut<PendingBallRequests>, mut ball_changes_answered_events: EventWriter<BallChangesAnsweredEvent>| {
+                pending_ball_requests.0.remove(&idempotency_key);
+                match deserialize_response::<InsertBallResponseDto>(&req) {
+                    Ok(insert_response) if req.status() == StatusCode::OK => {
+                        bevy::log::info!("handle_insert_ball_responses: {} {}", insert_response.message, insert_response.transaction_id);
+                        ball_changes_answered_events.send(BallChangesAnsweredEvent { uuids: vec![uuid], committed: true });
+                    },
+                    _ => {
+                        bevy::log::error!("handle_insert_ball_responses: {}", req.as_str().unwrap_or("Received !Ok instead of a string."));
+                        ball_changes_answered_events.send(BallChangesAnsweredEvent { uuids: vec![uuid], committed: false });
+                    }
+                }
+            }),
+        );
+    }
+}
+
+fn send_delete_ball_request(
+    client: &mut BevyReqwest,
+    api_url: &crate::ApiURL,
+    globe_name: &str,
+    uuid: Uuid,
+    idempotency_key: &str,
 ) {
-    for event in events.read() {
-        if let Some(globe_name) = &globe_name.0 {
-            let url_string = build_url(api_url.0.as_str(), &globe_name).unwrap().to_string();
-            if let Ok(url) = Url::parse(&format!("{}/{}", url_string, event.uuid)) {
-                let req = client.delete(url).build().unwrap();
-                client.send(
-                    req,
-                    On::run(|req: Listener<ReqResponse>| {
-                        if let Ok(string) = req.as_str() {
-                            bevy::log::info!("handle_delete_ball_responses: {string}");
-                        }
-                        else{
-                            bevy::log::error!("handle_delete_ball_responses: Received !Ok instead of a string.");
+    let url_string = build_url(api_url.0.as_str(), globe_name).unwrap().to_string();
+    if let Ok(url) = Url::parse(&format!("{}/{}", url_string, uuid)) {
+        let req = client.delete(url)
+        .header("Idempotency-Key", idempotency_key)
+        .build().unwrap();
+        let idempotency_key = idempotency_key.to_string();
+        client.send(
+            req,
+            On::run(move |req: Listener<ReqResponse>, mut pending_ball_requests: ResMut<PendingBallRequests>, mut ball_changes_answered_events: EventWriter<BallChangesAnsweredEvent>| {
+                pending_ball_requests.0.remove(&idempotency_key);
+                let committed = req.status() == StatusCode::OK;
+                if !committed {
+                    bevy::log::error!("handle_delete_ball_responses: Server answered {}: {}", req.status(), req.as_str().unwrap_or(""));
+                }
+                else if let Ok(string) = req.as_str() {
+                    bevy::log::info!("handle_delete_ball_responses: {string}");
+                }
+                else{
+                    bevy::log::error!("handle_delete_ball_responses: Received !Ok instead of a string.");
+                }
+                ball_changes_answered_events.send(BallChangesAnsweredEvent { uuids: vec![uuid], committed });
+            }),
+        );
+    }
+}
+
+fn send_batch_request(
+    client: &mut BevyReqwest,
+    api_url: &crate::ApiURL,
+    wire_format: WireFormat,
+    globe_name: &str,
+    balls: &[BallDto],
+    idempotency_key: &str,
+) {
+    let url_string = build_url(api_url.0.as_str(), &format!("{}/batch", globe_name)).unwrap().to_string();
+    bevy::log::info!("send_batch_request url_string: {url_string}, number of balls: {}", balls.len());
+    if let Ok(url) = Url::parse(url_string.as_str()) {
+        let body = wire_format.encode(&BatchRequestDto { balls: balls.to_vec() });
+        let uuids: Vec<Uuid> = balls.iter().map(|ball| ball.uuid).collect();
+
+        let req = client.post(url)
+        .header("Content-Type", wire_format.content_type())
+        .header("Accept", wire_format.content_type())
+        .header("Idempotency-Key", idempotency_key)
+        .body(body).build().unwrap();
+        let idempotency_key = idempotency_key.to_string();
+        client.send(
+            req,
+            On::run(move |req: Listener<ReqResponse>, mut pending_ball_requests: ResMut<PendingBallRequests>, mut ball_changes_answered_events: EventWriter<BallChangesAnsweredEvent>| {
+                pending_ball_requests.0.remove(&idempotency_key);
+                let committed = match deserialize_response::<BatchResponseDto>(&req) {
+                    Ok(batch_response) if batch_response.committed => {
+                        bevy::log::info!("handle_batch_responses: {}", batch_response.message);
+                        true
+                    },
+                    Ok(batch_response) => {
+                        bevy::log::error!("handle_batch_responses: {}", batch_response.message);
+                        for result in batch_response.results.iter().filter(|result| result.error.is_some()) {
+                            bevy::log::error!("handle_batch_responses: {} failed: {:?}", result.uuid, result.error);
                         }
-                    }),
-                );
-            }
-        }
+                        false
+                    },
+                    Err(err) => {
+                        bevy::log::error!("handle_batch_responses: Could not read response: {err}");
+                        false
+                    }
+                };
+                ball_changes_answered_events.send(BallChangesAnsweredEvent { uuids: uuids.clone(), committed });
+            }),
+        );
     }
 }
 
@@ -178,6 +522,7 @@ fn send_transactions_request(
     mut client: BevyReqwest,
     globe_name_res: Res<GlobeName>,
     last_trans: Res<LastReceivedTransaction>,
+    wire_format: Res<WireFormat>,
     mut send_create_new_globe_event: EventWriter<crate::query_server::SendCreateNewGlobeEvent>,
 ) {
     for _event in events.read() {
@@ -185,11 +530,16 @@ fn send_transactions_request(
             let url_string = build_url(api_url.0.as_str(), &globe_name)
                 .unwrap()
                 .to_string();
-            let request_url = format!("{}/{}", url_string, last_trans.0);
+            let request_url = match &last_trans.0 {
+                Some(after) => format!("{}?after={}&limit={}", url_string, after, TRANSACTIONS_PAGE_SIZE),
+                None => format!("{}?limit={}", url_string, TRANSACTIONS_PAGE_SIZE),
+            };
             bevy::log::info!("Sending transaction request to URL: {request_url}");
             
             if let Ok(url) = Url::parse(&request_url) {
-                let req = client.get(url).build().unwrap();
+                let req = client.get(url)
+                .header("Accept", wire_format.content_type())
+                .build().unwrap();
                 client.send(
                     req,
                     On::send_event::<ReceivedTransactionsEvent>());
@@ -199,11 +549,55 @@ fn send_transactions_request(
         }
         else {
             //If no globe_name is set, send event to create new globe
-            send_create_new_globe_event.send(crate::query_server::SendCreateNewGlobeEvent);
+            send_create_new_globe_event.send(crate::query_server::SendCreateNewGlobeEvent { template: GlobeTemplateDto::Empty });
         }
     }
 }
 
+fn send_snapshot_request(
+    mut events: EventReader<SendSnapshotRequestEvent>,
+    api_url: Res<crate::ApiURL>,
+    mut client: BevyReqwest,
+    globe_name_res: Res<GlobeName>,
+    wire_format: Res<WireFormat>,
+) {
+    //One request is enough however many times it was asked for
+    if events.read().count() == 0 {
+        return;
+    }
+    let Some(globe_name) = &globe_name_res.0 else { return; };
+
+    let url_string = build_url(api_url.0.as_str(), &format!("{}/snapshot", globe_name)).unwrap().to_string();
+    bevy::log::info!("Sending snapshot request to URL: {url_string}");
+    if let Ok(url) = Url::parse(url_string.as_str()) {
+        let req = client.get(url)
+        .header("Accept", wire_format.content_type())
+        .build().unwrap();
+        client.send(
+            req,
+            On::run(|req: Listener<ReqResponse>,
+                mut received_transactions_events: EventWriter<ReceivedTransactionsEvent>| {
+                match deserialize_response::<GetSnapshotResponseDto>(&req) {
+                    Ok(snapshot) => {
+                        received_transactions_events.send(ReceivedTransactionsEvent {
+                            ball_transactions: snapshot.ball_transactions,
+                            next_cursor: snapshot.next_cursor,
+                            has_more: false,
+                            globe_epoch: snapshot.globe_epoch,
+                            server_time: snapshot.server_time,
+                            alive_set_hash: Some(snapshot.alive_set_hash),
+                            is_snapshot: true,
+                        });
+                    },
+                    Err(err) => {
+                        bevy::log::error!("send_snapshot_request: Could not read response: {err}");
+                    }
+                }
+            }),
+        );
+    }
+}
+
 fn send_transactions_requests(
     time: Res<Time>,
     mut timer: ResMut<ReqTimer>,
@@ -216,18 +610,246 @@ fn send_transactions_requests(
     }
 }
 
+fn send_physics_profile_requests(
+    time: Res<Time>,
+    mut timer: ResMut<PhysicsProfileReqTimer>,
+    api_url: Res<crate::ApiURL>,
+    mut client: BevyReqwest,
+    globe_name: Res<GlobeName>,
+    wire_format: Res<WireFormat>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+    let Some(the_globe_name) = &globe_name.0 else { return; };
+
+    let url_string = build_url(api_url.0.as_str(), &format!("{}/physics", the_globe_name)).unwrap().to_string();
+    if let Ok(url) = Url::parse(url_string.as_str()) {
+        let req = client.get(url)
+        .header("Accept", wire_format.content_type())
+        .build().unwrap();
+        client.send(req, On::run(handle_physics_profile_response));
+    }
+}
+
+fn physics_profile_changes_event_listener(
+    mut events: EventReader<SendPhysicsProfileEvent>,
+    api_url: Res<crate::ApiURL>,
+    mut client: BevyReqwest,
+    globe_name: Res<GlobeName>,
+    wire_format: Res<WireFormat>,
+) {
+    //Only the last change counts when several are made in one frame
+    let Some(event) = events.read().last() else { return; };
+    let Some(the_globe_name) = &globe_name.0 else { return; };
+
+    let url_string = build_url(api_url.0.as_str(), &format!("{}/physics", the_globe_name)).unwrap().to_string();
+    if let Ok(url) = Url::parse(url_string.as_str()) {
+        let req = client.put(url)
+        .header("Content-Type", wire_format.content_type())
+        .header("Accept", wire_format.content_type())
+        .body(wire_format.encode(&event.physics_profile))
+        .build().unwrap();
+        //The server answers with the stored profile, including the time it takes effect
+        client.send(req, On::run(handle_physics_profile_response));
+    }
+}
+
+fn handle_physics_profile_response(
+    req: Listener<ReqResponse>,
+    mut received_physics_profile_events: EventWriter<ReceivedPhysicsProfileEvent>,
+) {
+    if req.status() != StatusCode::OK {
+        bevy::log::error!("handle_physics_profile_response: Server answered {}: {}", req.status(), req.as_str().unwrap_or_default());
+        return;
+    }
+    match deserialize_response::<PhysicsProfileDto>(&req) {
+        Ok(physics_profile) => {
+            received_physics_profile_events.send(ReceivedPhysicsProfileEvent { physics_profile });
+        },
+        Err(err) => {
+            bevy::log::error!("handle_physics_profile_response: Could not read response: {err}");
+        }
+    }
+}
+
+fn send_palette_requests(
+    time: Res<Time>,
+    mut timer: ResMut<PaletteReqTimer>,
+    api_url: Res<crate::ApiURL>,
+    mut client: BevyReqwest,
+    globe_name: Res<GlobeName>,
+    wire_format: Res<WireFormat>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+    let Some(the_globe_name) = &globe_name.0 else { return; };
+
+    let url_string = build_url(api_url.0.as_str(), &format!("{}/palette", the_globe_name)).unwrap().to_string();
+    if let Ok(url) = Url::parse(url_string.as_str()) {
+        let req = client.get(url)
+        .header("Accept", wire_format.content_type())
+        .build().unwrap();
+        client.send(req, On::run(handle_palette_response));
+    }
+}
+
+fn palette_changes_event_listener(
+    mut events: EventReader<SendPaletteEvent>,
+    api_url: Res<crate::ApiURL>,
+    mut client: BevyReqwest,
+    globe_name: Res<GlobeName>,
+    wire_format: Res<WireFormat>,
+) {
+    //Only the last change counts when several are made in one frame
+    let Some(event) = events.read().last() else { return; };
+    let Some(the_globe_name) = &globe_name.0 else { return; };
+
+    let url_string = build_url(api_url.0.as_str(), &format!("{}/palette", the_globe_name)).unwrap().to_string();
+    if let Ok(url) = Url::parse(url_string.as_str()) {
+        let req = client.put(url)
+        .header("Content-Type", wire_format.content_type())
+        .header("Accept", wire_format.content_type())
+        .body(wire_format.encode(&event.palette))
+        .build().unwrap();
+        client.send(req, On::run(handle_palette_response));
+    }
+}
+
+fn handle_palette_response(
+    req: Listener<ReqResponse>,
+    mut received_palette_events: EventWriter<ReceivedPaletteEvent>,
+) {
+    if req.status() != StatusCode::OK {
+        bevy::log::error!("handle_palette_response: Server answered {}: {}", req.status(), req.as_str().unwrap_or_default());
+        return;
+    }
+    match deserialize_response::<PaletteDto>(&req) {
+        Ok(palette) => {
+            received_palette_events.send(ReceivedPaletteEvent { palette });
+        },
+        Err(err) => {
+            bevy::log::error!("handle_palette_response: Could not read response: {err}");
+        }
+    }
+}
+
+fn send_surface_requests(
+    time: Res<Time>,
+    mut timer: ResMut<SurfaceReqTimer>,
+    api_url: Res<crate::ApiURL>,
+    mut client: BevyReqwest,
+    globe_name: Res<GlobeName>,
+    wire_format: Res<WireFormat>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+    let Some(the_globe_name) = &globe_name.0 else { return; };
+
+    let url_string = build_url(api_url.0.as_str(), &format!("{}/surface", the_globe_name)).unwrap().to_string();
+    if let Ok(url) = Url::parse(url_string.as_str()) {
+        let req = client.get(url)
+        .header("Accept", wire_format.content_type())
+        .build().unwrap();
+        client.send(req, On::run(handle_surface_response));
+    }
+}
+
+fn surface_changes_event_listener(
+    mut events: EventReader<SendSurfaceEvent>,
+    api_url: Res<crate::ApiURL>,
+    mut client: BevyReqwest,
+    globe_name: Res<GlobeName>,
+    wire_format: Res<WireFormat>,
+) {
+    //Only the last change counts when several are made in one frame
+    let Some(event) = events.read().last() else { return; };
+    let Some(the_globe_name) = &globe_name.0 else { return; };
+
+    let url_string = build_url(api_url.0.as_str(), &format!("{}/surface", the_globe_name)).unwrap().to_string();
+    if let Ok(url) = Url::parse(url_string.as_str()) {
+        let req = client.put(url)
+        .header("Content-Type", wire_format.content_type())
+        .header("Accept", wire_format.content_type())
+        .body(wire_format.encode(&event.surface))
+        .build().unwrap();
+        client.send(req, On::run(handle_surface_response));
+    }
+}
+
+fn handle_surface_response(
+    req: Listener<ReqResponse>,
+    mut received_surface_events: EventWriter<ReceivedSurfaceEvent>,
+) {
+    if req.status() != StatusCode::OK {
+        bevy::log::error!("handle_surface_response: Server answered {}: {}", req.status(), req.as_str().unwrap_or_default());
+        return;
+    }
+    match deserialize_response::<SurfaceDto>(&req) {
+        Ok(surface) => {
+            received_surface_events.send(ReceivedSurfaceEvent { surface });
+        },
+        Err(err) => {
+            bevy::log::error!("handle_surface_response: Could not read response: {err}");
+        }
+    }
+}
+
+//The uploaded image is only fetched when a new one has been uploaded
+fn send_surface_image_request(
+    mut events: EventReader<SendSurfaceImageRequestEvent>,
+    api_url: Res<crate::ApiURL>,
+    mut client: BevyReqwest,
+    globe_name: Res<GlobeName>,
+) {
+    if events.read().last().is_none() {
+        return;
+    }
+    let Some(the_globe_name) = &globe_name.0 else { return; };
+
+    let url_string = build_url(api_url.0.as_str(), &format!("{}/surface/image", the_globe_name)).unwrap().to_string();
+    if let Ok(url) = Url::parse(url_string.as_str()) {
+        let req = client.get(url).build().unwrap();
+        client.send(req, On::run(handle_surface_image_response));
+    }
+}
+
+fn handle_surface_image_response(
+    req: Listener<ReqResponse>,
+    mut received_surface_image_events: EventWriter<ReceivedSurfaceImageEvent>,
+) {
+    if req.status() != StatusCode::OK {
+        bevy::log::error!("handle_surface_image_response: Server answered {}", req.status());
+        return;
+    }
+    received_surface_image_events.send(ReceivedSurfaceImageEvent { image: req.body().to_vec() });
+}
+
 fn create_new_globe_event_listener(
     mut events: EventReader<SendCreateNewGlobeEvent>, 
     api_url: Res<crate::ApiURL>,
     mut client: BevyReqwest,
 ) {
-    for _event in events.read() {
+    for event in events.read() {
         bevy::log::info!("create_new_globe_event_listener");
 
-        let url_string = build_url(api_url.0.as_str(), "new_globe_id").unwrap().to_string();
+        //An empty globe only needs an id, a template is written by the server
+        let path = match event.template {
+            GlobeTemplateDto::Empty => "new_globe_id".to_string(),
+            template => format!("new_globe?template={}", template.name()),
+        };
+        let url_string = build_url(api_url.0.as_str(), &path).unwrap().to_string();
         bevy::log::info!("url_string: {}", url_string);
         if let Ok(url) = Url::parse(url_string.as_str()) {
-            let req = client.get(url).build().unwrap();
+            let req = match event.template {
+                GlobeTemplateDto::Empty => client.get(url).build().unwrap(),
+                _ => client.post(url).build().unwrap(),
+            };
             client.send(
                 req,
                 On::send_event::<ReceivedGetNewGlobeIdResponseEvent>());
@@ -235,6 +857,30 @@ fn create_new_globe_event_listener(
     }
 }
 
+//The server answers with the id of the copy, which is opened like a new globe
+fn fork_globe_event_listener(
+    mut events: EventReader<SendForkGlobeEvent>,
+    api_url: Res<crate::ApiURL>,
+    mut client: BevyReqwest,
+    globe_name: Res<GlobeName>,
+    wire_format: Res<WireFormat>,
+) {
+    //Several presses in one frame make one fork
+    if events.read().last().is_none() { return; }
+    let Some(the_globe_name) = &globe_name.0 else { return; };
+
+    let url_string = build_url(api_url.0.as_str(), &format!("{}/fork", the_globe_name)).unwrap().to_string();
+    bevy::log::info!("fork_globe_event_listener: {}", url_string);
+    if let Ok(url) = Url::parse(url_string.as_str()) {
+        let req = client.post(url)
+        .header("Accept", wire_format.content_type())
+        .build().unwrap();
+        client.send(
+            req,
+            On::send_event::<ReceivedGetNewGlobeIdResponseEvent>());
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 fn handle_received_new_globe_id_response_events(
     mut events: EventReader<ReceivedGetNewGlobeIdResponseEvent>,
@@ -242,18 +888,34 @@ fn handle_received_new_globe_id_response_events(
     query_static_balls: Query<Entity, With<StaticBall>>,
     mut globe_name: ResMut<GlobeName>,
     mut last_received_transaction: ResMut<LastReceivedTransaction>,
+    mut simulation_clock: ResMut<SimulationClock>,
+    mut scheduled_transactions: ResMut<ScheduledTransactions>,
+    mut pending_links: ResMut<PendingLinks>,
+    mut received_log: ResMut<ReceivedLog>,
+    mut simulation_checkpoints: ResMut<SimulationCheckpoints>,
+    mut physics_profile: ResMut<PhysicsProfile>,
+    mut globe_palette: ResMut<GlobePalette>,
+    mut globe_surface: ResMut<GlobeSurface>,
     mut commands: Commands
 ) {
     for ev in events.read() {
         if !ev.new_globe_id.is_empty(){
             for entity_moving_ball in query_moving_balls.iter() {
-                commands.entity(entity_moving_ball).despawn();
+                commands.entity(entity_moving_ball).despawn_recursive();
             }
             for entity_static_ball in query_static_balls.iter() {
-                commands.entity(entity_static_ball).despawn();
+                commands.entity(entity_static_ball).despawn_recursive();
             }
             globe_name.0 = Some(ev.new_globe_id.clone());
-            last_received_transaction.0 = "0".to_string();
+            last_received_transaction.0 = None;
+            *simulation_clock = SimulationClock::default();
+            scheduled_transactions.0.clear();
+            pending_links.0.clear();
+            *received_log = ReceivedLog::default();
+            simulation_checkpoints.0.clear();
+            *physics_profile = PhysicsProfile::default();
+            *globe_palette = GlobePalette::default();
+            *globe_surface = GlobeSurface::default();
         }
         else{
             bevy::log::error!("handle_create_new_globe_responses: Received empty new globe_id.");
@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use bevy::ecs::schedule::ScheduleLabel;
+use std::time::Duration;
+
+//Length of one simulation step, the same on every client
+pub const SIMULATION_TIMESTEP_NANOS: i64 = 1_000_000_000 / 60;
+pub const SIMULATION_TIMESTEP_SECONDS: f32 = SIMULATION_TIMESTEP_NANOS as f32 / 1_000_000_000.0;
+//The shared simulation runs this far behind server time, so the changes of every client are normally
+//received before their step. One that arrives later makes the client run the simulation again from a checkpoint.
+pub const INPUT_DELAY_NANOS: i64 = 2_000_000_000;
+//Most steps simulated in one frame when catching up, so the app stays responsive
+const MAX_STEPS_PER_FRAME: u64 = 1000;
+
+//Runs one fixed timestep of the simulation. Physics and the systems changing it run in this schedule.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimulationStep;
+
+pub struct SimulationClockPlugin;
+
+impl Plugin for SimulationClockPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_schedule(SimulationStep)
+        .insert_resource(SimulationClock::default())
+        .add_systems(Update, advance_simulation);
+    }
+}
+
+//Simulation time shared by all clients of a globe.
+//Step n is at globe_epoch + n * SIMULATION_TIMESTEP_NANOS in server time, so a client joining later
+//can fast-forward to the same step as everyone else. Each log entry takes effect at the step of its transaction.
+#[derive(Resource, Default)]
+pub struct SimulationClock {
+    pub globe_epoch: Option<i64>,
+    //Best estimate of the server time, and the local time it was received
+    server_time: Option<(i64, Duration)>,
+    //Last simulated step. None until the globe is loaded, until then the simulation runs in local time.
+    pub step: Option<u64>,
+    //Local time not simulated yet when running in local time
+    unsimulated_local_time: Duration,
+}
+
+impl SimulationClock {
+    //The sample giving the latest server time has the least network delay, keep that one
+    pub fn sync_server_time(&mut self, server_time: i64, now: Duration) {
+        let is_better = match self.server_now(now) {
+            Some(estimate) => server_time > estimate,
+            None => true,
+        };
+        if is_better {
+            self.server_time = Some((server_time, now));
+        }
+    }
+
+    fn server_now(&self, now: Duration) -> Option<i64> {
+        self.server_time.map(|(server_time, received_at)| server_time + (now - received_at).as_nanos() as i64)
+    }
+
+    //Step the simulation should be at now, INPUT_DELAY_NANOS behind the server
+    pub fn target_step(&self, now: Duration) -> Option<u64> {
+        let globe_epoch = self.globe_epoch?;
+        let server_now = self.server_now(now)?;
+        Some(((server_now - INPUT_DELAY_NANOS - globe_epoch).max(0) / SIMULATION_TIMESTEP_NANOS) as u64)
+    }
+
+    //Step a log entry of the given transaction takes effect at. Transaction ids are server times.
+    pub fn transaction_step(&self, transaction_id: &str) -> Option<u64> {
+        self.spawn_step(transaction_id.parse().ok()?)
+    }
+
+    //First step a ball created at the given server time is part of
+    pub fn spawn_step(&self, created_at: i64) -> Option<u64> {
+        let globe_epoch = self.globe_epoch?;
+        let since_epoch = (created_at - globe_epoch).max(0);
+        Some(((since_epoch + SIMULATION_TIMESTEP_NANOS - 1) / SIMULATION_TIMESTEP_NANOS) as u64)
+    }
+
+    fn steps_due(&mut self, now: Duration, delta: Duration) -> u64 {
+        if let (Some(step), Some(target_step)) = (self.step, self.target_step(now)) {
+            return target_step.saturating_sub(step).min(MAX_STEPS_PER_FRAME);
+        }
+
+        self.unsimulated_local_time += delta;
+        let timestep = Duration::from_nanos(SIMULATION_TIMESTEP_NANOS as u64);
+        let steps = (self.unsimulated_local_time.as_nanos() / timestep.as_nanos()) as u64;
+        self.unsimulated_local_time -= timestep * steps as u32;
+        steps.min(MAX_STEPS_PER_FRAME)
+    }
+}
+
+fn advance_simulation(world: &mut World) {
+    let time = world.resource::<Time>();
+    let (now, delta) = (time.elapsed(), time.delta());
+
+    let steps = world.resource_mut::<SimulationClock>().steps_due(now, delta);
+    for _ in 0..steps {
+        if let Some(step) = world.resource_mut::<SimulationClock>().step.as_mut() {
+            *step += 1;
+        }
+        world.run_schedule(SimulationStep);
+    }
+}
\ No newline at end of file
@@ -12,17 +12,49 @@ impl Plugin for GridMenuPlugin {
     fn build(&self, app: &mut App) {
         app
             .insert_resource(SelectedColor(Color::BLUE))
+            .insert_resource(SelectedBody::default())
+            .insert_resource(SelectedAppearance::default())
+            .insert_resource(Symmetry::default())
+            .insert_resource(SnapGrid::default())
+            .insert_resource(ColorPicker::default())
+            .insert_resource(GlobePalette::default())
+            .insert_resource(RecentColors::default())
             .insert_resource(SelectedDelete(false))
+            .insert_resource(SelectedLink(false))
+            .insert_resource(SelectedAnnotation(false))
+            .insert_resource(SelectedBrush(false))
+            .insert_resource(SelectedCurve::default())
+            .insert_resource(SelectedArea::default())
             .insert_resource(SelectedInfo(false))
             .insert_resource(ImageResources::default())
             .add_systems(Startup, spawn_layout)
             .add_systems(Update, check_cursor_over_ui)
             .add_systems(Update, color_button_selector)
             .add_systems(Update, update_color_button_appearance)
+            .add_systems(Update, update_swatch_colors)
+            .add_systems(Update, remember_recent_colors)
+            .add_systems(Update, receive_palette_event_listener)
             .add_systems(Update, delete_button_selector)
             .add_systems(Update, update_delete_button_appearance)
+            .add_systems(Update, link_button_selector)
+            .add_systems(Update, update_link_button_appearance)
+            .add_systems(Update, annotation_button_selector)
+            .add_systems(Update, update_annotation_button_appearance)
+            .add_systems(Update, brush_button_selector)
+            .add_systems(Update, update_brush_button_appearance)
+            .add_systems(Update, curve_button_selector)
+            .add_systems(Update, update_curve_button_appearance)
+            .add_systems(Update, area_button_selector)
+            .add_systems(Update, update_area_button_appearance)
+            .add_systems(Update, selection_panel_button_selector)
+            .add_systems(Update, update_selection_panel)
             .add_systems(Update, create_new_globe_button_selector)
+            .add_systems(Update, fork_globe_button_selector)
             .add_systems(Update, info_button_selector)
-            .add_systems(Update, update_info_button_appearance);
+            .add_systems(Update, update_info_button_appearance)
+            .add_systems(Update, settings_button_selector)
+            .add_systems(Update, update_settings_button_appearance)
+            .add_systems(Update, physics_setting_button_selector)
+            .add_systems(Update, update_physics_setting_texts);
     }
 }
\ No newline at end of file
@@ -1,4 +1,25 @@
 use bevy::prelude::*;
+use shared::domain::dtos::body_dto::BodyDto;
+use shared::domain::dtos::appearance_dto::{AppearanceDto, DEFAULT_ROUGHNESS};
+use shared::domain::dtos::palette_dto::{PaletteDto, MAX_PALETTE_COLORS};
+use shared::domain::dtos::globe_template_dto::GlobeTemplateDto;
+
+// Swatches of a globe without a palette
+pub const DEFAULT_SWATCH_COLORS: [Color; MAX_PALETTE_COLORS] = [
+    Color::ORANGE, Color::BISQUE, Color::BLUE, Color::CYAN,
+    Color::ORANGE_RED, Color::DARK_GREEN, Color::TEAL, Color::ALICE_BLUE,
+];
+
+// Looks of new balls offered in the settings panel, the server accepts any appearance within its limits
+pub const BALL_LOOKS: [(&str, AppearanceDto); 4] = [
+    ("Plain", AppearanceDto { emissive: 0.0, metallic: 0.0, roughness: DEFAULT_ROUGHNESS, transparency: 0.0 }),
+    ("Light", AppearanceDto { emissive: 4.0, metallic: 0.0, roughness: DEFAULT_ROUGHNESS, transparency: 0.0 }),
+    ("Metal", AppearanceDto { emissive: 0.0, metallic: 1.0, roughness: 0.2, transparency: 0.0 }),
+    ("Glass", AppearanceDto { emissive: 0.0, metallic: 0.0, roughness: 0.05, transparency: 0.7 }),
+];
+
+// How many of the last used colors are kept
+pub const RECENT_COLORS: usize = 3;
 
 #[derive(Component)]
 pub struct Menu;
@@ -12,6 +33,151 @@ pub struct SelectedColor(pub Color); // Stores the currently selected color
 #[derive(Component)]
 pub struct SelectedColorButton;
 
+// Where the color of a swatch comes from
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum Swatch {
+    Palette(usize), // The palette of the globe, or the default colors
+    Picked, // The color picker
+    Recent(usize), // The last used colors, newest first
+}
+
+#[derive(Resource, Default)]
+pub struct GlobePalette(pub PaletteDto);
+
+#[derive(Resource, Default)]
+pub struct RecentColors(pub Vec<Color>);
+
+// Hue in degrees, saturation and value from 0 to 1
+#[derive(Resource, Clone, Copy, PartialEq)]
+pub struct ColorPicker {
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+}
+
+impl Default for ColorPicker {
+    fn default() -> Self {
+        ColorPicker { hue: 0.0, saturation: 1.0, value: 1.0 }
+    }
+}
+
+impl ColorPicker {
+    pub fn color(&self) -> Color {
+        let chroma = self.value * self.saturation;
+        let hue_sector = self.hue / 60.0;
+        let second = chroma * (1.0 - (hue_sector % 2.0 - 1.0).abs());
+        let (red, green, blue) = match hue_sector as u32 {
+            0 => (chroma, second, 0.0),
+            1 => (second, chroma, 0.0),
+            2 => (0.0, chroma, second),
+            3 => (0.0, second, chroma),
+            4 => (second, 0.0, chroma),
+            _ => (chroma, 0.0, second),
+        };
+        let lightest = self.value - chroma;
+        Color::rgb(red + lightest, green + lightest, blue + lightest)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SelectedBody(pub BodyDto); // Size and shape of new balls
+
+#[derive(Resource, Default)]
+pub struct SelectedAppearance(pub AppearanceDto); // Look of new balls
+
+pub const MAX_SNAP_SUBDIVISIONS: u32 = 4;
+pub const MIN_SNAP_STEP_DEGREES: f32 = 5.0;
+pub const MAX_SNAP_STEP_DEGREES: f32 = 45.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SnapMode {
+    #[default]
+    Off,
+    Geodesic,
+    LatLong,
+}
+
+//Grid a ball placed with a click snaps to. Geodesic is the vertices of an icosphere,
+//lat/long the crossings of parallels and meridians step degrees apart.
+#[derive(Resource)]
+pub struct SnapGrid {
+    pub mode: SnapMode,
+    pub subdivisions: u32,
+    pub step_degrees: f32,
+}
+
+impl Default for SnapGrid {
+    fn default() -> Self {
+        SnapGrid {
+            mode: SnapMode::Off,
+            subdivisions: 2,
+            step_degrees: 15.0,
+        }
+    }
+}
+
+pub const MIN_SYMMETRY_FOLDS: u32 = 2;
+pub const MAX_SYMMETRY_FOLDS: u32 = 12;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SymmetryMode {
+    #[default]
+    Off,
+    Mirror,
+    Rotation,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SymmetryAxis {
+    X,
+    #[default]
+    Y,
+    Z,
+}
+
+impl SymmetryAxis {
+    pub fn direction(&self) -> Vec3 {
+        match self {
+            SymmetryAxis::X => Vec3::X,
+            SymmetryAxis::Y => Vec3::Y,
+            SymmetryAxis::Z => Vec3::Z,
+        }
+    }
+}
+
+//Symmetry of the placement tools. Mirror copies across the plane through the center that the axis stands on,
+//rotation copies to folds evenly spaced turns around the axis.
+#[derive(Resource)]
+pub struct Symmetry {
+    pub mode: SymmetryMode,
+    pub axis: SymmetryAxis,
+    pub folds: u32,
+}
+
+impl Default for Symmetry {
+    fn default() -> Self {
+        Symmetry {
+            mode: SymmetryMode::Off,
+            axis: SymmetryAxis::Y,
+            folds: 4,
+        }
+    }
+}
+
+impl Symmetry {
+    //Maps from a placed ball to each of its copies, for both position and impulse
+    pub fn copies(&self) -> Vec<Mat3> {
+        let axis = self.axis.direction();
+        match self.mode {
+            SymmetryMode::Off => Vec::new(),
+            SymmetryMode::Mirror => vec![Mat3::IDENTITY - Mat3::from_cols(axis * axis.x, axis * axis.y, axis * axis.z) * 2.0],
+            SymmetryMode::Rotation => (1..self.folds)
+                .map(|fold| Mat3::from_axis_angle(axis, std::f32::consts::TAU * fold as f32 / self.folds as f32))
+                .collect(),
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct DeleteButton; 
 
@@ -21,9 +187,94 @@ pub struct SelectedDeleteButton;
 #[derive(Resource)]
 pub struct SelectedDelete(pub bool);
 
+#[derive(Component)]
+pub struct LinkButton; 
+
+#[derive(Component)]
+pub struct SelectedLinkButton;
+
+#[derive(Resource)]
+pub struct SelectedLink(pub bool);
+
+#[derive(Component)]
+pub struct AnnotationButton; 
+
+#[derive(Component)]
+pub struct SelectedAnnotationButton;
+
+#[derive(Resource)]
+pub struct SelectedAnnotation(pub bool);
+
+#[derive(Component)]
+pub struct BrushButton; 
+
+#[derive(Component)]
+pub struct SelectedBrushButton;
+
+#[derive(Resource)]
+pub struct SelectedBrush(pub bool);
+
+//Drawing tools that fill a line or a circle through picked points with balls
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CurveTool {
+    Line,
+    Circle,
+}
+
+impl CurveTool {
+    pub fn points_needed(&self) -> usize {
+        match self {
+            CurveTool::Line => 2,
+            CurveTool::Circle => 3,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct CurveButton(pub CurveTool);
+
+#[derive(Resource, Default)]
+pub struct SelectedCurve(pub Option<CurveTool>);
+
+//Tools that select the balls in an area, to delete them together
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AreaTool {
+    Cap,
+    Lasso,
+}
+
+#[derive(Component)]
+pub struct AreaButton(pub AreaTool);
+
+#[derive(Resource, Default)]
+pub struct SelectedArea(pub Option<AreaTool>);
+
+//Shows how many balls are selected, with buttons to delete them and to undo
+#[derive(Component)]
+pub struct SelectionPanel;
+
+#[derive(Component)]
+pub struct SelectionCountText;
+
+#[derive(Component)]
+pub struct DeleteSelectionButton;
+
+#[derive(Component)]
+pub struct UndoDeleteSelectionButton;
+
 #[derive(Component)]
 pub struct CreateNewGlobeButton; 
 
+#[derive(Component)]
+pub struct ForkGlobeButton;
+
+//Opened by the create button, a new globe is made when a template is picked
+#[derive(Component)]
+pub struct TemplateChooser;
+
+#[derive(Component)]
+pub struct TemplateButton(pub GlobeTemplateDto);
+
 #[derive(Component)]
 pub struct InfoButton; 
 
@@ -36,6 +287,52 @@ pub struct SelectedInfoButton;
 #[derive(Resource)]
 pub struct SelectedInfo(pub bool);
 
+#[derive(Component)]
+pub struct SettingsButton; 
+
+#[derive(Component)]
+pub struct SelectedSettingsButton;
+
+#[derive(Component)]
+pub struct SettingsPanel; 
+
+//The physics profile values that can be changed in the settings panel,
+//the size and shape of new balls, which are only kept here, the color picker and the palette of the globe
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PhysicsSetting {
+    Gravity,
+    Restitution,
+    LinearDamping,
+    PreserveSpeed,
+    MinBallRadius,
+    MaxBallRadius,
+    BallRadius,
+    BallShape,
+    BallLook,
+    Hue,
+    Saturation,
+    Value,
+    InPalette,
+    PaletteEnforced,
+    Symmetry,
+    SymmetryFolds,
+    SymmetryAxis,
+    Snap,
+    SnapSubdivisions,
+    SnapStep,
+    Surface,
+}
+
+//Changes a setting by delta, or toggles it if it is on/off
+#[derive(Component)]
+pub struct PhysicsSettingButton {
+    pub setting: PhysicsSetting,
+    pub delta: f32,
+}
+
+#[derive(Component)]
+pub struct PhysicsSettingText(pub PhysicsSetting);
+
 #[derive(Component)]
 pub struct QRButton; 
 
@@ -48,18 +345,36 @@ pub struct QRButtonText;
 #[derive(Resource)]
 pub struct ImageResources {
     pub delete_ball: Handle<Image>,
+    pub link: Handle<Image>,
+    pub annotation: Handle<Image>,
+    pub brush: Handle<Image>,
+    pub line: Handle<Image>,
+    pub circle: Handle<Image>,
+    pub eraser: Handle<Image>,
+    pub lasso: Handle<Image>,
+    pub fork: Handle<Image>,
     pub info: Handle<Image>,
     pub plus: Handle<Image>,
     pub qr: Handle<Image>,
+    pub settings: Handle<Image>,
 }
 
 impl Default for ImageResources {
     fn default() -> Self {
         ImageResources {
             delete_ball: Handle::default(),
+            link: Handle::default(),
+            annotation: Handle::default(),
+            brush: Handle::default(),
+            line: Handle::default(),
+            circle: Handle::default(),
+            eraser: Handle::default(),
+            lasso: Handle::default(),
+            fork: Handle::default(),
             info: Handle::default(),
             plus: Handle::default(),
             qr: Handle::default(),
+            settings: Handle::default(),
         }
     }
 }
@@ -67,9 +382,18 @@ impl Default for ImageResources {
 #[derive(PartialEq, Eq)]
 enum ButtonType {
     DeleteButton,
+    LinkButton,
+    AnnotationButton,
+    BrushButton,
+    LineButton,
+    CircleButton,
+    EraserButton,
+    LassoButton,
     CreateButton,
+    ForkButton,
     InfoButton,
     QRButton,
+    SettingsButton,
 }
 
 pub fn spawn_layout(mut commands: Commands, asset_server: Res<AssetServer>, 
@@ -77,8 +401,17 @@ pub fn spawn_layout(mut commands: Commands, asset_server: Res<AssetServer>,
     //color_material_map: Res<ColorMaterialMap>
 ) {
     image_resources.delete_ball = asset_server.load("delete_ball.png");
+    image_resources.link = asset_server.load("link.png");
+    image_resources.annotation = asset_server.load("annotation.png");
+    image_resources.brush = asset_server.load("brush.png");
+    image_resources.line = asset_server.load("line.png");
+    image_resources.circle = asset_server.load("circle.png");
+    image_resources.eraser = asset_server.load("eraser.png");
+    image_resources.lasso = asset_server.load("lasso.png");
+    image_resources.fork = asset_server.load("fork.png");
     image_resources.plus = asset_server.load("plus.png");
     image_resources.info = asset_server.load("info.png");
+    image_resources.settings = asset_server.load("settings.png");
 
     let font = asset_server.load("fonts/FiraSans-Bold.ttf");
 
@@ -118,9 +451,8 @@ pub fn spawn_layout(mut commands: Commands, asset_server: Res<AssetServer>,
                         // Set the grid to have 4 columns all with sizes minmax(0, 1fr)
                         // This creates 4 exactly evenly sized columns
                         grid_template_columns: RepeatedGridTrack::flex(3, 1.0),
-                        // Set the grid to have 4 rows all with sizes minmax(0, 1fr)
-                        // This creates 4 exactly evenly sized rows
-                        grid_template_rows: RepeatedGridTrack::flex(3, 1.0),
+                        // Three rows of palette swatches and the picked color, then the recent colors
+                        grid_template_rows: RepeatedGridTrack::flex(4, 1.0),
                         // Set a 12px gap/gutter between rows and columns
                         row_gap: Val::Px(12.0),
                         column_gap: Val::Px(12.0),
@@ -131,16 +463,14 @@ pub fn spawn_layout(mut commands: Commands, asset_server: Res<AssetServer>,
                     ..default()
                 })
                 .with_children(|builder| {
-                    item_rect_color(builder, Color::ORANGE, false);
-                    item_rect_color(builder, Color::BISQUE, false);
-                    item_rect_color(builder, Color::BLUE, true);
-
-                    item_rect_color(builder, Color::CYAN, false);
-                    item_rect_color(builder, Color::ORANGE_RED, false);
-                    item_rect_color(builder, Color::DARK_GREEN, false);
-
-                    item_rect_color(builder, Color::TEAL, false);
-                    item_rect_color(builder, Color::ALICE_BLUE, false);
+                    for (index, color) in DEFAULT_SWATCH_COLORS.into_iter().enumerate() {
+                        item_rect_color(builder, color, color == Color::BLUE, Swatch::Palette(index));
+                    }
+                    item_rect_color(builder, ColorPicker::default().color(), false, Swatch::Picked);
+
+                    for index in 0..RECENT_COLORS {
+                        item_rect_color(builder, Color::NONE, false, Swatch::Recent(index));
+                    }
                     //item_rect_image(builder, image_resources.delete_ball.clone(), ButtonType::DeleteButton);
                 })
                 .insert(Menu);
@@ -150,11 +480,58 @@ pub fn spawn_layout(mut commands: Commands, asset_server: Res<AssetServer>,
                 .spawn(NodeBundle {
                     style: Style {
                         grid_row: GridPlacement::span(4),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
                         ..default()
                     },
                     //background_color: BackgroundColor(Color::BLACK),
                     visibility: Visibility::Hidden,
                     ..default()
+                })
+                .with_children(|builder| {
+                    let text_style = TextStyle {
+                        font: font.clone(),
+                        font_size: 16.0,
+                        ..default()
+                    };
+                    builder
+                        .spawn(NodeBundle {
+                            style: Style {
+                                padding: UiRect::all(Val::Px(6.0)),
+                                column_gap: Val::Px(6.0),
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            visibility: Visibility::Hidden,
+                            background_color: BackgroundColor(Color::DARK_GRAY),
+                            ..default()
+                        })
+                        .with_children(|builder| {
+                            builder.spawn(TextBundle::from_section("", text_style.clone()))
+                                .insert(SelectionCountText);
+                            selection_panel_button(builder, &text_style, "Delete", DeleteSelectionButton);
+                            selection_panel_button(builder, &text_style, "Undo", UndoDeleteSelectionButton);
+                        })
+                        .insert(SelectionPanel);
+                    builder
+                        .spawn(NodeBundle {
+                            style: Style {
+                                padding: UiRect::all(Val::Px(6.0)),
+                                column_gap: Val::Px(6.0),
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            visibility: Visibility::Hidden,
+                            background_color: BackgroundColor(Color::DARK_GRAY),
+                            ..default()
+                        })
+                        .with_children(|builder| {
+                            builder.spawn(TextBundle::from_section("New globe:", text_style.clone()));
+                            for template in GlobeTemplateDto::ALL {
+                                selection_panel_button(builder, &text_style, template.label(), TemplateButton(template));
+                            }
+                        })
+                        .insert(TemplateChooser);
                 });
 
             // Right column
@@ -173,7 +550,7 @@ pub fn spawn_layout(mut commands: Commands, asset_server: Res<AssetServer>,
                         grid_template_columns: RepeatedGridTrack::flex(3, 1.0),
                         // Set the grid to have 4 rows all with sizes minmax(0, 1fr)
                         // This creates 2 exactly evenly sized rows
-                        grid_template_rows: RepeatedGridTrack::flex(3, 1.0),
+                        grid_template_rows: RepeatedGridTrack::flex(4, 1.0),
                         // Set a 12px gap/gutter between rows and columns
                         row_gap: Val::Px(12.0),
                         column_gap: Val::Px(12.0),
@@ -185,22 +562,62 @@ pub fn spawn_layout(mut commands: Commands, asset_server: Res<AssetServer>,
                 })
                 .with_children(|builder| {
                     item_rect_image(builder, image_resources.plus.clone(), ButtonType::CreateButton);
+                    item_rect_image(builder, image_resources.fork.clone(), ButtonType::ForkButton);
                     item_rect_image(builder, image_resources.info.clone(), ButtonType::InfoButton);
                     item_rect_image(builder, image_resources.delete_ball.clone(), ButtonType::DeleteButton);
+                    item_rect_image(builder, image_resources.settings.clone(), ButtonType::SettingsButton);
+                    item_rect_image(builder, image_resources.link.clone(), ButtonType::LinkButton);
+                    item_rect_image(builder, image_resources.annotation.clone(), ButtonType::AnnotationButton);
+                    item_rect_image(builder, image_resources.brush.clone(), ButtonType::BrushButton);
+                    item_rect_image(builder, image_resources.line.clone(), ButtonType::LineButton);
+                    item_rect_image(builder, image_resources.circle.clone(), ButtonType::CircleButton);
+                    item_rect_image(builder, image_resources.eraser.clone(), ButtonType::EraserButton);
+                    item_rect_image(builder, image_resources.lasso.clone(), ButtonType::LassoButton);
                 })
                 .insert(Menu);
 
             // Left bottom
-            builder.spawn(NodeBundle {
-                style: Style {
-                    // Make this node span two grid column so that it takes up the entire bottom row
-                    grid_row: GridPlacement::span(2),
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        grid_row: GridPlacement::span(2),
+                        display: Display::Grid,
+                        padding: UiRect::all(Val::Px(12.0)),
+                        // Label, minus, value, plus
+                        grid_template_columns: vec![GridTrack::flex(3.0), GridTrack::flex(1.0), GridTrack::flex(2.0), GridTrack::flex(1.0)],
+                        grid_template_rows: RepeatedGridTrack::flex(21, 1.0),
+                        row_gap: Val::Px(6.0),
+                        column_gap: Val::Px(6.0),
+                        ..default()
+                    },
+                    visibility: Visibility::Hidden,
+                    background_color: BackgroundColor(Color::DARK_GRAY),
                     ..default()
-                },
-                background_color: BackgroundColor(Color::WHITE),
-                visibility: Visibility::Hidden,
-                ..default()
-            });
+                })
+                .with_children(|builder| {
+                    physics_setting_row(builder, &font, "Gravity", PhysicsSetting::Gravity, 1.0);
+                    physics_setting_row(builder, &font, "Bounce", PhysicsSetting::Restitution, 0.1);
+                    physics_setting_row(builder, &font, "Damping", PhysicsSetting::LinearDamping, 0.1);
+                    physics_setting_row(builder, &font, "Keep speed", PhysicsSetting::PreserveSpeed, 0.0);
+                    physics_setting_row(builder, &font, "Min size", PhysicsSetting::MinBallRadius, 0.005);
+                    physics_setting_row(builder, &font, "Max size", PhysicsSetting::MaxBallRadius, 0.005);
+                    physics_setting_row(builder, &font, "Ball size", PhysicsSetting::BallRadius, 0.005);
+                    physics_setting_row(builder, &font, "Shape", PhysicsSetting::BallShape, 0.0);
+                    physics_setting_row(builder, &font, "Look", PhysicsSetting::BallLook, 0.0);
+                    physics_setting_row(builder, &font, "Hue", PhysicsSetting::Hue, 15.0);
+                    physics_setting_row(builder, &font, "Saturation", PhysicsSetting::Saturation, 0.1);
+                    physics_setting_row(builder, &font, "Brightness", PhysicsSetting::Value, 0.1);
+                    physics_setting_row(builder, &font, "In palette", PhysicsSetting::InPalette, 0.0);
+                    physics_setting_row(builder, &font, "Lock palette", PhysicsSetting::PaletteEnforced, 0.0);
+                    physics_setting_row(builder, &font, "Symmetry", PhysicsSetting::Symmetry, 0.0);
+                    physics_setting_row(builder, &font, "Folds", PhysicsSetting::SymmetryFolds, 1.0);
+                    physics_setting_row(builder, &font, "Axis", PhysicsSetting::SymmetryAxis, 0.0);
+                    physics_setting_row(builder, &font, "Snap", PhysicsSetting::Snap, 0.0);
+                    physics_setting_row(builder, &font, "Subdivisions", PhysicsSetting::SnapSubdivisions, 1.0);
+                    physics_setting_row(builder, &font, "Grid step", PhysicsSetting::SnapStep, 5.0);
+                    physics_setting_row(builder, &font, "Surface", PhysicsSetting::Surface, 0.0);
+                })
+                .insert(SettingsPanel);
 
             // Rigth bottom
             builder
@@ -252,7 +669,7 @@ pub fn spawn_layout(mut commands: Commands, asset_server: Res<AssetServer>,
 /// Create a coloured rectangle node. The node has size as it is assumed that it will be
 /// spawned as a child of a Grid container with `AlignItems::Stretch` and `JustifyItems::Stretch`
 /// which will allow it to take it's size from the size of the grid area it occupies.
-fn item_rect_color(builder: &mut ChildBuilder, color: Color, is_selected: bool) {
+fn item_rect_color(builder: &mut ChildBuilder, color: Color, is_selected: bool, swatch: Swatch) {
     builder
         .spawn(NodeBundle {
             style: Style {
@@ -269,7 +686,7 @@ fn item_rect_color(builder: &mut ChildBuilder, color: Color, is_selected: bool)
                 background_color: BackgroundColor(color),
                 ..default()
             });
-            button.insert(ColorButton(color));
+            button.insert((ColorButton(color), swatch));
             if is_selected{
                 button.insert(SelectedColorButton);
             }
@@ -322,15 +739,131 @@ fn item_rect_image(
                 ButtonType::DeleteButton => {
                     button.insert(DeleteButton);
                 },
+                ButtonType::LinkButton => {
+                    button.insert(LinkButton);
+                },
+                ButtonType::AnnotationButton => {
+                    button.insert(AnnotationButton);
+                },
+                ButtonType::BrushButton => {
+                    button.insert(BrushButton);
+                },
+                ButtonType::LineButton => {
+                    button.insert(CurveButton(CurveTool::Line));
+                },
+                ButtonType::CircleButton => {
+                    button.insert(CurveButton(CurveTool::Circle));
+                },
+                ButtonType::EraserButton => {
+                    button.insert(AreaButton(AreaTool::Cap));
+                },
+                ButtonType::LassoButton => {
+                    button.insert(AreaButton(AreaTool::Lasso));
+                },
                 ButtonType::CreateButton => {
                     button.insert(CreateNewGlobeButton);
                 },
+                ButtonType::ForkButton => {
+                    button.insert(ForkGlobeButton);
+                },
                 ButtonType::InfoButton => {
                     button.insert(InfoButton);
                 },
                 ButtonType::QRButton => {
                     button.insert(QRButton);
                 },
+                ButtonType::SettingsButton => {
+                    button.insert(SettingsButton);
+                },
+            }
+        });
+}
+
+//One row of the settings panel. Settings that are on/off get one button showing the value, the others get minus and plus.
+fn physics_setting_row(
+    builder: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: &str,
+    setting: PhysicsSetting,
+    delta: f32,
+) {
+    let text_style = TextStyle {
+        font: font.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+
+    builder.spawn(TextBundle::from_section(label, text_style.clone()));
+
+    if matches!(setting, PhysicsSetting::PreserveSpeed | PhysicsSetting::BallShape | PhysicsSetting::BallLook | PhysicsSetting::InPalette | PhysicsSetting::PaletteEnforced
+        | PhysicsSetting::Symmetry | PhysicsSetting::SymmetryAxis | PhysicsSetting::Snap | PhysicsSetting::Surface) {
+        physics_setting_button(builder, &text_style, None, PhysicsSettingButton { setting, delta }, 3);
+        return;
+    }
+
+    physics_setting_button(builder, &text_style, Some("-"), PhysicsSettingButton { setting, delta: -delta }, 1);
+    builder
+        .spawn(NodeBundle {
+            style: Style {
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("", text_style.clone()))
+                .insert(PhysicsSettingText(setting));
+        });
+    physics_setting_button(builder, &text_style, Some("+"), PhysicsSettingButton { setting, delta }, 1);
+}
+
+//Without a label the button shows the value of the setting
+fn selection_panel_button(
+    builder: &mut ChildBuilder,
+    text_style: &TextStyle,
+    label: &str,
+    marker: impl Component,
+) {
+    builder
+        .spawn(ButtonBundle {
+            style: Style {
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::GRAY),
+            ..default()
+        })
+        .insert(marker)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(label, text_style.clone()));
+        });
+}
+
+fn physics_setting_button(
+    builder: &mut ChildBuilder,
+    text_style: &TextStyle,
+    label: Option<&str>,
+    physics_setting_button: PhysicsSettingButton,
+    column_span: u16,
+) {
+    let setting = physics_setting_button.setting;
+    builder
+        .spawn(ButtonBundle {
+            style: Style {
+                grid_column: GridPlacement::span(column_span),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::GRAY),
+            ..default()
+        })
+        .insert(physics_setting_button)
+        .with_children(|parent| {
+            let mut text = parent.spawn(TextBundle::from_section(label.unwrap_or_default(), text_style.clone()));
+            if label.is_none() {
+                text.insert(PhysicsSettingText(setting));
             }
         });
 }
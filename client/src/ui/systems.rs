@@ -1,6 +1,13 @@
 use bevy::prelude::*;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 use super::spawn::*;
+use crate::ball::resources::PhysicsProfile;
+use shared::domain::dtos::physics_profile_dto::{MAX_GRAVITY, MAX_RESTITUTION, MAX_LINEAR_DAMPING};
+use shared::domain::dtos::body_dto::{ShapeDto, MIN_BALL_RADIUS, MAX_BALL_RADIUS};
+use shared::domain::dtos::palette_dto::MAX_PALETTE_COLORS;
+use crate::ball::systems::color_to_hex;
+use crate::globe::GlobeSurface;
+use shared::domain::dtos::surface_dto::{SurfaceDto, SurfaceTextureDto};
 use bevy::input::touch::{TouchInput, TouchPhase};
 use bevy::render::texture::Image;
 use qrcode::QrCode;
@@ -112,6 +119,76 @@ pub fn update_color_button_appearance(
 }
 
 
+//Gives the swatches the colors of the palette, the picker and the last used colors.
+//Changing the picker selects its swatch.
+pub fn update_swatch_colors(
+    mut commands: Commands,
+    globe_palette: Res<GlobePalette>,
+    recent_colors: Res<RecentColors>,
+    color_picker: Res<ColorPicker>,
+    mut query_swatches: Query<(Entity, &Swatch, &mut ColorButton, &Parent, Has<SelectedColorButton>)>,
+    mut query_swatch_frames: Query<&mut Visibility, Without<ColorButton>>,
+    mut selected_color: ResMut<SelectedColor>,
+) {
+    if !globe_palette.is_changed() && !recent_colors.is_changed() && !color_picker.is_changed() {
+        return;
+    }
+    let is_picker_used = color_picker.is_changed() && !color_picker.is_added();
+    let palette = &globe_palette.0;
+
+    for (entity, swatch, mut color_button, parent, is_selected) in query_swatches.iter_mut() {
+        let color = match *swatch {
+            Swatch::Palette(index) if palette.colors.is_empty() => DEFAULT_SWATCH_COLORS.get(index).copied(),
+            Swatch::Palette(index) => palette.colors.get(index).and_then(|hex_color| Color::hex(hex_color).ok()),
+            Swatch::Picked => Some(color_picker.color()),
+            Swatch::Recent(index) => recent_colors.0.get(index).copied(),
+        };
+        //Colors the server would not accept are not offered
+        let color = color.filter(|color| !palette.is_enforced || palette.contains(&color_to_hex(*color)));
+
+        if let Ok(mut frame_visibility) = query_swatch_frames.get_mut(parent.get()) {
+            *frame_visibility = if color.is_some() { Visibility::Inherited } else { Visibility::Hidden };
+        }
+        let Some(color) = color else { continue; };
+        color_button.0 = color;
+
+        if *swatch == Swatch::Picked && is_picker_used && !is_selected {
+            commands.entity(entity).insert(SelectedColorButton);
+            selected_color.0 = color;
+        } else if is_selected && is_picker_used && *swatch != Swatch::Picked {
+            commands.entity(entity).remove::<SelectedColorButton>();
+        } else if is_selected {
+            selected_color.0 = color;
+        }
+    }
+}
+
+pub fn remember_recent_colors(
+    mut send_ball_change_events: EventReader<crate::query_server::SendBallChangeEvent>,
+    mut recent_colors: ResMut<RecentColors>,
+) {
+    for event in send_ball_change_events.read().filter(|event| event.ball.is_insert) {
+        let Some(color) = event.ball.color.as_ref().and_then(|hex_color| Color::hex(hex_color).ok()) else { continue; };
+        if recent_colors.0.first() == Some(&color) {
+            continue;
+        }
+        recent_colors.0.retain(|recent_color| *recent_color != color);
+        recent_colors.0.insert(0, color);
+        recent_colors.0.truncate(RECENT_COLORS);
+    }
+}
+
+pub fn receive_palette_event_listener(
+    mut events: EventReader<crate::query_server::ReceivedPaletteEvent>,
+    mut globe_palette: ResMut<GlobePalette>,
+) {
+    for event in events.read() {
+        if globe_palette.0 != event.palette {
+            globe_palette.0 = event.palette.clone();
+        }
+    }
+}
+
 pub fn delete_button_selector(
     mut commands: Commands,
     interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<DeleteButton>)>,
@@ -169,16 +246,378 @@ pub fn update_delete_button_appearance(
     }
 }
 
+pub fn link_button_selector(
+    mut commands: Commands,
+    interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<LinkButton>)>,
+    touch_input_query: Query<(Entity, &GlobalTransform, &Node), With<LinkButton>>,
+    mut touch_events: EventReader<TouchInput>,
+    mut selected_query: Query<Entity, (With<SelectedLinkButton>, With<LinkButton>)>,
+    mut selected_link: ResMut<SelectedLink>,
+) {
+    // Handle mouse interaction
+    for (entity, interaction) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            toggle_link_button(&mut commands, &mut selected_query, entity, &mut selected_link);
+        }
+    }
+
+    // Handle touch events
+    for touch in touch_events.read() {
+        if touch.phase == TouchPhase::Started {
+            for (entity, global_transform, node) in touch_input_query.iter() {
+                if is_touch_over_button(touch, global_transform, node) {
+                    toggle_link_button(&mut commands, &mut selected_query, entity, &mut selected_link);
+                }
+            }
+        }
+    }
+}
+
+fn toggle_link_button(
+    commands: &mut Commands,
+    selected_query: &mut Query<Entity, (With<SelectedLinkButton>, With<LinkButton>)>,
+    entity: Entity,
+    selected_link: &mut ResMut<SelectedLink>,
+) {
+    if let Ok(previous_entity) = selected_query.get_single_mut() {
+        commands.entity(previous_entity).remove::<SelectedLinkButton>();
+        selected_link.0 = false;
+    } else {
+        commands.entity(entity).insert(SelectedLinkButton);
+        selected_link.0 = true;
+    }
+}
+
+pub fn update_link_button_appearance(
+    mut query: Query<(&mut Style, Option<&SelectedLinkButton>), With<LinkButton>>,
+) {
+    for (mut style, selected) in query.iter_mut() {
+        if selected.is_some() {
+            // Change appearance to indicate selection
+            style.margin = UiRect::all(Val::Px(3.0));
+        } else {
+            // Revert to normal appearance
+            style.margin = UiRect::all(Val::Px(0.0));
+        }
+    }
+}
+
+pub fn annotation_button_selector(
+    mut commands: Commands,
+    interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<AnnotationButton>)>,
+    touch_input_query: Query<(Entity, &GlobalTransform, &Node), With<AnnotationButton>>,
+    mut touch_events: EventReader<TouchInput>,
+    mut selected_query: Query<Entity, (With<SelectedAnnotationButton>, With<AnnotationButton>)>,
+    mut selected_annotation: ResMut<SelectedAnnotation>,
+) {
+    // Handle mouse interaction
+    for (entity, interaction) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            toggle_annotation_button(&mut commands, &mut selected_query, entity, &mut selected_annotation);
+        }
+    }
+
+    // Handle touch events
+    for touch in touch_events.read() {
+        if touch.phase == TouchPhase::Started {
+            for (entity, global_transform, node) in touch_input_query.iter() {
+                if is_touch_over_button(touch, global_transform, node) {
+                    toggle_annotation_button(&mut commands, &mut selected_query, entity, &mut selected_annotation);
+                }
+            }
+        }
+    }
+}
+
+fn toggle_annotation_button(
+    commands: &mut Commands,
+    selected_query: &mut Query<Entity, (With<SelectedAnnotationButton>, With<AnnotationButton>)>,
+    entity: Entity,
+    selected_annotation: &mut ResMut<SelectedAnnotation>,
+) {
+    if let Ok(previous_entity) = selected_query.get_single_mut() {
+        commands.entity(previous_entity).remove::<SelectedAnnotationButton>();
+        selected_annotation.0 = false;
+    } else {
+        commands.entity(entity).insert(SelectedAnnotationButton);
+        selected_annotation.0 = true;
+    }
+}
+
+pub fn update_annotation_button_appearance(
+    mut query: Query<(&mut Style, Option<&SelectedAnnotationButton>), With<AnnotationButton>>,
+) {
+    for (mut style, selected) in query.iter_mut() {
+        if selected.is_some() {
+            // Change appearance to indicate selection
+            style.margin = UiRect::all(Val::Px(3.0));
+        } else {
+            // Revert to normal appearance
+            style.margin = UiRect::all(Val::Px(0.0));
+        }
+    }
+}
+
+pub fn brush_button_selector(
+    mut commands: Commands,
+    interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<BrushButton>)>,
+    touch_input_query: Query<(Entity, &GlobalTransform, &Node), With<BrushButton>>,
+    mut touch_events: EventReader<TouchInput>,
+    mut selected_query: Query<Entity, (With<SelectedBrushButton>, With<BrushButton>)>,
+    mut selected_brush: ResMut<SelectedBrush>,
+) {
+    // Handle mouse interaction
+    for (entity, interaction) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            toggle_brush_button(&mut commands, &mut selected_query, entity, &mut selected_brush);
+        }
+    }
+
+    // Handle touch events
+    for touch in touch_events.read() {
+        if touch.phase == TouchPhase::Started {
+            for (entity, global_transform, node) in touch_input_query.iter() {
+                if is_touch_over_button(touch, global_transform, node) {
+                    toggle_brush_button(&mut commands, &mut selected_query, entity, &mut selected_brush);
+                }
+            }
+        }
+    }
+}
+
+fn toggle_brush_button(
+    commands: &mut Commands,
+    selected_query: &mut Query<Entity, (With<SelectedBrushButton>, With<BrushButton>)>,
+    entity: Entity,
+    selected_brush: &mut ResMut<SelectedBrush>,
+) {
+    if let Ok(previous_entity) = selected_query.get_single_mut() {
+        commands.entity(previous_entity).remove::<SelectedBrushButton>();
+        selected_brush.0 = false;
+    } else {
+        commands.entity(entity).insert(SelectedBrushButton);
+        selected_brush.0 = true;
+    }
+}
+
+pub fn update_brush_button_appearance(
+    mut query: Query<(&mut Style, Option<&SelectedBrushButton>), With<BrushButton>>,
+) {
+    for (mut style, selected) in query.iter_mut() {
+        if selected.is_some() {
+            // Change appearance to indicate selection
+            style.margin = UiRect::all(Val::Px(3.0));
+        } else {
+            // Revert to normal appearance
+            style.margin = UiRect::all(Val::Px(0.0));
+        }
+    }
+}
+
+//Picking the selected line or circle tool again turns it off
+pub fn curve_button_selector(
+    interaction_query: Query<(&CurveButton, &Interaction), Changed<Interaction>>,
+    touch_input_query: Query<(&CurveButton, &GlobalTransform, &Node)>,
+    mut touch_events: EventReader<TouchInput>,
+    mut selected_curve: ResMut<SelectedCurve>,
+) {
+    // Handle mouse interaction
+    for (curve_button, interaction) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            toggle_curve_button(curve_button.0, &mut selected_curve);
+        }
+    }
+
+    // Handle touch events
+    for touch in touch_events.read() {
+        if touch.phase == TouchPhase::Started {
+            for (curve_button, global_transform, node) in touch_input_query.iter() {
+                if is_touch_over_button(touch, global_transform, node) {
+                    toggle_curve_button(curve_button.0, &mut selected_curve);
+                }
+            }
+        }
+    }
+}
+
+fn toggle_curve_button(tool: CurveTool, selected_curve: &mut ResMut<SelectedCurve>) {
+    if selected_curve.0 == Some(tool) {
+        selected_curve.0 = None;
+    } else {
+        selected_curve.0 = Some(tool);
+    }
+}
+
+pub fn update_curve_button_appearance(
+    mut query: Query<(&mut Style, &CurveButton)>,
+    selected_curve: Res<SelectedCurve>,
+) {
+    for (mut style, curve_button) in query.iter_mut() {
+        if selected_curve.0 == Some(curve_button.0) {
+            // Change appearance to indicate selection
+            style.margin = UiRect::all(Val::Px(3.0));
+        } else {
+            // Revert to normal appearance
+            style.margin = UiRect::all(Val::Px(0.0));
+        }
+    }
+}
+
+//Picking the selected cap eraser or lasso again turns it off
+pub fn area_button_selector(
+    interaction_query: Query<(&AreaButton, &Interaction), Changed<Interaction>>,
+    touch_input_query: Query<(&AreaButton, &GlobalTransform, &Node)>,
+    mut touch_events: EventReader<TouchInput>,
+    mut selected_area: ResMut<SelectedArea>,
+) {
+    // Handle mouse interaction
+    for (area_button, interaction) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            toggle_area_button(area_button.0, &mut selected_area);
+        }
+    }
+
+    // Handle touch events
+    for touch in touch_events.read() {
+        if touch.phase == TouchPhase::Started {
+            for (area_button, global_transform, node) in touch_input_query.iter() {
+                if is_touch_over_button(touch, global_transform, node) {
+                    toggle_area_button(area_button.0, &mut selected_area);
+                }
+            }
+        }
+    }
+}
+
+fn toggle_area_button(tool: AreaTool, selected_area: &mut ResMut<SelectedArea>) {
+    if selected_area.0 == Some(tool) {
+        selected_area.0 = None;
+    } else {
+        selected_area.0 = Some(tool);
+    }
+}
+
+pub fn update_area_button_appearance(
+    mut query: Query<(&mut Style, &AreaButton)>,
+    selected_area: Res<SelectedArea>,
+) {
+    for (mut style, area_button) in query.iter_mut() {
+        if selected_area.0 == Some(area_button.0) {
+            // Change appearance to indicate selection
+            style.margin = UiRect::all(Val::Px(3.0));
+        } else {
+            // Revert to normal appearance
+            style.margin = UiRect::all(Val::Px(0.0));
+        }
+    }
+}
+
+pub fn selection_panel_button_selector(
+    delete_interaction_query: Query<&Interaction, (Changed<Interaction>, With<DeleteSelectionButton>)>,
+    undo_interaction_query: Query<&Interaction, (Changed<Interaction>, With<UndoDeleteSelectionButton>)>,
+    touch_input_query: Query<(&GlobalTransform, &Node, &InheritedVisibility, Has<DeleteSelectionButton>), Or<(With<DeleteSelectionButton>, With<UndoDeleteSelectionButton>)>>,
+    mut touch_events: EventReader<TouchInput>,
+    mut delete_selection_events: EventWriter<crate::ball::events::DeleteSelectionEvent>,
+    mut undo_events: EventWriter<crate::ball::events::UndoDeleteSelectionEvent>,
+) {
+    let mut delete_pressed = delete_interaction_query.iter().any(|interaction| *interaction == Interaction::Pressed);
+    let mut undo_pressed = undo_interaction_query.iter().any(|interaction| *interaction == Interaction::Pressed);
+
+    // Handle touch events, only while the panel is shown
+    for touch in touch_events.read() {
+        if touch.phase == TouchPhase::Started {
+            for (global_transform, node, visibility, is_delete_button) in touch_input_query.iter() {
+                if visibility.get() && is_touch_over_button(touch, global_transform, node) {
+                    if is_delete_button {
+                        delete_pressed = true;
+                    } else {
+                        undo_pressed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    if delete_pressed {
+        delete_selection_events.send(crate::ball::events::DeleteSelectionEvent);
+    }
+    if undo_pressed {
+        undo_events.send(crate::ball::events::UndoDeleteSelectionEvent);
+    }
+}
+
+//The panel is shown while the cap eraser or the lasso is in use
+pub fn update_selection_panel(
+    selected_area: Res<SelectedArea>,
+    area_selection: Res<crate::ball::resources::AreaSelection>,
+    deleted_selections: Res<crate::ball::resources::DeletedSelections>,
+    mut query_panel: Query<&mut Visibility, With<SelectionPanel>>,
+    mut query_text: Query<&mut Text, With<SelectionCountText>>,
+) {
+    if !selected_area.is_changed() && !area_selection.is_changed() && !deleted_selections.is_changed() {
+        return;
+    }
+    for mut visibility in query_panel.iter_mut() {
+        *visibility = if selected_area.0.is_some() { Visibility::Visible } else { Visibility::Hidden };
+    }
+    for mut text in query_text.iter_mut() {
+        text.sections[0].value = format!("{} selected", area_selection.selected.len());
+    }
+}
+
+//The create button opens and closes the template chooser, picking a template creates the globe
 pub fn create_new_globe_button_selector(
     interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<CreateNewGlobeButton>)>,
     touch_input_query: Query<(Entity, &GlobalTransform, &Node), With<CreateNewGlobeButton>>,
+    template_interaction_query: Query<(&TemplateButton, &Interaction), Changed<Interaction>>,
+    template_touch_input_query: Query<(&TemplateButton, &GlobalTransform, &Node, &InheritedVisibility)>,
+    mut query_chooser: Query<&mut Visibility, With<TemplateChooser>>,
     mut touch_events: EventReader<TouchInput>,
     mut send_create_new_globe_event: EventWriter<crate::query_server::SendCreateNewGlobeEvent>,
+) {
+    // Handle mouse interaction
+    let mut toggle_pressed = interaction_query.iter().any(|(_entity, interaction)| *interaction == Interaction::Pressed);
+    let mut picked_template = template_interaction_query.iter()
+        .find(|(_template_button, interaction)| **interaction == Interaction::Pressed)
+        .map(|(template_button, _interaction)| template_button.0);
+
+    // Handle touch events, templates only while the chooser is shown
+    for touch in touch_events.read() {
+        if touch.phase == TouchPhase::Started {
+            for (_entity, global_transform, node) in touch_input_query.iter() {
+                if is_touch_over_button(touch, global_transform, node) {
+                    toggle_pressed = true;
+                }
+            }
+            for (template_button, global_transform, node, visibility) in template_touch_input_query.iter() {
+                if visibility.get() && is_touch_over_button(touch, global_transform, node) {
+                    picked_template = Some(template_button.0);
+                }
+            }
+        }
+    }
+
+    if let Some(template) = picked_template {
+        send_create_new_globe_event.send(crate::query_server::SendCreateNewGlobeEvent { template });
+    }
+    if toggle_pressed || picked_template.is_some() {
+        for mut visibility in query_chooser.iter_mut() {
+            *visibility = if toggle_pressed && *visibility == Visibility::Hidden { Visibility::Visible } else { Visibility::Hidden };
+        }
+    }
+}
+
+//Copies the alive set of the globe into a new globe and goes there
+pub fn fork_globe_button_selector(
+    interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<ForkGlobeButton>)>,
+    touch_input_query: Query<(Entity, &GlobalTransform, &Node), With<ForkGlobeButton>>,
+    mut touch_events: EventReader<TouchInput>,
+    mut send_fork_globe_event: EventWriter<crate::query_server::SendForkGlobeEvent>,
 ) {
     // Handle mouse interaction
     for (_entity, interaction) in interaction_query.iter() {
         if *interaction == Interaction::Pressed {
-            send_create_new_globe_event.send(crate::query_server::SendCreateNewGlobeEvent);
+            send_fork_globe_event.send(crate::query_server::SendForkGlobeEvent);
         }
     }
 
@@ -187,7 +626,7 @@ pub fn create_new_globe_button_selector(
         if touch.phase == TouchPhase::Started {
             for (_entity, global_transform, node) in touch_input_query.iter() {
                 if is_touch_over_button(touch, global_transform, node) {
-                    send_create_new_globe_event.send(crate::query_server::SendCreateNewGlobeEvent);
+                    send_fork_globe_event.send(crate::query_server::SendForkGlobeEvent);
                 }
             }
         }
@@ -306,3 +745,336 @@ pub fn update_info_button_appearance(
         }
     }
 }
+
+pub fn settings_button_selector(
+    mut commands: Commands,
+    interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<SettingsButton>)>,
+    touch_input_query: Query<(Entity, &GlobalTransform, &Node), With<SettingsButton>>,
+    mut touch_events: EventReader<TouchInput>,
+    mut selected_query: Query<Entity, (With<SelectedSettingsButton>, With<SettingsButton>)>,
+    mut query_settings_panel: Query<&mut Visibility, With<SettingsPanel>>,
+) {
+    // Handle mouse interaction
+    for (entity, interaction) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            toggle_settings_button(&mut commands, &mut selected_query, entity, &mut query_settings_panel);
+        }
+    }
+
+    // Handle touch events
+    for touch in touch_events.read() {
+        if touch.phase == TouchPhase::Started {
+            for (entity, global_transform, node) in touch_input_query.iter() {
+                if is_touch_over_button(touch, global_transform, node) {
+                    toggle_settings_button(&mut commands, &mut selected_query, entity, &mut query_settings_panel);
+                }
+            }
+        }
+    }
+}
+
+fn toggle_settings_button(
+    commands: &mut Commands,
+    selected_query: &mut Query<Entity, (With<SelectedSettingsButton>, With<SettingsButton>)>,
+    entity: Entity,
+    query_settings_panel: &mut Query<&mut Visibility, With<SettingsPanel>>,
+) {
+    let visibility = if let Ok(previous_entity) = selected_query.get_single_mut() {
+        commands.entity(previous_entity).remove::<SelectedSettingsButton>();
+        Visibility::Hidden
+    } else {
+        commands.entity(entity).insert(SelectedSettingsButton);
+        Visibility::Visible
+    };
+    for mut panel_visibility in query_settings_panel.iter_mut() {
+        *panel_visibility = visibility;
+    }
+}
+
+pub fn update_settings_button_appearance(
+    mut query: Query<(&mut Style, Option<&SelectedSettingsButton>), With<SettingsButton>>,
+) {
+    for (mut style, selected) in query.iter_mut() {
+        if selected.is_some() {
+            // Change appearance to indicate selection
+            style.margin = UiRect::all(Val::Px(3.0));
+        } else {
+            // Revert to normal appearance
+            style.margin = UiRect::all(Val::Px(0.0));
+        }
+    }
+}
+
+//Sends the changed profile to the server, it is used when the server answers so all viewers switch at the same time.
+//The same goes for the palette.
+pub fn physics_setting_button_selector(
+    interaction_query: Query<(&PhysicsSettingButton, &Interaction), Changed<Interaction>>,
+    touch_input_query: Query<(&PhysicsSettingButton, &GlobalTransform, &Node, &InheritedVisibility)>,
+    mut touch_events: EventReader<TouchInput>,
+    physics_profile: Res<PhysicsProfile>,
+    mut selected_body: ResMut<SelectedBody>,
+    mut selected_appearance: ResMut<SelectedAppearance>,
+    mut send_physics_profile_event: EventWriter<crate::query_server::SendPhysicsProfileEvent>,
+    mut color_picker: ResMut<ColorPicker>,
+    globe_palette: Res<GlobePalette>,
+    selected_color: Res<SelectedColor>,
+    mut send_palette_event: EventWriter<crate::query_server::SendPaletteEvent>,
+    mut symmetry: ResMut<Symmetry>,
+    mut snap_grid: ResMut<SnapGrid>,
+    globe_surface: Res<GlobeSurface>,
+    mut send_surface_event: EventWriter<crate::query_server::SendSurfaceEvent>,
+) {
+    // Handle mouse interaction
+    let mut pressed_buttons: Vec<&PhysicsSettingButton> = interaction_query.iter()
+        .filter(|(_, interaction)| **interaction == Interaction::Pressed)
+        .map(|(physics_setting_button, _)| physics_setting_button)
+        .collect();
+
+    // Handle touch events, only while the settings panel is shown
+    for touch in touch_events.read() {
+        if touch.phase == TouchPhase::Started {
+            for (physics_setting_button, global_transform, node, visibility) in touch_input_query.iter() {
+                if visibility.get() && is_touch_over_button(touch, global_transform, node) {
+                    pressed_buttons.push(physics_setting_button);
+                }
+            }
+        }
+    }
+
+    for physics_setting_button in pressed_buttons {
+        match physics_setting_button.setting {
+            PhysicsSetting::Hue | PhysicsSetting::Saturation | PhysicsSetting::Value => {
+                change_color_picker(physics_setting_button, &mut color_picker);
+            },
+            PhysicsSetting::BallLook => {
+                //Next look, or the first one if the look is not one of them
+                let look_index = BALL_LOOKS.iter().position(|(_, appearance)| *appearance == selected_appearance.0);
+                selected_appearance.0 = BALL_LOOKS[look_index.map_or(0, |index| (index + 1) % BALL_LOOKS.len())].1;
+            },
+            PhysicsSetting::InPalette | PhysicsSetting::PaletteEnforced => {
+                change_palette(physics_setting_button, &globe_palette, &selected_color, &mut send_palette_event);
+            },
+            PhysicsSetting::Symmetry | PhysicsSetting::SymmetryFolds | PhysicsSetting::SymmetryAxis => {
+                change_symmetry(physics_setting_button, &mut symmetry);
+            },
+            PhysicsSetting::Snap | PhysicsSetting::SnapSubdivisions | PhysicsSetting::SnapStep => {
+                change_snap_grid(physics_setting_button, &mut snap_grid);
+            },
+            PhysicsSetting::Surface => {
+                change_surface(&globe_surface, &mut send_surface_event);
+            },
+            _ => change_physics_setting(physics_setting_button, &physics_profile, &mut selected_body, &mut send_physics_profile_event),
+        }
+    }
+}
+
+fn change_color_picker(
+    physics_setting_button: &PhysicsSettingButton,
+    color_picker: &mut ResMut<ColorPicker>,
+) {
+    let change = |value: f32| ((value + physics_setting_button.delta) * 10.0).round().clamp(0.0, 10.0) / 10.0;
+    match physics_setting_button.setting {
+        PhysicsSetting::Hue => color_picker.hue = (color_picker.hue + physics_setting_button.delta).rem_euclid(360.0),
+        PhysicsSetting::Saturation => color_picker.saturation = change(color_picker.saturation),
+        PhysicsSetting::Value => color_picker.value = change(color_picker.value),
+        _ => {},
+    }
+}
+
+fn change_symmetry(
+    physics_setting_button: &PhysicsSettingButton,
+    symmetry: &mut ResMut<Symmetry>,
+) {
+    match physics_setting_button.setting {
+        PhysicsSetting::Symmetry => {
+            symmetry.mode = match symmetry.mode {
+                SymmetryMode::Off => SymmetryMode::Mirror,
+                SymmetryMode::Mirror => SymmetryMode::Rotation,
+                SymmetryMode::Rotation => SymmetryMode::Off,
+            };
+        },
+        PhysicsSetting::SymmetryFolds => {
+            let folds = symmetry.folds as f32 + physics_setting_button.delta;
+            symmetry.folds = (folds as u32).clamp(MIN_SYMMETRY_FOLDS, MAX_SYMMETRY_FOLDS);
+        },
+        PhysicsSetting::SymmetryAxis => {
+            symmetry.axis = match symmetry.axis {
+                SymmetryAxis::X => SymmetryAxis::Y,
+                SymmetryAxis::Y => SymmetryAxis::Z,
+                SymmetryAxis::Z => SymmetryAxis::X,
+            };
+        },
+        _ => {},
+    }
+}
+
+//Next texture, the uploaded one only once an image has been uploaded.
+//The globe changes when the server answers.
+fn change_surface(
+    globe_surface: &GlobeSurface,
+    send_surface_event: &mut EventWriter<crate::query_server::SendSurfaceEvent>,
+) {
+    let has_image = globe_surface.surface.image_version.is_some();
+    let texture = match globe_surface.surface.texture {
+        SurfaceTextureDto::Plain => SurfaceTextureDto::Earth,
+        SurfaceTextureDto::Earth => SurfaceTextureDto::Grid,
+        SurfaceTextureDto::Grid => SurfaceTextureDto::Checkerboard,
+        SurfaceTextureDto::Checkerboard if has_image => SurfaceTextureDto::Uploaded,
+        SurfaceTextureDto::Checkerboard | SurfaceTextureDto::Uploaded => SurfaceTextureDto::Plain,
+    };
+    send_surface_event.send(crate::query_server::SendSurfaceEvent {
+        surface: SurfaceDto { texture, ..globe_surface.surface.clone() },
+    });
+}
+
+fn change_snap_grid(
+    physics_setting_button: &PhysicsSettingButton,
+    snap_grid: &mut ResMut<SnapGrid>,
+) {
+    match physics_setting_button.setting {
+        PhysicsSetting::Snap => {
+            snap_grid.mode = match snap_grid.mode {
+                SnapMode::Off => SnapMode::Geodesic,
+                SnapMode::Geodesic => SnapMode::LatLong,
+                SnapMode::LatLong => SnapMode::Off,
+            };
+        },
+        PhysicsSetting::SnapSubdivisions => {
+            let subdivisions = snap_grid.subdivisions as f32 + physics_setting_button.delta;
+            snap_grid.subdivisions = (subdivisions.max(0.0) as u32).min(MAX_SNAP_SUBDIVISIONS);
+        },
+        PhysicsSetting::SnapStep => {
+            snap_grid.step_degrees = (snap_grid.step_degrees + physics_setting_button.delta).clamp(MIN_SNAP_STEP_DEGREES, MAX_SNAP_STEP_DEGREES);
+        },
+        _ => {},
+    }
+}
+
+//In palette adds the selected color to the palette of the globe, or removes it if it is there
+fn change_palette(
+    physics_setting_button: &PhysicsSettingButton,
+    globe_palette: &GlobePalette,
+    selected_color: &SelectedColor,
+    send_palette_event: &mut EventWriter<crate::query_server::SendPaletteEvent>,
+) {
+    let mut new_palette = globe_palette.0.clone();
+    match physics_setting_button.setting {
+        PhysicsSetting::InPalette => {
+            let hex_color = color_to_hex(selected_color.0);
+            if new_palette.contains(&hex_color) {
+                new_palette.colors.retain(|palette_color| !palette_color.eq_ignore_ascii_case(&hex_color));
+                // An enforced palette can not be empty
+                new_palette.is_enforced &= !new_palette.colors.is_empty();
+            } else if new_palette.colors.len() < MAX_PALETTE_COLORS {
+                new_palette.colors.push(hex_color);
+            } else {
+                return;
+            }
+        },
+        PhysicsSetting::PaletteEnforced if !new_palette.colors.is_empty() => new_palette.is_enforced = !new_palette.is_enforced,
+        _ => return,
+    }
+    send_palette_event.send(crate::query_server::SendPaletteEvent { palette: new_palette });
+}
+
+fn change_physics_setting(
+    physics_setting_button: &PhysicsSettingButton,
+    physics_profile: &PhysicsProfile,
+    selected_body: &mut ResMut<SelectedBody>,
+    send_physics_profile_event: &mut EventWriter<crate::query_server::SendPhysicsProfileEvent>,
+) {
+    let mut new_physics_profile = physics_profile.latest().clone();
+    // Rounded so repeated steps do not drift, and kept within what the server accepts
+    let change = |value: f32, max: f32| ((value + physics_setting_button.delta) * 10.0).round().clamp(0.0, max * 10.0) / 10.0;
+    let change_radius = |value: f32, min: f32, max: f32| ((value + physics_setting_button.delta) * 1000.0).round().clamp(min * 1000.0, max * 1000.0) / 1000.0;
+    match physics_setting_button.setting {
+        PhysicsSetting::BallRadius => {
+            selected_body.0.radius = change_radius(selected_body.0.radius, new_physics_profile.min_ball_radius, new_physics_profile.max_ball_radius);
+            return;
+        },
+        PhysicsSetting::BallShape => {
+            selected_body.0.shape = match selected_body.0.shape {
+                ShapeDto::Sphere => ShapeDto::Cube,
+                ShapeDto::Cube => ShapeDto::Capsule,
+                ShapeDto::Capsule => ShapeDto::Sphere,
+            };
+            return;
+        },
+        PhysicsSetting::MinBallRadius => new_physics_profile.min_ball_radius = change_radius(new_physics_profile.min_ball_radius, MIN_BALL_RADIUS, new_physics_profile.max_ball_radius),
+        PhysicsSetting::MaxBallRadius => new_physics_profile.max_ball_radius = change_radius(new_physics_profile.max_ball_radius, new_physics_profile.min_ball_radius, MAX_BALL_RADIUS),
+        PhysicsSetting::Gravity => new_physics_profile.gravity = change(new_physics_profile.gravity, MAX_GRAVITY),
+        PhysicsSetting::Restitution => new_physics_profile.restitution = change(new_physics_profile.restitution, MAX_RESTITUTION),
+        PhysicsSetting::LinearDamping => new_physics_profile.linear_damping = change(new_physics_profile.linear_damping, MAX_LINEAR_DAMPING),
+        PhysicsSetting::PreserveSpeed => new_physics_profile.preserve_speed = !new_physics_profile.preserve_speed,
+        _ => return,
+    }
+    // New balls stay within the new bounds
+    selected_body.0.radius = selected_body.0.radius.clamp(new_physics_profile.min_ball_radius, new_physics_profile.max_ball_radius);
+    send_physics_profile_event.send(crate::query_server::SendPhysicsProfileEvent { physics_profile: new_physics_profile });
+}
+
+pub fn update_physics_setting_texts(
+    physics_profile: Res<PhysicsProfile>,
+    selected_body: Res<SelectedBody>,
+    selected_appearance: Res<SelectedAppearance>,
+    color_picker: Res<ColorPicker>,
+    globe_palette: Res<GlobePalette>,
+    selected_color: Res<SelectedColor>,
+    symmetry: Res<Symmetry>,
+    snap_grid: Res<SnapGrid>,
+    globe_surface: Res<GlobeSurface>,
+    mut query_texts: Query<(&PhysicsSettingText, &mut Text)>,
+) {
+    if !physics_profile.is_changed() && !selected_body.is_changed() && !selected_appearance.is_changed() && !color_picker.is_changed()
+        && !globe_palette.is_changed() && !selected_color.is_changed() && !symmetry.is_changed() && !snap_grid.is_changed() && !globe_surface.is_changed() {
+        return;
+    }
+    let on_off = |is_on: bool| if is_on { "On".to_string() } else { "Off".to_string() };
+    let latest = physics_profile.latest();
+    for (physics_setting_text, mut text) in query_texts.iter_mut() {
+        text.sections[0].value = match physics_setting_text.0 {
+            PhysicsSetting::Gravity => format!("{:.1}", latest.gravity),
+            PhysicsSetting::Restitution => format!("{:.1}", latest.restitution),
+            PhysicsSetting::LinearDamping => format!("{:.1}", latest.linear_damping),
+            PhysicsSetting::PreserveSpeed => on_off(latest.preserve_speed),
+            PhysicsSetting::MinBallRadius => format!("{:.3}", latest.min_ball_radius),
+            PhysicsSetting::MaxBallRadius => format!("{:.3}", latest.max_ball_radius),
+            PhysicsSetting::BallRadius => format!("{:.3}", selected_body.0.radius),
+            PhysicsSetting::BallShape => match selected_body.0.shape {
+                ShapeDto::Sphere => "Sphere".to_string(),
+                ShapeDto::Cube => "Cube".to_string(),
+                ShapeDto::Capsule => "Capsule".to_string(),
+            },
+            PhysicsSetting::BallLook => BALL_LOOKS.iter()
+                .find(|(_, appearance)| *appearance == selected_appearance.0)
+                .map_or("Custom", |(name, _)| name)
+                .to_string(),
+            PhysicsSetting::Hue => format!("{:.0}", color_picker.hue),
+            PhysicsSetting::Saturation => format!("{:.1}", color_picker.saturation),
+            PhysicsSetting::Value => format!("{:.1}", color_picker.value),
+            PhysicsSetting::InPalette => if globe_palette.0.contains(&color_to_hex(selected_color.0)) { "Yes".to_string() } else { "No".to_string() },
+            PhysicsSetting::PaletteEnforced => on_off(globe_palette.0.is_enforced),
+            PhysicsSetting::Symmetry => match symmetry.mode {
+                SymmetryMode::Off => "Off".to_string(),
+                SymmetryMode::Mirror => "Mirror".to_string(),
+                SymmetryMode::Rotation => format!("{}-fold", symmetry.folds),
+            },
+            PhysicsSetting::SymmetryFolds => symmetry.folds.to_string(),
+            PhysicsSetting::SymmetryAxis => format!("{:?}", symmetry.axis),
+            PhysicsSetting::Snap => match snap_grid.mode {
+                SnapMode::Off => "Off".to_string(),
+                SnapMode::Geodesic => "Geodesic".to_string(),
+                SnapMode::LatLong => "Lat/long".to_string(),
+            },
+            PhysicsSetting::SnapSubdivisions => snap_grid.subdivisions.to_string(),
+            PhysicsSetting::SnapStep => format!("{:.0}", snap_grid.step_degrees),
+            PhysicsSetting::Surface => match globe_surface.surface.texture {
+                SurfaceTextureDto::Plain => "Plain".to_string(),
+                SurfaceTextureDto::Earth => "Earth".to_string(),
+                SurfaceTextureDto::Grid => "Grid".to_string(),
+                SurfaceTextureDto::Checkerboard => "Checkerboard".to_string(),
+                SurfaceTextureDto::Uploaded => "Uploaded".to_string(),
+            },
+        };
+    }
+}
@@ -0,0 +1,128 @@
+use crate::domain::models::ball_entity::{BallEntity, PositionEntity, ImpulseEntity};
+use shared::domain::dtos::body_dto::DEFAULT_BALL_RADIUS;
+use shared::domain::dtos::geo_position_dto::GeoPositionDto;
+use shared::domain::dtos::globe_template_dto::GlobeTemplateDto;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use uuid::Uuid;
+
+const TEMPLATE_COLORS: [&str; 6] = ["#e6194bff", "#3cb44bff", "#ffe119ff", "#4363d8ff", "#f58231ff", "#911eb4ff"];
+const ORBIT_IMPULSE: f32 = 0.3;
+const GRID_STEP_DEGREES: f32 = 30.0;
+const SCATTER_BALLS: usize = 80;
+// Scattered balls keep some space between them so they are easy to pick
+const SCATTER_MIN_DISTANCE: f32 = 3.0 * DEFAULT_BALL_RADIUS;
+
+// Inserts that seed a new globe, in the order they are stored. Every call makes new uuids.
+pub fn template_balls(template: GlobeTemplateDto) -> Vec<BallEntity> {
+    match template {
+        GlobeTemplateDto::Empty => Vec::new(),
+        GlobeTemplateDto::Orbit => orbit_balls(),
+        GlobeTemplateDto::Grid => grid_balls(),
+        GlobeTemplateDto::Scatter => scatter_balls(),
+    }
+}
+
+fn fixed_ball(latitude: f32, longitude: f32, color: &str) -> BallEntity {
+    let mut ball_entity = BallEntity::new(Uuid::new_v4(), true);
+    ball_entity.is_fixed = true;
+    ball_entity.color = Some(color.to_string());
+    ball_entity.position = Some(resting_position(latitude, longitude));
+    ball_entity
+}
+
+// A ball with the default radius resting on the surface
+fn resting_position(latitude: f32, longitude: f32) -> PositionEntity {
+    let geo_position = GeoPositionDto { latitude, longitude, altitude: None };
+    let position = geo_position.to_position(DEFAULT_BALL_RADIUS).expect("Template latitudes are within -90 and 90");
+    PositionEntity { x: position.x, y: position.y, z: position.z }
+}
+
+// Moving balls leave the equator eastward, some of them tilted toward the north, between fixed balls at the poles
+fn orbit_balls() -> Vec<BallEntity> {
+    let mut ball_entities = vec![fixed_ball(90.0, 0.0, TEMPLATE_COLORS[2]), fixed_ball(-90.0, 0.0, TEMPLATE_COLORS[2])];
+    for index in 0..12 {
+        let longitude = index as f32 * 30.0;
+        let tilt = if index % 2 == 0 { 0.0 } else { 45.0_f32.to_radians() };
+        let (sin_longitude, cos_longitude) = longitude.to_radians().sin_cos();
+        // East and north on the equator
+        let east = [-sin_longitude, 0.0, -cos_longitude];
+        let north = [0.0, 1.0, 0.0];
+        let mut ball_entity = BallEntity::new(Uuid::new_v4(), true);
+        ball_entity.color = Some(TEMPLATE_COLORS[if index % 2 == 0 { 3 } else { 0 }].to_string());
+        ball_entity.position = Some(resting_position(0.0, longitude));
+        ball_entity.impulse = Some(ImpulseEntity {
+            x: ORBIT_IMPULSE * (tilt.cos() * east[0] + tilt.sin() * north[0]),
+            y: ORBIT_IMPULSE * (tilt.cos() * east[1] + tilt.sin() * north[1]),
+            z: ORBIT_IMPULSE * (tilt.cos() * east[2] + tilt.sin() * north[2]),
+        });
+        ball_entities.push(ball_entity);
+    }
+    ball_entities
+}
+
+// One ball at each pole and one at every grid crossing in between, a color per latitude
+fn grid_balls() -> Vec<BallEntity> {
+    let mut ball_entities = vec![fixed_ball(90.0, 0.0, TEMPLATE_COLORS[0]), fixed_ball(-90.0, 0.0, TEMPLATE_COLORS[0])];
+    let rows = (180.0 / GRID_STEP_DEGREES) as usize - 1;
+    let columns = (360.0 / GRID_STEP_DEGREES) as usize;
+    for row in 1..=rows {
+        let latitude = 90.0 - row as f32 * GRID_STEP_DEGREES;
+        for column in 0..columns {
+            let longitude = column as f32 * GRID_STEP_DEGREES - 180.0;
+            ball_entities.push(fixed_ball(latitude, longitude, TEMPLATE_COLORS[row % TEMPLATE_COLORS.len()]));
+        }
+    }
+    ball_entities
+}
+
+// Evenly spread over the surface, places too close to an earlier ball are skipped
+fn scatter_balls() -> Vec<BallEntity> {
+    let mut rng = rand::thread_rng();
+    let mut ball_entities: Vec<BallEntity> = Vec::with_capacity(SCATTER_BALLS);
+    for _ in 0..SCATTER_BALLS * 10 {
+        if ball_entities.len() == SCATTER_BALLS {
+            break;
+        }
+        let latitude = rng.gen_range(-1.0_f32..1.0).asin().to_degrees();
+        let longitude = rng.gen_range(-180.0..180.0);
+        let position = resting_position(latitude, longitude);
+        let is_too_close = ball_entities.iter()
+            .filter_map(|ball_entity| ball_entity.position.as_ref())
+            .any(|other| position.distance_squared(other) < SCATTER_MIN_DISTANCE * SCATTER_MIN_DISTANCE);
+        if !is_too_close {
+            let color = TEMPLATE_COLORS.choose(&mut rng).unwrap_or(&TEMPLATE_COLORS[0]);
+            ball_entities.push(fixed_ball(latitude, longitude, color));
+        }
+    }
+    ball_entities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::application::services::validation_service::ValidationService;
+    use crate::domain::models::physics_profile_entity::PhysicsProfileEntity;
+    use crate::domain::models::palette_entity::PaletteEntity;
+
+    #[test]
+    fn test_templates_are_valid_on_a_new_globe() {
+        let validation_service = ValidationService::new();
+        for template in GlobeTemplateDto::ALL {
+            let ball_entities = template_balls(template);
+            let results = validation_service.validate_batch_against(&ball_entities, HashMap::new(), &PhysicsProfileEntity::default(), &PaletteEntity::default());
+            for result in results {
+                assert!(result.is_ok(), "{:?}: {:?}", template, result);
+            }
+        }
+    }
+
+    #[test]
+    fn test_template_sizes() {
+        assert!(template_balls(GlobeTemplateDto::Empty).is_empty());
+        assert_eq!(template_balls(GlobeTemplateDto::Grid).len(), 2 + 5 * 12);
+        assert_eq!(template_balls(GlobeTemplateDto::Orbit).iter().filter(|ball_entity| !ball_entity.is_fixed).count(), 12);
+        assert!(template_balls(GlobeTemplateDto::Scatter).len() > SCATTER_BALLS / 2);
+    }
+}
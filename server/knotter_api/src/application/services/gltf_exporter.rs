@@ -0,0 +1,325 @@
+use crate::domain::errors::my_error::MyError;
+use crate::domain::models::ball_entity::{BallEntity, ShapeEntity, AppearanceEntity};
+use crate::application::services::validation::ball_position_validator::GLOBE_RADIUS;
+use shared::domain::dtos::body_dto::{CUBE_HALF_SIZE, CAPSULE_RADIUS, CAPSULE_HALF_HEIGHT};
+use shared::domain::dtos::appearance_dto::DEFAULT_ROUGHNESS;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+pub const GLB_CONTENT_TYPE: &str = "model/gltf-binary";
+
+const GLB_MAGIC: &[u8] = b"glTF";
+const GLB_VERSION: u32 = 2;
+const CHUNK_JSON: &[u8] = b"JSON";
+const CHUNK_BIN: &[u8] = b"BIN\0";
+// glTF component types and buffer view targets
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const SECTORS: usize = 32;
+const STACKS: usize = 16;
+const GLOBE_SECTORS: usize = 72;
+const GLOBE_STACKS: usize = 36;
+
+struct Geometry {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    indices: Vec<u32>,
+}
+
+// A .glb with the globe and one node per ball, scaled from shapes with radius 1 as the client draws them.
+// Balls keep their uuid and physics in the extras of their node, links and annotations are in the extras of the scene.
+pub fn export_glb(globe_id: &str, objects: &[BallEntity]) -> Result<Vec<u8>, MyError> {
+    let mut builder = GltfBuilder::default();
+    let globe_material = builder.add_material(json!({
+        "name": "globe",
+        "pbrMetallicRoughness": { "baseColorFactor": [0.0, 0.0, 0.0, 1.0], "metallicFactor": 0.0, "roughnessFactor": DEFAULT_ROUGHNESS },
+    }));
+    let globe_geometry = builder.add_geometry(&lathe(&sphere_profile(GLOBE_STACKS, GLOBE_RADIUS, 0.0), GLOBE_SECTORS));
+    let globe_mesh = builder.add_mesh("globe", globe_geometry, globe_material);
+    let mut nodes = vec![json!({ "name": "globe", "mesh": globe_mesh })];
+
+    let mut shape_geometries: HashMap<ShapeEntity, usize> = HashMap::new();
+    let mut materials: HashMap<String, usize> = HashMap::new();
+    let mut meshes: HashMap<(ShapeEntity, usize), usize> = HashMap::new();
+    let mut links = Vec::new();
+    let mut annotations = Vec::new();
+    for object in objects {
+        let color = object.color.clone().unwrap_or_default();
+        if let Some(link) = &object.link {
+            links.push(json!({ "uuid": object.uuid, "from_uuid": link.from_uuid, "to_uuid": link.to_uuid, "color": color }));
+            continue;
+        }
+        let Some(position) = &object.position else { continue; };
+        if let Some(annotation) = &object.annotation {
+            annotations.push(json!({
+                "uuid": object.uuid,
+                "text": annotation.text,
+                "font_size": annotation.font_size,
+                "color": color,
+                "position": [position.x, position.y, position.z],
+            }));
+            continue;
+        }
+
+        let shape = object.body.as_ref().map_or(ShapeEntity::Sphere, |body| body.shape);
+        let geometry = *shape_geometries.entry(shape)
+            .or_insert_with(|| builder.add_geometry(&shape_geometry(shape)));
+        let material_key = format!("{}{:?}", color, object.appearance);
+        let material = *materials.entry(material_key)
+            .or_insert_with(|| builder.add_material(ball_material(&color, object.appearance.as_ref())));
+        let mesh = *meshes.entry((shape, material))
+            .or_insert_with(|| builder.add_mesh(&format!("{:?}", shape).to_lowercase(), geometry, material));
+
+        let radius = object.radius();
+        let mut extras = json!({ "uuid": object.uuid, "is_fixed": object.is_fixed, "color": color });
+        if let Some(impulse) = &object.impulse {
+            extras["impulse"] = json!([impulse.x, impulse.y, impulse.z]);
+        }
+        if let Some(mass) = object.body.as_ref().and_then(|body| body.mass) {
+            extras["mass"] = json!(mass);
+        }
+        nodes.push(json!({
+            "name": object.uuid,
+            "mesh": mesh,
+            "translation": [position.x, position.y, position.z],
+            "scale": [radius, radius, radius],
+            "extras": extras,
+        }));
+    }
+
+    let scene_extras = json!({ "globe_id": globe_id, "links": links, "annotations": annotations });
+    builder.into_glb(nodes, scene_extras)
+}
+
+#[derive(Default)]
+struct GltfBuilder {
+    buffer: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    materials: Vec<Value>,
+    meshes: Vec<Value>,
+    // Accessors of the positions, normals and indices of each geometry
+    geometries: Vec<(usize, usize, usize)>,
+    uses_emissive_strength: bool,
+}
+
+impl GltfBuilder {
+    fn add_material(&mut self, material: Value) -> usize {
+        self.uses_emissive_strength |= material.pointer("/extensions/KHR_materials_emissive_strength").is_some();
+        self.materials.push(material);
+        self.materials.len() - 1
+    }
+
+    fn add_geometry(&mut self, geometry: &Geometry) -> usize {
+        let (min, max) = geometry.positions.iter().fold(([f32::MAX; 3], [f32::MIN; 3]), |(min, max), position| {
+            ([min[0].min(position[0]), min[1].min(position[1]), min[2].min(position[2])],
+             [max[0].max(position[0]), max[1].max(position[1]), max[2].max(position[2])])
+        });
+        let positions = self.add_accessor(geometry.positions.iter().flatten().flat_map(|value| value.to_le_bytes()).collect(), ARRAY_BUFFER,
+            json!({ "componentType": FLOAT, "count": geometry.positions.len(), "type": "VEC3", "min": min, "max": max }));
+        let normals = self.add_accessor(geometry.normals.iter().flatten().flat_map(|value| value.to_le_bytes()).collect(), ARRAY_BUFFER,
+            json!({ "componentType": FLOAT, "count": geometry.normals.len(), "type": "VEC3" }));
+        let indices = self.add_accessor(geometry.indices.iter().flat_map(|index| index.to_le_bytes()).collect(), ELEMENT_ARRAY_BUFFER,
+            json!({ "componentType": UNSIGNED_INT, "count": geometry.indices.len(), "type": "SCALAR" }));
+        self.geometries.push((positions, normals, indices));
+        self.geometries.len() - 1
+    }
+
+    // Every accessor has a buffer view of its own, the data are 4 byte values so the views stay aligned
+    fn add_accessor(&mut self, data: Vec<u8>, target: u32, mut accessor: Value) -> usize {
+        self.buffer_views.push(json!({ "buffer": 0, "byteOffset": self.buffer.len(), "byteLength": data.len(), "target": target }));
+        self.buffer.extend(data);
+        accessor["bufferView"] = json!(self.buffer_views.len() - 1);
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    fn add_mesh(&mut self, name: &str, geometry: usize, material: usize) -> usize {
+        let (positions, normals, indices) = self.geometries[geometry];
+        self.meshes.push(json!({
+            "name": name,
+            "primitives": [{ "attributes": { "POSITION": positions, "NORMAL": normals }, "indices": indices, "material": material }],
+        }));
+        self.meshes.len() - 1
+    }
+
+    fn into_glb(self, nodes: Vec<Value>, scene_extras: Value) -> Result<Vec<u8>, MyError> {
+        let mut gltf = json!({
+            "asset": { "version": "2.0", "generator": "knotter" },
+            "scene": 0,
+            "scenes": [{ "nodes": (0..nodes.len()).collect::<Vec<_>>(), "extras": scene_extras }],
+            "nodes": nodes,
+            "meshes": self.meshes,
+            "materials": self.materials,
+            "accessors": self.accessors,
+            "bufferViews": self.buffer_views,
+            "buffers": [{ "byteLength": self.buffer.len() }],
+        });
+        if self.uses_emissive_strength {
+            gltf["extensionsUsed"] = json!(["KHR_materials_emissive_strength"]);
+        }
+
+        // Chunks are padded to 4 bytes, the JSON with spaces and the binary data with zeros
+        let mut json_chunk = serde_json::to_vec(&gltf)?;
+        json_chunk.resize(json_chunk.len().next_multiple_of(4), b' ');
+        let mut bin_chunk = self.buffer;
+        bin_chunk.resize(bin_chunk.len().next_multiple_of(4), 0);
+
+        let length = 12 + 8 + json_chunk.len() + 8 + bin_chunk.len();
+        let mut glb = Vec::with_capacity(length);
+        glb.extend_from_slice(GLB_MAGIC);
+        glb.extend_from_slice(&GLB_VERSION.to_le_bytes());
+        glb.extend_from_slice(&(length as u32).to_le_bytes());
+        for (chunk_type, chunk) in [(CHUNK_JSON, &json_chunk), (CHUNK_BIN, &bin_chunk)] {
+            glb.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+            glb.extend_from_slice(chunk_type);
+            glb.extend_from_slice(chunk);
+        }
+        Ok(glb)
+    }
+}
+
+// The same material the client gives a ball, glTF colors are linear
+fn ball_material(color: &str, appearance: Option<&AppearanceEntity>) -> Value {
+    let [red, green, blue, alpha] = linear_rgba(color);
+    let (emissive, metallic, roughness, transparency) = appearance
+        .map_or((0.0, 0.0, DEFAULT_ROUGHNESS, 0.0), |appearance| (appearance.emissive, appearance.metallic, appearance.roughness, appearance.transparency));
+    let alpha = alpha * (1.0 - transparency);
+    let mut material = json!({
+        "name": color,
+        "pbrMetallicRoughness": { "baseColorFactor": [red, green, blue, alpha], "metallicFactor": metallic, "roughnessFactor": roughness },
+        "alphaMode": if alpha < 1.0 { "BLEND" } else { "OPAQUE" },
+    });
+    if emissive > 0.0 {
+        material["emissiveFactor"] = json!([red, green, blue]);
+        material["extensions"] = json!({ "KHR_materials_emissive_strength": { "emissiveStrength": emissive } });
+    }
+    material
+}
+
+// #RRGGBBAA with the color in linear space, white if it can not be read
+fn linear_rgba(color: &str) -> [f32; 4] {
+    let channel = |index: usize| color.get(1 + 2 * index..3 + 2 * index)
+        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        .map_or(1.0, |value| value as f32 / 255.0);
+    let linear = |value: f32| if value <= 0.04045 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) };
+    [linear(channel(0)), linear(channel(1)), linear(channel(2)), channel(3)]
+}
+
+fn shape_geometry(shape: ShapeEntity) -> Geometry {
+    match shape {
+        ShapeEntity::Sphere => lathe(&sphere_profile(STACKS, 1.0, 0.0), SECTORS),
+        ShapeEntity::Cube => cube(CUBE_HALF_SIZE),
+        ShapeEntity::Capsule => lathe(&sphere_profile(STACKS, CAPSULE_RADIUS, CAPSULE_HALF_HEIGHT), SECTORS),
+    }
+}
+
+// Rings from the top to the bottom as (ring radius, height, normal). With a half height the
+// hemispheres are moved apart, which makes a capsule along the y axis.
+fn sphere_profile(stacks: usize, radius: f32, half_height: f32) -> Vec<(f32, f32, [f32; 2])> {
+    let ring = |angle: f32, offset: f32| (radius * angle.sin(), offset + radius * angle.cos(), [angle.sin(), angle.cos()]);
+    let half = stacks / 2;
+    let mut profile: Vec<_> = (0..=half).map(|stack| ring(PI * stack as f32 / stacks as f32, half_height)).collect();
+    profile.extend((half..=stacks).map(|stack| ring(PI * stack as f32 / stacks as f32, -half_height)));
+    profile
+}
+
+// Turns the profile around the y axis
+fn lathe(profile: &[(f32, f32, [f32; 2])], sectors: usize) -> Geometry {
+    let mut geometry = Geometry { positions: Vec::new(), normals: Vec::new(), indices: Vec::new() };
+    for (ring_radius, height, [normal_out, normal_y]) in profile {
+        for sector in 0..=sectors {
+            let angle = 2.0 * PI * sector as f32 / sectors as f32;
+            let (sin, cos) = angle.sin_cos();
+            geometry.positions.push([ring_radius * cos, *height, -ring_radius * sin]);
+            geometry.normals.push([normal_out * cos, *normal_y, -normal_out * sin]);
+        }
+    }
+    let row = sectors as u32 + 1;
+    for ring in 0..profile.len() as u32 - 1 {
+        for sector in 0..sectors as u32 {
+            let (top, bottom) = (ring * row + sector, (ring + 1) * row + sector);
+            geometry.indices.extend_from_slice(&[top, bottom, top + 1, top + 1, bottom, bottom + 1]);
+        }
+    }
+    geometry
+}
+
+fn cube(half_size: f32) -> Geometry {
+    let mut geometry = Geometry { positions: Vec::new(), normals: Vec::new(), indices: Vec::new() };
+    let axes = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for axis in 0..3 {
+        for sign in [1.0, -1.0] {
+            let normal: [f32; 3] = axes[axis].map(|value| value * sign);
+            // Two axes along the face, in the order that makes the corners counter-clockwise from outside
+            let (u, v) = (axes[(axis + 1) % 3], axes[(axis + 2) % 3]);
+            let (u, v) = if sign > 0.0 { (u, v) } else { (v, u) };
+            let first = geometry.positions.len() as u32;
+            for (a, b) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+                geometry.positions.push(std::array::from_fn(|i| half_size * (normal[i] + a * u[i] + b * v[i])));
+                geometry.normals.push(normal);
+            }
+            geometry.indices.extend_from_slice(&[first, first + 1, first + 2, first, first + 2, first + 3]);
+        }
+    }
+    geometry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+    use crate::domain::models::ball_entity::{PositionEntity, BodyEntity, LinkEntity};
+
+    fn glb_json(glb: &[u8]) -> Value {
+        let json_length = u32::from_le_bytes(glb[12..16].try_into().unwrap()) as usize;
+        serde_json::from_slice(&glb[20..20 + json_length]).unwrap()
+    }
+
+    #[test]
+    fn test_glb_has_the_globe_and_a_node_per_ball() {
+        let mut sphere = BallEntity::new(Uuid::new_v4(), true);
+        sphere.is_fixed = true;
+        sphere.color = Some("#ff0000ff".to_string());
+        sphere.position = Some(PositionEntity { x: 1.05, y: 0.0, z: 0.0 });
+        let mut cube = sphere.clone();
+        cube.uuid = Uuid::new_v4();
+        cube.position = Some(PositionEntity { x: -1.08, y: 0.0, z: 0.0 });
+        cube.body = Some(BodyEntity { shape: ShapeEntity::Cube, radius: 0.125, mass: None });
+        let mut link = BallEntity::new(Uuid::new_v4(), true);
+        link.link = Some(LinkEntity { from_uuid: sphere.uuid, to_uuid: cube.uuid });
+
+        let glb = export_glb("hasu45selo", &[sphere.clone(), cube, link]).unwrap();
+        assert_eq!(&glb[0..4], GLB_MAGIC);
+        assert_eq!(u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize, glb.len());
+        assert_eq!(glb.len() % 4, 0);
+
+        let gltf = glb_json(&glb);
+        assert_eq!(gltf["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(gltf["nodes"][1]["extras"]["uuid"], json!(sphere.uuid));
+        assert_eq!(gltf["nodes"][2]["scale"], json!([0.125, 0.125, 0.125]));
+        // Same color, different shapes
+        assert_eq!(gltf["materials"].as_array().unwrap().len(), 2);
+        assert_eq!(gltf["meshes"].as_array().unwrap().len(), 3);
+        assert_eq!(gltf["scenes"][0]["extras"]["links"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_accessors_fit_in_the_buffer() {
+        let glb = export_glb("hasu45selo", &[]).unwrap();
+        let gltf = glb_json(&glb);
+        let buffer_length = gltf["buffers"][0]["byteLength"].as_u64().unwrap();
+        for buffer_view in gltf["bufferViews"].as_array().unwrap() {
+            assert!(buffer_view["byteOffset"].as_u64().unwrap() + buffer_view["byteLength"].as_u64().unwrap() <= buffer_length);
+        }
+    }
+
+    #[test]
+    fn test_colors_are_linear() {
+        assert_eq!(linear_rgba("#ff000080"), [1.0, 0.0, 0.0, 128.0 / 255.0]);
+        assert!((linear_rgba("#808080ff")[0] - 0.2158605).abs() < 1e-5);
+    }
+}
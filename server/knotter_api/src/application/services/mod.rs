@@ -1,2 +1,4 @@
 pub mod validation_service;
-pub mod validation;
\ No newline at end of file
+pub mod validation;
+pub mod gltf_exporter;
+pub mod globe_templates;
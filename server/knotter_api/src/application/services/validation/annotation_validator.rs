@@ -0,0 +1,22 @@
+use crate::domain::models::ball_entity::AnnotationEntity;
+use crate::domain::errors::my_error::MyError;
+use shared::domain::dtos::annotation_dto::{MAX_ANNOTATION_LENGTH, MIN_FONT_SIZE, MAX_FONT_SIZE};
+
+pub fn validate_annotation_text(annotation: &AnnotationEntity) -> Result<(), MyError> {
+    if annotation.text.trim().is_empty() {
+        return Err(MyError::ValidationError("Annotation text can not be empty.".to_string()));
+    }
+    if annotation.text.chars().count() > MAX_ANNOTATION_LENGTH {
+        return Err(MyError::ValidationError(format!("Annotation text can be at most {} characters.", MAX_ANNOTATION_LENGTH)));
+    }
+    // Line breaks are kept, other control characters would not show
+    if annotation.text.chars().any(|c| c.is_control() && c != '\n') {
+        return Err(MyError::ValidationError("Annotation text can not have control characters.".to_string()));
+    }
+    // NaN is outside every range
+    if !(MIN_FONT_SIZE..=MAX_FONT_SIZE).contains(&annotation.font_size) {
+        return Err(MyError::ValidationError(format!("Font size must be between {} and {}.", MIN_FONT_SIZE, MAX_FONT_SIZE)));
+    }
+
+    Ok(())
+}
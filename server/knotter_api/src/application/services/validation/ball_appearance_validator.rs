@@ -0,0 +1,21 @@
+use crate::domain::models::ball_entity::AppearanceEntity;
+use crate::domain::errors::my_error::MyError;
+use shared::domain::dtos::appearance_dto::{MAX_EMISSIVE, MAX_METALLIC, MAX_ROUGHNESS, MAX_TRANSPARENCY};
+
+pub fn validate_appearance(appearance: &AppearanceEntity) -> Result<(), MyError> {
+    validate_in_range("emissive", appearance.emissive, MAX_EMISSIVE)?;
+    validate_in_range("metallic", appearance.metallic, MAX_METALLIC)?;
+    validate_in_range("roughness", appearance.roughness, MAX_ROUGHNESS)?;
+    validate_in_range("transparency", appearance.transparency, MAX_TRANSPARENCY)?;
+
+    Ok(())
+}
+
+fn validate_in_range(name: &str, value: f32, max: f32) -> Result<(), MyError> {
+    // NaN is outside every range
+    if !(0.0..=max).contains(&value) {
+        return Err(MyError::ValidationError(format!("Appearance {} must be between 0 and {}.", name, max)));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use crate::domain::models::ball_entity::BodyEntity;
+use crate::domain::models::physics_profile_entity::PhysicsProfileEntity;
+use crate::domain::errors::my_error::MyError;
+use shared::domain::dtos::body_dto::MAX_BALL_MASS;
+
+pub fn validate_body(body: &BodyEntity, physics_profile: &PhysicsProfileEntity) -> Result<(), MyError> {
+    let (min_radius, max_radius) = (physics_profile.min_ball_radius, physics_profile.max_ball_radius);
+    // NaN is outside every range
+    if !(min_radius..=max_radius).contains(&body.radius) {
+        return Err(MyError::ValidationError(format!("Ball radius must be between {} and {}.", min_radius, max_radius)));
+    }
+    if let Some(mass) = body.mass {
+        if !(mass > 0.0 && mass <= MAX_BALL_MASS) {
+            return Err(MyError::ValidationError(format!("Ball mass must be above 0 and at most {}.", MAX_BALL_MASS)));
+        }
+    }
+
+    Ok(())
+}
@@ -1,28 +1,26 @@
 use crate::domain::models::ball_entity::PositionEntity;
 
-const GLOBE_RADIUS: f32 = 1.0;
-const BALL_RADIUS: f32 = 0.05;
+pub use shared::domain::dtos::geo_position_dto::GLOBE_RADIUS;
 const TOLERANCE: f32 = 0.001; // small limit above the sphere
 const GLOBE_POSITION: PositionEntity = PositionEntity { x: 0.0, y: 0.0, z: 0.0 };
 
 pub struct Globe;
 
 impl Globe {
-    pub fn contains(ball: &PositionEntity) -> bool {
+    pub fn contains(ball: &PositionEntity, radius: f32) -> bool {
         let distance_from_center = ball.distance_squared(&GLOBE_POSITION).sqrt();
         let lower_bound = GLOBE_RADIUS;
-        let upper_bound = GLOBE_RADIUS + BALL_RADIUS + TOLERANCE;
+        let upper_bound = GLOBE_RADIUS + radius + TOLERANCE;
 
         lower_bound <= distance_from_center && distance_from_center <= upper_bound
     }
 }
 
-const MIN_DISTANCE: f32 = 0.1;
-const MIN_DISTANCE_SQUARED: f32 = MIN_DISTANCE * MIN_DISTANCE;
-
-pub fn is_valid_distance_from_others(point: &PositionEntity, others: &Vec<&PositionEntity>) -> bool {
-    for other_point in others {
-        if point.distance_squared(other_point) < MIN_DISTANCE_SQUARED {
+// Balls are not closer than their radii added, so they do not overlap
+pub fn is_valid_distance_from_others(point: &PositionEntity, radius: f32, others: &[(&PositionEntity, f32)]) -> bool {
+    for (other_point, other_radius) in others {
+        let min_distance = radius + other_radius;
+        if point.distance_squared(other_point) < min_distance * min_distance {
             return false;
         }
     }
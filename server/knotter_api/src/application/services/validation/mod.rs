@@ -1,2 +1,9 @@
 pub mod ball_impulse_validator;
-pub mod ball_position_validator;
\ No newline at end of file
+pub mod ball_position_validator;
+pub mod ball_body_validator;
+pub mod ball_appearance_validator;
+pub mod annotation_validator;
+pub mod physics_profile_validator;
+
+pub mod palette_validator;
+pub mod surface_validator;
@@ -0,0 +1,29 @@
+use crate::domain::models::palette_entity::PaletteEntity;
+use crate::domain::errors::my_error::MyError;
+use shared::domain::dtos::palette_dto::MAX_PALETTE_COLORS;
+use regex::Regex;
+
+pub fn validate_palette(palette: &PaletteEntity) -> Result<(), MyError> {
+    if palette.colors.len() > MAX_PALETTE_COLORS {
+        return Err(MyError::ValidationError(format!("Palette can have at most {} colors.", MAX_PALETTE_COLORS)));
+    }
+    if palette.is_enforced && palette.colors.is_empty() {
+        return Err(MyError::ValidationError("An enforced palette needs at least one color.".to_string()));
+    }
+    for (index, color) in palette.colors.iter().enumerate() {
+        if !is_valid_color_format(color) {
+            return Err(MyError::ValidationError(format!("Invalid color value provided: {}", color)));
+        }
+        if palette.colors[..index].iter().any(|previous_color| previous_color.eq_ignore_ascii_case(color)) {
+            return Err(MyError::ValidationError(format!("Color is in the palette twice: {}", color)));
+        }
+    }
+
+    Ok(())
+}
+
+// #RRGGBBAA
+pub fn is_valid_color_format(color: &str) -> bool {
+    let re = Regex::new(r"^#([A-Fa-f0-9]{8})$").unwrap();
+    re.is_match(color)
+}
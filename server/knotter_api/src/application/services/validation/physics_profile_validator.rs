@@ -0,0 +1,29 @@
+use crate::domain::models::physics_profile_entity::PhysicsProfileEntity;
+use crate::domain::errors::my_error::MyError;
+use shared::domain::dtos::physics_profile_dto::{MAX_GRAVITY, MAX_RESTITUTION, MAX_LINEAR_DAMPING};
+use shared::domain::dtos::body_dto::{MIN_BALL_RADIUS, MAX_BALL_RADIUS};
+
+pub fn validate_physics_profile(physics_profile: &PhysicsProfileEntity) -> Result<(), MyError> {
+    validate_in_range("gravity", physics_profile.gravity, MAX_GRAVITY)?;
+    validate_in_range("restitution", physics_profile.restitution, MAX_RESTITUTION)?;
+    validate_in_range("linear_damping", physics_profile.linear_damping, MAX_LINEAR_DAMPING)?;
+
+    let ball_radius_bounds = MIN_BALL_RADIUS..=MAX_BALL_RADIUS;
+    if !ball_radius_bounds.contains(&physics_profile.min_ball_radius) || !ball_radius_bounds.contains(&physics_profile.max_ball_radius) {
+        return Err(MyError::ValidationError(format!("Ball radius bounds must be between {} and {}.", MIN_BALL_RADIUS, MAX_BALL_RADIUS)));
+    }
+    if physics_profile.min_ball_radius > physics_profile.max_ball_radius {
+        return Err(MyError::ValidationError("min_ball_radius can not be larger than max_ball_radius.".to_string()));
+    }
+
+    Ok(())
+}
+
+fn validate_in_range(name: &str, value: f32, max: f32) -> Result<(), MyError> {
+    // NaN is outside every range
+    if !(0.0..=max).contains(&value) {
+        return Err(MyError::ValidationError(format!("{} must be between 0 and {}.", name, max)));
+    }
+
+    Ok(())
+}
\ No newline at end of file
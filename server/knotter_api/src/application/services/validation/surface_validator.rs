@@ -0,0 +1,95 @@
+use crate::domain::models::surface_entity::{SurfaceEntity, SurfaceTextureEntity};
+use crate::domain::errors::my_error::MyError;
+use shared::domain::dtos::surface_dto::MAX_SURFACE_IMAGE_BYTES;
+
+const PNG_SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+const JPEG_SIGNATURE: &[u8] = &[0xFF, 0xD8, 0xFF];
+
+pub fn validate_surface(surface: &SurfaceEntity) -> Result<(), MyError> {
+    if surface.texture == SurfaceTextureEntity::Uploaded && surface.image_version.is_none() {
+        return Err(MyError::ValidationError("No image has been uploaded for the globe.".to_string()));
+    }
+    Ok(())
+}
+
+// The content type of a PNG or JPEG image that is twice as wide as it is high, as equirectangular images are
+pub fn validate_surface_image(image: &[u8]) -> Result<&'static str, MyError> {
+    if image.len() > MAX_SURFACE_IMAGE_BYTES {
+        return Err(MyError::ValidationError(format!("Image can not be larger than {} bytes.", MAX_SURFACE_IMAGE_BYTES)));
+    }
+    let (content_type, size) = if image.starts_with(PNG_SIGNATURE) {
+        ("image/png", png_size(image))
+    } else if image.starts_with(JPEG_SIGNATURE) {
+        ("image/jpeg", jpeg_size(image))
+    } else {
+        return Err(MyError::ValidationError("Image must be a PNG or JPEG.".to_string()));
+    };
+    let (width, height) = size.ok_or_else(||
+        MyError::ValidationError("Image size could not be read.".to_string())
+    )?;
+    if height == 0 || width != 2 * height {
+        return Err(MyError::ValidationError(format!("Image must be twice as wide as it is high, it is {}x{}.", width, height)));
+    }
+    Ok(content_type)
+}
+
+// The IHDR chunk comes first, with the width and height
+fn png_size(image: &[u8]) -> Option<(u32, u32)> {
+    let header = image.get(12..24)?;
+    if &header[0..4] != b"IHDR" {
+        return None;
+    }
+    Some((u32::from_be_bytes(header[4..8].try_into().ok()?), u32::from_be_bytes(header[8..12].try_into().ok()?)))
+}
+
+// Walks the segments up to the start of frame, which has the height and width
+fn jpeg_size(image: &[u8]) -> Option<(u32, u32)> {
+    let mut offset = 2;
+    loop {
+        let segment = image.get(offset..offset + 9)?;
+        if segment[0] != 0xFF {
+            return None;
+        }
+        let marker = segment[1];
+        let is_start_of_frame = (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if is_start_of_frame {
+            let height = u16::from_be_bytes([segment[5], segment[6]]) as u32;
+            let width = u16::from_be_bytes([segment[7], segment[8]]) as u32;
+            return Some((width, height));
+        }
+        offset += 2 + u16::from_be_bytes([segment[2], segment[3]]) as usize;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut image = PNG_SIGNATURE.to_vec();
+        image.extend_from_slice(&13u32.to_be_bytes());
+        image.extend_from_slice(b"IHDR");
+        image.extend_from_slice(&width.to_be_bytes());
+        image.extend_from_slice(&height.to_be_bytes());
+        image
+    }
+
+    #[test]
+    fn test_equirectangular_png_is_accepted() {
+        assert_eq!(validate_surface_image(&png_header(1024, 512)).unwrap(), "image/png");
+        assert!(validate_surface_image(&png_header(512, 512)).is_err());
+    }
+
+    #[test]
+    fn test_jpeg_size_is_read_from_start_of_frame() {
+        // An APP0 segment before the start of frame
+        let mut image = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00];
+        image.extend_from_slice(&[0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0x00, 0x02, 0x00]);
+        assert_eq!(validate_surface_image(&image).unwrap(), "image/jpeg");
+    }
+
+    #[test]
+    fn test_other_files_are_rejected() {
+        assert!(validate_surface_image(b"GIF89a").is_err());
+    }
+}
@@ -1,11 +1,21 @@
 use crate::domain::errors::my_error::MyError;
 use log::debug;
-use regex::Regex;
 use uuid::Uuid;
+use std::collections::HashMap;
 use crate::infrastructure::database::key_value_store::KeyValueStoreTrait;
-use crate::domain::models::ball_entity::{BallEntity, PositionEntity};
+use crate::domain::models::ball_entity::{BallEntity, PositionEntity, LinkEntity, AnnotationEntity};
 use crate::application::services::validation::ball_position_validator::*;
 use crate::application::services::validation::ball_impulse_validator::*;
+use crate::application::services::validation::ball_body_validator::*;
+use crate::application::services::validation::ball_appearance_validator::*;
+use crate::application::services::validation::annotation_validator::*;
+use shared::domain::dtos::annotation_dto::MAX_ANCHOR_HEIGHT;
+use crate::application::services::validation::physics_profile_validator::*;
+use crate::domain::models::physics_profile_entity::PhysicsProfileEntity;
+use crate::application::services::validation::palette_validator::*;
+use crate::domain::models::palette_entity::PaletteEntity;
+use crate::application::services::validation::surface_validator::*;
+use crate::domain::models::surface_entity::SurfaceEntity;
 
 
 pub struct ValidationService {
@@ -22,6 +32,10 @@ impl ValidationService {
     pub fn validate_delete<T: KeyValueStoreTrait>(uuid_to_delete: &Uuid, globe_id: &str, key_value_store: &T) -> Result<(), MyError> {
         let map_alive_objects = key_value_store.get_alive_objects_map(globe_id)?;
         
+        Self::validate_delete_against(uuid_to_delete, &map_alive_objects)
+    }
+
+    fn validate_delete_against(uuid_to_delete: &Uuid, map_alive_objects: &HashMap<Uuid, BallEntity>) -> Result<(), MyError> {
         if !map_alive_objects.contains_key(uuid_to_delete) {
             return Err(MyError::ValidationError("Cannot delete: UUID not found.".to_string()));
         }
@@ -32,35 +46,115 @@ impl ValidationService {
     }
 
     pub fn validate_insert<T: KeyValueStoreTrait>(&self, ball_entity: &BallEntity, globe_id: &str, key_value_store: &T) -> Result<(), MyError> {
-        // Preliminary checks
-        if ball_entity.is_fixed && ball_entity.impulse.is_some() {
-            return Err(MyError::ValidationError("Velocity should be None for fixed objects.".to_string()));
-        }
         //debug!("insert_ball_dto {:?}", insert_ball_dto);
         debug!("validate 1" );
         // Retrieve all alive objects
         let map_alive_objects = key_value_store.get_alive_objects_map(globe_id)?;
+        let physics_profile = key_value_store.get_physics_profile(globe_id)?;
+        let palette = key_value_store.get_palette(globe_id)?;
         debug!("validate 2" );
-        let mut vec_position_alive_fixed_objects: Vec<&PositionEntity> = Vec::new();
+        self.validate_insert_against(ball_entity, &map_alive_objects, &physics_profile, &palette)
+    }
+
+    // Validates a list of inserts and deletes as if they were applied one after another,
+    // so later operations see the balls inserted and deleted by earlier ones.
+    // Returns one result per operation.
+    pub fn validate_batch<T: KeyValueStoreTrait>(&self, ball_entities: &[BallEntity], globe_id: &str, key_value_store: &T) -> Result<Vec<Result<(), MyError>>, MyError> {
+        let map_alive_objects = key_value_store.get_alive_objects_map(globe_id)?;
+        let physics_profile = key_value_store.get_physics_profile(globe_id)?;
+        let palette = key_value_store.get_palette(globe_id)?;
+
+        // Profile changes are only written by the server, the same as for a single insert
+        Ok(self.validate_entries(ball_entities, map_alive_objects, &physics_profile, &palette, false))
+    }
+
+    // The same for a globe that is not stored yet, starting from the given alive objects and settings.
+    // Changes of the physics profile among the entries apply to the entries after them.
+    pub fn validate_batch_against(&self, ball_entities: &[BallEntity], map_alive_objects: HashMap<Uuid, BallEntity>, physics_profile: &PhysicsProfileEntity, palette: &PaletteEntity) -> Vec<Result<(), MyError>> {
+        self.validate_entries(ball_entities, map_alive_objects, physics_profile, palette, true)
+    }
+
+    fn validate_entries(&self, ball_entities: &[BallEntity], mut map_alive_objects: HashMap<Uuid, BallEntity>, physics_profile: &PhysicsProfileEntity, palette: &PaletteEntity, allow_physics_profile_changes: bool) -> Vec<Result<(), MyError>> {
+        let mut physics_profile = physics_profile;
+        let mut results = Vec::with_capacity(ball_entities.len());
+        for ball_entity in ball_entities {
+            if let Some(new_physics_profile) = &ball_entity.physics_profile {
+                let result = if allow_physics_profile_changes {
+                    self.validate_physics_profile(new_physics_profile)
+                } else {
+                    Err(Self::physics_profile_entry_error())
+                };
+                if result.is_ok() {
+                    physics_profile = new_physics_profile;
+                }
+                results.push(result);
+                continue;
+            }
+
+            let result = if ball_entity.is_insert {
+                self.validate_insert_against(ball_entity, &map_alive_objects, physics_profile, palette)
+            } else {
+                Self::validate_delete_against(&ball_entity.uuid, &map_alive_objects)
+            };
+
+            if result.is_ok() {
+                if ball_entity.is_insert {
+                    map_alive_objects.insert(ball_entity.uuid, ball_entity.clone());
+                } else {
+                    map_alive_objects.remove(&ball_entity.uuid);
+                    map_alive_objects.retain(|_, alive_object| !alive_object.is_link_to(&ball_entity.uuid));
+                }
+            }
+            results.push(result);
+        }
+
+        results
+    }
+
+    fn physics_profile_entry_error() -> MyError {
+        MyError::ValidationError("Physics profile changes can only be made through the physics profile of the globe.".to_string())
+    }
+
+    fn validate_insert_against(&self, ball_entity: &BallEntity, map_alive_objects: &HashMap<Uuid, BallEntity>, physics_profile: &PhysicsProfileEntity, palette: &PaletteEntity) -> Result<(), MyError> {
+        if ball_entity.physics_profile.is_some() {
+            return Err(Self::physics_profile_entry_error());
+        }
+        if let Some(link) = &ball_entity.link {
+            return self.validate_link_against(ball_entity, link, map_alive_objects, palette);
+        }
+        if let Some(annotation) = &ball_entity.annotation {
+            return self.validate_annotation_against(ball_entity, annotation, map_alive_objects, palette);
+        }
+        // Preliminary checks
+        if ball_entity.is_fixed && ball_entity.impulse.is_some() {
+            return Err(MyError::ValidationError("Velocity should be None for fixed objects.".to_string()));
+        }
+        // Balls without a body are spheres with the default radius
+        validate_body(&ball_entity.body.clone().unwrap_or_default(), physics_profile)?;
+        if let Some(appearance) = &ball_entity.appearance {
+            validate_appearance(appearance)?;
+        }
+        let mut vec_position_alive_fixed_objects: Vec<(&PositionEntity, f32)> = Vec::new();
         for value in map_alive_objects.values() {
-            if value.is_fixed {
+            // Annotations have a position, but balls can be placed over them
+            if value.is_fixed && value.annotation.is_none() {
                 if let Some(position) = &value.position {
-                    vec_position_alive_fixed_objects.push(position);
+                    vec_position_alive_fixed_objects.push((position, value.radius()));
                 }
             }
         }
         debug!("validate 3" );
         // Check that the new object is on the surface of the sphere/globe
         let position = ball_entity.position.as_ref().ok_or_else(|| 
-            MyError::ValidationError("Position is missing.".to_string())
+            MyError::ValidationError("Position is missing or out of range.".to_string())
         )?;
         debug!("validate 4" );
-        if !Globe::contains(&position) {
+        if !Globe::contains(position, ball_entity.radius()) {
             return Err(MyError::ValidationError("Ball is not on surface of sphere.".to_string()));
         }
         debug!("validate 5" );
         // Check the distance of the new ball from existing fixed balls
-        if !is_valid_distance_from_others(position, &vec_position_alive_fixed_objects) {
+        if !is_valid_distance_from_others(position, ball_entity.radius(), &vec_position_alive_fixed_objects) {
             return Err(MyError::ValidationError("Ball is too close to other fixed objects.".to_string()));
         }
         debug!("validate 6" );
@@ -70,16 +164,7 @@ impl ValidationService {
         }
         debug!("validate 7" );
         // Validate color
-        match &ball_entity.color {
-            Some(color) => {
-                if !ValidationService::validate_color(color) {
-                    return Err(MyError::ValidationError(format!("Invalid color value provided: {}", color)));
-                }
-            },
-            None => {
-                return Err(MyError::ValidationError("Color is required for insertion.".to_string()));
-            }
-        }
+        ValidationService::validate_color(&ball_entity.color, palette)?;
         debug!("validate 8" );
         // Validate impulse direction and magnitude if the ball is not fixed
         if !ball_entity.is_fixed {
@@ -95,16 +180,98 @@ impl ValidationService {
         Ok(())
     }
 
-    fn validate_color(color: &str) -> bool {
-        let re = Regex::new(r"^#([A-Fa-f0-9]{8})$").unwrap();
-        re.is_match(color)
+    // Links connect two different balls that are alive, and only one link can connect the same two balls
+    fn validate_link_against(&self, ball_entity: &BallEntity, link: &LinkEntity, map_alive_objects: &HashMap<Uuid, BallEntity>, palette: &PaletteEntity) -> Result<(), MyError> {
+        if ball_entity.position.is_some() || ball_entity.impulse.is_some() {
+            return Err(MyError::ValidationError("Links can not have position or impulse.".to_string()));
+        }
+        if ball_entity.appearance.is_some() {
+            return Err(MyError::ValidationError("Only balls can have an appearance.".to_string()));
+        }
+        if map_alive_objects.contains_key(&ball_entity.uuid) {
+            return Err(MyError::ValidationError("Object UUID is already in use.".to_string()));
+        }
+        if link.from_uuid == link.to_uuid {
+            return Err(MyError::ValidationError("Link must connect two different balls.".to_string()));
+        }
+        for endpoint_uuid in [&link.from_uuid, &link.to_uuid] {
+            match map_alive_objects.get(endpoint_uuid) {
+                Some(endpoint) if endpoint.link.is_none() && endpoint.annotation.is_none() => {},
+                Some(_) => return Err(MyError::ValidationError("Link can only connect balls.".to_string())),
+                None => return Err(MyError::ValidationError(format!("Link endpoint not found: {}", endpoint_uuid))),
+            }
+        }
+        let is_already_linked = map_alive_objects.values().any(|alive_object| {
+            alive_object.is_link_to(&link.from_uuid) && alive_object.is_link_to(&link.to_uuid)
+        });
+        if is_already_linked {
+            return Err(MyError::ValidationError("Balls are already linked.".to_string()));
+        }
+        ValidationService::validate_color(&ball_entity.color, palette)?;
+
+        Ok(())
+    }
+
+    // Annotations are anchored on the globe surface and have no body, impulse or link
+    fn validate_annotation_against(&self, ball_entity: &BallEntity, annotation: &AnnotationEntity, map_alive_objects: &HashMap<Uuid, BallEntity>, palette: &PaletteEntity) -> Result<(), MyError> {
+        if !ball_entity.is_fixed || ball_entity.impulse.is_some() || ball_entity.body.is_some() || ball_entity.link.is_some() {
+            return Err(MyError::ValidationError("Annotations can not move, have a body or be links.".to_string()));
+        }
+        if ball_entity.appearance.is_some() {
+            return Err(MyError::ValidationError("Only balls can have an appearance.".to_string()));
+        }
+        let position = ball_entity.position.as_ref().ok_or_else(||
+            MyError::ValidationError("Position is missing or out of range.".to_string())
+        )?;
+        if !Globe::contains(position, MAX_ANCHOR_HEIGHT) {
+            return Err(MyError::ValidationError("Annotation is not on surface of sphere.".to_string()));
+        }
+        if map_alive_objects.contains_key(&ball_entity.uuid) {
+            return Err(MyError::ValidationError("Object UUID is already in use.".to_string()));
+        }
+        validate_annotation_text(annotation)?;
+        ValidationService::validate_color(&ball_entity.color, palette)?;
+
+        Ok(())
+    }
+
+    pub fn validate_physics_profile(&self, physics_profile: &PhysicsProfileEntity) -> Result<(), MyError> {
+        validate_physics_profile(physics_profile)
+    }
+
+    pub fn validate_palette(&self, palette: &PaletteEntity) -> Result<(), MyError> {
+        validate_palette(palette)
+    }
+
+    pub fn validate_surface(&self, surface: &SurfaceEntity) -> Result<(), MyError> {
+        validate_surface(surface)
+    }
+
+    // The content type of the image
+    pub fn validate_surface_image(&self, image: &[u8]) -> Result<&'static str, MyError> {
+        validate_surface_image(image)
+    }
+
+    // New objects need a #RRGGBBAA color, from the palette of the globe if it is enforced
+    fn validate_color(color: &Option<String>, palette: &PaletteEntity) -> Result<(), MyError> {
+        let color = color.as_ref().ok_or_else(||
+            MyError::ValidationError("Color is required for insertion.".to_string())
+        )?;
+        if !is_valid_color_format(color) {
+            return Err(MyError::ValidationError(format!("Invalid color value provided: {}", color)));
+        }
+        if !palette.allows(color) {
+            return Err(MyError::ValidationError(format!("Color is not in the palette of the globe: {}", color)));
+        }
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::models::ball_entity::{BallEntity, PositionEntity};
+    use crate::domain::models::ball_entity::{BodyEntity, ShapeEntity, AppearanceEntity};
     use std::collections::HashMap;
 
     // Mock implementation of KeyValueStore to be used in tests
@@ -120,6 +287,12 @@ mod tests {
         fn get_alive_objects_map(&self, _globe_id: &str) -> Result<HashMap<Uuid, BallEntity>, MyError> {
             Ok(HashMap::new())  // Mock implementation
         }
+        fn get_physics_profile(&self, _globe_id: &str) -> Result<PhysicsProfileEntity, MyError> {
+            Ok(PhysicsProfileEntity::default())
+        }
+        fn get_palette(&self, _globe_id: &str) -> Result<PaletteEntity, MyError> {
+            Ok(PaletteEntity::default())
+        }
         // Mock other methods here as needed...
     }
 
@@ -135,6 +308,11 @@ mod tests {
             color: Some("#ff0000".to_string()),
             is_fixed: true,
             impulse: None,
+            link: None,
+            body: None,
+            annotation: None,
+            appearance: None,
+            physics_profile: None,
             // Add any other required fields here
         };
 
@@ -152,4 +330,351 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_validate_batch_balls_too_close_to_each_other() {
+        let validation_service = ValidationService::new();
+        let key_value_store = MockKeyValueStore;
+
+        let first_ball = BallEntity {
+            is_insert: true,
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 1.02, y: 0.0, z: 0.0 }),
+            color: Some("#ff0000ff".to_string()),
+            is_fixed: true,
+            impulse: None,
+            link: None,
+            body: None,
+            annotation: None,
+            appearance: None,
+            physics_profile: None,
+        };
+        let second_ball = BallEntity {
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 1.02, y: 0.05, z: 0.0 }),
+            ..first_ball.clone()
+        };
+
+        let results = validation_service.validate_batch(&[first_ball, second_ball], "some_globe_id", &key_value_store).unwrap();
+
+        assert!(results[0].is_ok());
+        match &results[1] {
+            Err(MyError::ValidationError(msg)) => assert_eq!(msg, "Ball is too close to other fixed objects."),
+            _ => panic!("Expected ValidationError for the second ball"),
+        }
+    }
+
+    #[test]
+    fn test_validate_batch_delete_ball_inserted_in_same_batch() {
+        let validation_service = ValidationService::new();
+        let key_value_store = MockKeyValueStore;
+
+        let inserted_ball = BallEntity {
+            is_insert: true,
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 1.02, y: 0.0, z: 0.0 }),
+            color: Some("#ff0000ff".to_string()),
+            is_fixed: true,
+            impulse: None,
+            link: None,
+            body: None,
+            annotation: None,
+            appearance: None,
+            physics_profile: None,
+        };
+        let deleted_ball = BallEntity::new(inserted_ball.uuid, false);
+        let unknown_ball = BallEntity::new(Uuid::new_v4(), false);
+
+        let results = validation_service.validate_batch(&[inserted_ball, deleted_ball, unknown_ball], "some_globe_id", &key_value_store).unwrap();
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_ok());
+        assert!(results[2].is_err());
+    }
+
+    #[test]
+    fn test_validate_batch_link_between_balls() {
+        let validation_service = ValidationService::new();
+        let key_value_store = MockKeyValueStore;
+
+        let first_ball = BallEntity {
+            is_insert: true,
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 1.02, y: 0.0, z: 0.0 }),
+            color: Some("#ff0000ff".to_string()),
+            is_fixed: true,
+            impulse: None,
+            link: None,
+            body: None,
+            annotation: None,
+            appearance: None,
+            physics_profile: None,
+        };
+        let second_ball = BallEntity {
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 0.0, y: 1.02, z: 0.0 }),
+            ..first_ball.clone()
+        };
+        let link = BallEntity {
+            uuid: Uuid::new_v4(),
+            position: None,
+            link: Some(LinkEntity { from_uuid: first_ball.uuid, to_uuid: second_ball.uuid }),
+            ..first_ball.clone()
+        };
+        let same_link = BallEntity { uuid: Uuid::new_v4(), ..link.clone() };
+        let deleted_ball = BallEntity::new(first_ball.uuid, false);
+        // Deleting a ball removes its links
+        let deleted_link = BallEntity::new(link.uuid, false);
+
+        let batch = [first_ball, second_ball, link, same_link, deleted_ball, deleted_link];
+        let results = validation_service.validate_batch(&batch, "some_globe_id", &key_value_store).unwrap();
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_ok());
+        assert!(results[2].is_ok());
+        match &results[3] {
+            Err(MyError::ValidationError(msg)) => assert_eq!(msg, "Balls are already linked."),
+            _ => panic!("Expected ValidationError for the second link"),
+        }
+        assert!(results[4].is_ok());
+        assert!(results[5].is_err());
+    }
+
+    #[test]
+    fn test_validate_batch_balls_with_body() {
+        let validation_service = ValidationService::new();
+        let key_value_store = MockKeyValueStore;
+
+        let big_ball = BallEntity {
+            is_insert: true,
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 1.1, y: 0.0, z: 0.0 }),
+            color: Some("#ff0000ff".to_string()),
+            is_fixed: true,
+            impulse: None,
+            link: None,
+            body: Some(BodyEntity { shape: ShapeEntity::Cube, radius: 0.1, mass: Some(2.0) }),
+            annotation: None,
+            appearance: None,
+            physics_profile: None,
+        };
+        // Far enough from a ball with the default radius, but not from the big one
+        let small_ball = BallEntity {
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 1.02, y: 0.12, z: 0.0 }),
+            body: None,
+            ..big_ball.clone()
+        };
+        let too_big_ball = BallEntity {
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: -1.05, y: 0.0, z: 0.0 }),
+            body: Some(BodyEntity { radius: 0.5, ..BodyEntity::default() }),
+            ..big_ball.clone()
+        };
+
+        let results = validation_service.validate_batch(&[big_ball, small_ball, too_big_ball], "some_globe_id", &key_value_store).unwrap();
+
+        assert!(results[0].is_ok());
+        match &results[1] {
+            Err(MyError::ValidationError(msg)) => assert_eq!(msg, "Ball is too close to other fixed objects."),
+            _ => panic!("Expected ValidationError for the small ball"),
+        }
+        match &results[2] {
+            Err(MyError::ValidationError(msg)) => assert_eq!(msg, "Ball radius must be between 0.025 and 0.1."),
+            _ => panic!("Expected ValidationError for the too big ball"),
+        }
+    }
+
+    #[test]
+    fn test_validate_batch_against_physics_profile_changes() {
+        let validation_service = ValidationService::new();
+
+        let big_ball = BallEntity {
+            is_insert: true,
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 1.15, y: 0.0, z: 0.0 }),
+            color: Some("#ff0000ff".to_string()),
+            is_fixed: true,
+            impulse: None,
+            link: None,
+            body: Some(BodyEntity { radius: 0.15, ..BodyEntity::default() }),
+            annotation: None,
+            appearance: None,
+            physics_profile: None,
+        };
+        let larger_radius = PhysicsProfileEntity { max_ball_radius: 0.2, ..PhysicsProfileEntity::default() };
+        let later_big_ball = BallEntity { uuid: Uuid::new_v4(), position: Some(PositionEntity { x: -1.15, y: 0.0, z: 0.0 }), ..big_ball.clone() };
+        let ball_entities = [big_ball, BallEntity::new_physics_profile(larger_radius), later_big_ball];
+
+        // Entries before the change are checked against the profile in force before it
+        let results = validation_service.validate_batch_against(&ball_entities, HashMap::new(), &PhysicsProfileEntity::default(), &PaletteEntity::default());
+
+        assert!(results[0].is_err());
+        assert!(results[1].is_ok());
+        assert!(results[2].is_ok());
+
+        // Clients can not write profile changes with the balls
+        let results = validation_service.validate_batch(&ball_entities[1..], "some_globe_id", &MockKeyValueStore).unwrap();
+
+        assert!(results[0].is_err());
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn test_validate_batch_annotation() {
+        let validation_service = ValidationService::new();
+        let key_value_store = MockKeyValueStore;
+
+        let annotation = BallEntity {
+            is_insert: true,
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 1.02, y: 0.0, z: 0.0 }),
+            color: Some("#000000ff".to_string()),
+            is_fixed: true,
+            impulse: None,
+            link: None,
+            body: None,
+            annotation: Some(AnnotationEntity { text: "Start here ✓".to_string(), font_size: 24.0 }),
+            appearance: None,
+            physics_profile: None,
+        };
+        // Annotations do not take up space
+        let ball_on_annotation = BallEntity {
+            uuid: Uuid::new_v4(),
+            annotation: None,
+            ..annotation.clone()
+        };
+        let too_long_annotation = BallEntity {
+            uuid: Uuid::new_v4(),
+            annotation: Some(AnnotationEntity { text: "x".repeat(281), font_size: 24.0 }),
+            ..annotation.clone()
+        };
+        let link_to_annotation = BallEntity {
+            uuid: Uuid::new_v4(),
+            position: None,
+            link: Some(LinkEntity { from_uuid: annotation.uuid, to_uuid: ball_on_annotation.uuid }),
+            annotation: None,
+            ..annotation.clone()
+        };
+
+        let batch = [annotation, ball_on_annotation, too_long_annotation, link_to_annotation];
+        let results = validation_service.validate_batch(&batch, "some_globe_id", &key_value_store).unwrap();
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_ok());
+        match &results[2] {
+            Err(MyError::ValidationError(msg)) => assert_eq!(msg, "Annotation text can be at most 280 characters."),
+            _ => panic!("Expected ValidationError for the too long annotation"),
+        }
+        match &results[3] {
+            Err(MyError::ValidationError(msg)) => assert_eq!(msg, "Link can only connect balls."),
+            _ => panic!("Expected ValidationError for the link to an annotation"),
+        }
+    }
+
+    #[test]
+    fn test_validate_physics_profile_out_of_range() {
+        let validation_service = ValidationService::new();
+
+        assert!(validation_service.validate_physics_profile(&PhysicsProfileEntity::default()).is_ok());
+
+        let negative_gravity = PhysicsProfileEntity { gravity: -1.0, ..PhysicsProfileEntity::default() };
+        match validation_service.validate_physics_profile(&negative_gravity) {
+            Err(MyError::ValidationError(msg)) => assert_eq!(msg, "gravity must be between 0 and 50."),
+            _ => panic!("Expected ValidationError for negative gravity"),
+        }
+
+        let nan_restitution = PhysicsProfileEntity { restitution: f32::NAN, ..PhysicsProfileEntity::default() };
+        assert!(validation_service.validate_physics_profile(&nan_restitution).is_err());
+    }
+
+    #[test]
+    fn test_validate_batch_balls_with_appearance() {
+        let validation_service = ValidationService::new();
+        let key_value_store = MockKeyValueStore;
+
+        let glass_ball = BallEntity {
+            is_insert: true,
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 1.05, y: 0.0, z: 0.0 }),
+            color: Some("#ffffffff".to_string()),
+            is_fixed: true,
+            impulse: None,
+            link: None,
+            body: None,
+            annotation: None,
+            appearance: Some(AppearanceEntity { emissive: 0.0, metallic: 0.0, roughness: 0.1, transparency: 0.7 }),
+            physics_profile: None,
+        };
+        let invisible_ball = BallEntity {
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: -1.05, y: 0.0, z: 0.0 }),
+            appearance: Some(AppearanceEntity { transparency: 1.0, ..glass_ball.appearance.unwrap() }),
+            ..glass_ball.clone()
+        };
+        let nan_light = BallEntity {
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 0.0, y: 1.05, z: 0.0 }),
+            appearance: Some(AppearanceEntity { emissive: f32::NAN, ..glass_ball.appearance.unwrap() }),
+            ..glass_ball.clone()
+        };
+
+        let results = validation_service.validate_batch(&[glass_ball, invisible_ball, nan_light], "some_globe_id", &key_value_store).unwrap();
+
+        assert!(results[0].is_ok());
+        match &results[1] {
+            Err(MyError::ValidationError(msg)) => assert_eq!(msg, "Appearance transparency must be between 0 and 0.9."),
+            _ => panic!("Expected ValidationError for the invisible ball"),
+        }
+        assert!(results[2].is_err());
+    }
+
+    #[test]
+    fn test_validate_insert_color_outside_enforced_palette() {
+        let validation_service = ValidationService::new();
+        let palette = PaletteEntity { colors: vec!["#FF0000FF".to_string()], is_enforced: true };
+
+        let ball_in_palette = BallEntity {
+            is_insert: true,
+            uuid: Uuid::new_v4(),
+            position: Some(PositionEntity { x: 0.0, y: 0.0, z: 1.05 }),
+            color: Some("#ff0000ff".to_string()),
+            is_fixed: true,
+            impulse: None,
+            link: None,
+            body: None,
+            annotation: None,
+            appearance: None,
+            physics_profile: None,
+        };
+        let ball_outside_palette = BallEntity { uuid: Uuid::new_v4(), color: Some("#00ff00ff".to_string()), ..ball_in_palette.clone() };
+
+        assert!(validation_service.validate_insert_against(&ball_in_palette, &HashMap::new(), &PhysicsProfileEntity::default(), &palette).is_ok());
+        match validation_service.validate_insert_against(&ball_outside_palette, &HashMap::new(), &PhysicsProfileEntity::default(), &palette) {
+            Err(MyError::ValidationError(msg)) => assert_eq!(msg, "Color is not in the palette of the globe: #00ff00ff"),
+            _ => panic!("Expected ValidationError for a color outside the palette"),
+        }
+        // Palettes that are not enforced are only a suggestion
+        let suggested_palette = PaletteEntity { is_enforced: false, ..palette };
+        assert!(validation_service.validate_insert_against(&ball_outside_palette, &HashMap::new(), &PhysicsProfileEntity::default(), &suggested_palette).is_ok());
+    }
+
+    #[test]
+    fn test_validate_palette() {
+        let validation_service = ValidationService::new();
+
+        assert!(validation_service.validate_palette(&PaletteEntity::default()).is_ok());
+
+        let duplicate_colors = PaletteEntity { colors: vec!["#ff0000ff".to_string(), "#FF0000FF".to_string()], is_enforced: false };
+        match validation_service.validate_palette(&duplicate_colors) {
+            Err(MyError::ValidationError(msg)) => assert_eq!(msg, "Color is in the palette twice: #FF0000FF"),
+            _ => panic!("Expected ValidationError for a duplicate color"),
+        }
+
+        let empty_enforced = PaletteEntity { colors: Vec::new(), is_enforced: true };
+        assert!(validation_service.validate_palette(&empty_enforced).is_err());
+
+        let invalid_color = PaletteEntity { colors: vec!["red".to_string()], is_enforced: false };
+        assert!(validation_service.validate_palette(&invalid_color).is_err());
+    }
 }
@@ -10,6 +10,7 @@ pub enum MyError {
     ValidationError(String),
     InternalServerError(String),
     JsonError(String),
+    MsgPackError(String),
     // ... other errors
 }
 
@@ -46,6 +47,7 @@ impl fmt::Display for MyError {
             MyError::ValidationError(ref message) => write!(f, "Validation error: {}", message),
             MyError::InternalServerError(ref message) => write!(f, "Internal error: {}", message),
             MyError::JsonError(ref message) => write!(f, "JSON serialization/deserialization error: {}", message),
+            MyError::MsgPackError(ref message) => write!(f, "MessagePack serialization/deserialization error: {}", message),
             // ... other error variants
         }
     }
@@ -60,6 +62,7 @@ impl ResponseError for MyError {
             MyError::ValidationError(ref message) => HttpResponse::BadRequest().json(message),
             MyError::InternalServerError(ref message) => HttpResponse::InternalServerError().json(message),
             MyError::JsonError(ref message) => HttpResponse::BadRequest().json(message), // You can choose an appropriate status for JSON errors
+            MyError::MsgPackError(ref message) => HttpResponse::BadRequest().json(message),
             // ... other error mappings
         }
     }
@@ -69,4 +72,16 @@ impl From<serde_json::Error> for MyError {
     fn from(err: serde_json::Error) -> Self {
         MyError::JsonError(err.to_string())
     }
+}
+
+impl From<rmp_serde::encode::Error> for MyError {
+    fn from(err: rmp_serde::encode::Error) -> Self {
+        MyError::MsgPackError(err.to_string())
+    }
+}
+
+impl From<rmp_serde::decode::Error> for MyError {
+    fn from(err: rmp_serde::decode::Error) -> Self {
+        MyError::MsgPackError(err.to_string())
+    }
 }
\ No newline at end of file
@@ -1,15 +1,23 @@
 use shared::domain::dtos::insert_ball_dto::InsertBallDto;
 use shared::domain::dtos::impulse_dto::ImpulseDto;
 use shared::domain::dtos::position_dto::PositionDto;
-use crate::domain::models::ball_entity::{BallEntity, PositionEntity, ImpulseEntity};
+use shared::domain::dtos::link_dto::LinkDto;
+use shared::domain::dtos::body_dto::{BodyDto, ShapeDto, DEFAULT_BALL_RADIUS};
+use shared::domain::dtos::annotation_dto::AnnotationDto;
+use shared::domain::dtos::appearance_dto::AppearanceDto;
+use crate::domain::mapping::physics_profile_mapper;
+use crate::domain::models::ball_entity::{BallEntity, PositionEntity, ImpulseEntity, LinkEntity, BodyEntity, ShapeEntity, AnnotationEntity, AppearanceEntity};
 
 pub fn dto_to_entity(dto: &InsertBallDto) -> BallEntity {
+    // Latitude and longitude without an altitude rest the ball on the surface
+    let radius = dto.body.as_ref().map_or(DEFAULT_BALL_RADIUS, |body| body.radius);
+    let position = dto.position.clone().or_else(|| dto.geo_position.as_ref().and_then(|geo| geo.to_position(radius)));
     BallEntity {
         is_fixed: dto.is_fixed,
         is_insert: dto.is_insert,
         uuid: dto.uuid,
         color: dto.color.clone(),
-        position: dto.position.as_ref().map(|pos| PositionEntity {
+        position: position.map(|pos| PositionEntity {
             x: pos.x,
             y: pos.y,
             z: pos.z,
@@ -19,6 +27,30 @@ pub fn dto_to_entity(dto: &InsertBallDto) -> BallEntity {
             y: imp.y,
             z: imp.z,
         }),
+        link: dto.link.as_ref().map(|link| LinkEntity {
+            from_uuid: link.from_uuid,
+            to_uuid: link.to_uuid,
+        }),
+        body: dto.body.as_ref().map(|body| BodyEntity {
+            shape: match body.shape {
+                ShapeDto::Sphere => ShapeEntity::Sphere,
+                ShapeDto::Cube => ShapeEntity::Cube,
+                ShapeDto::Capsule => ShapeEntity::Capsule,
+            },
+            radius: body.radius,
+            mass: body.mass,
+        }),
+        annotation: dto.annotation.as_ref().map(|annotation| AnnotationEntity {
+            text: annotation.text.clone(),
+            font_size: annotation.font_size,
+        }),
+        appearance: dto.appearance.map(|appearance| AppearanceEntity {
+            emissive: appearance.emissive,
+            metallic: appearance.metallic,
+            roughness: appearance.roughness,
+            transparency: appearance.transparency,
+        }),
+        physics_profile: dto.physics_profile.as_ref().map(physics_profile_mapper::dto_to_entity),
     }
 }
 
@@ -38,5 +70,31 @@ pub fn entity_to_dto(entity: &BallEntity) -> InsertBallDto {
             y: imp.y,
             z: imp.z,
         }),
+        created_at: None,
+        link: entity.link.as_ref().map(|link| LinkDto {
+            from_uuid: link.from_uuid,
+            to_uuid: link.to_uuid,
+        }),
+        body: entity.body.as_ref().map(|body| BodyDto {
+            shape: match body.shape {
+                ShapeEntity::Sphere => ShapeDto::Sphere,
+                ShapeEntity::Cube => ShapeDto::Cube,
+                ShapeEntity::Capsule => ShapeDto::Capsule,
+            },
+            radius: body.radius,
+            mass: body.mass,
+        }),
+        annotation: entity.annotation.as_ref().map(|annotation| AnnotationDto {
+            text: annotation.text.clone(),
+            font_size: annotation.font_size,
+        }),
+        appearance: entity.appearance.map(|appearance| AppearanceDto {
+            emissive: appearance.emissive,
+            metallic: appearance.metallic,
+            roughness: appearance.roughness,
+            transparency: appearance.transparency,
+        }),
+        geo_position: None,
+        physics_profile: entity.physics_profile.as_ref().map(physics_profile_mapper::entity_to_dto),
     }
 }
@@ -0,0 +1,128 @@
+use uuid::Uuid;
+use shared::domain::dtos::ball_dto::BallDto;
+use shared::domain::dtos::body_dto::BodyDto;
+use shared::domain::dtos::impulse_dto::ImpulseDto;
+use shared::domain::dtos::geo_position_dto::GeoPositionDto;
+use shared::domain::dtos::position_dto::PositionDto;
+use shared::domain::dtos::geojson_dto::{FeatureDto, GeometryDto, FeaturePropertiesDto};
+use crate::domain::mapping::ball_mapper::dto_to_entity;
+use crate::domain::models::ball_entity::BallEntity;
+use crate::domain::errors::my_error::MyError;
+
+const DEFAULT_FEATURE_COLOR: &str = "#ffffffff";
+
+// A point feature as a ball to insert. The uuid is new unless the feature has one.
+pub fn feature_to_entity(feature: &FeatureDto) -> (Uuid, Result<BallEntity, MyError>) {
+    let properties = feature.properties.clone().unwrap_or_default();
+    let uuid = properties.uuid.unwrap_or_else(Uuid::new_v4);
+    (uuid, point_to_geo_position(feature).map(|geo_position| dto_to_entity(&BallDto {
+        is_fixed: properties.is_fixed.unwrap_or(true),
+        is_insert: true,
+        uuid,
+        color: Some(feature_color(&properties)),
+        impulse: properties.impulse.map(|[x, y, z]| ImpulseDto { x, y, z }),
+        body: properties.radius.map(|radius| BodyDto { radius, ..Default::default() }),
+        geo_position: Some(geo_position),
+        ..Default::default()
+    })))
+}
+
+fn point_to_geo_position(feature: &FeatureDto) -> Result<GeoPositionDto, MyError> {
+    let invalid = |message: &str| MyError::ValidationError(message.to_string());
+    let geometry = feature.geometry.as_ref().ok_or_else(|| invalid("Feature has no geometry."))?;
+    if geometry.geometry_type != "Point" {
+        return Err(MyError::ValidationError(format!("Only points can be imported, not {}.", geometry.geometry_type)));
+    }
+    let coordinates: Vec<f32> = geometry.coordinates.as_array()
+        .ok_or_else(|| invalid("Point coordinates must be an array."))?
+        .iter()
+        .map(|coordinate| coordinate.as_f64().map(|coordinate| coordinate as f32))
+        .collect::<Option<_>>()
+        .ok_or_else(|| invalid("Point coordinates must be numbers."))?;
+    match coordinates[..] {
+        [longitude, latitude] => Ok(GeoPositionDto { latitude, longitude, altitude: None }),
+        [longitude, latitude, altitude] => Ok(GeoPositionDto { latitude, longitude, altitude: Some(altitude) }),
+        _ => Err(invalid("Point needs a longitude, a latitude and optionally an altitude.")),
+    }
+}
+
+fn feature_color(properties: &FeaturePropertiesDto) -> String {
+    match (&properties.color, &properties.marker_color) {
+        (Some(color), _) => color.clone(),
+        (None, Some(marker_color)) if marker_color.len() == 7 => format!("{}ff", marker_color),
+        _ => DEFAULT_FEATURE_COLOR.to_string(),
+    }
+}
+
+// Balls as points, links and annotations are left out
+pub fn entity_to_feature(entity: &BallEntity) -> Option<FeatureDto> {
+    if entity.link.is_some() || entity.annotation.is_some() {
+        return None;
+    }
+    let position = entity.position.as_ref()?;
+    let geo_position = GeoPositionDto::from_position(&PositionDto { x: position.x, y: position.y, z: position.z });
+    Some(FeatureDto {
+        feature_type: "Feature".to_string(),
+        geometry: Some(GeometryDto {
+            geometry_type: "Point".to_string(),
+            coordinates: serde_json::json!([geo_position.longitude, geo_position.latitude, geo_position.altitude]),
+        }),
+        properties: Some(FeaturePropertiesDto {
+            uuid: Some(entity.uuid),
+            color: entity.color.clone(),
+            marker_color: None,
+            is_fixed: Some(entity.is_fixed),
+            radius: entity.body.as_ref().map(|body| body.radius),
+            impulse: entity.impulse.as_ref().map(|impulse| [impulse.x, impulse.y, impulse.z]),
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::domain::dtos::geojson_dto::FeatureCollectionDto;
+
+    fn features(payload: &str) -> Vec<FeatureDto> {
+        serde_json::from_str::<FeatureCollectionDto>(payload).unwrap().features
+    }
+
+    #[test]
+    fn test_point_is_a_fixed_ball_on_the_surface() {
+        let features = features(r##"{"type":"FeatureCollection","features":[
+            {"type":"Feature","geometry":{"type":"Point","coordinates":[0.0,90.0]},"properties":{"marker-color":"#ff0000"}}
+        ]}"##);
+        let (_, ball_entity) = feature_to_entity(&features[0]);
+        let ball_entity = ball_entity.unwrap();
+        assert!(ball_entity.is_fixed);
+        assert_eq!(ball_entity.color, Some("#ff0000ff".to_string()));
+        let position = ball_entity.position.unwrap();
+        assert!((position.y - 1.05).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_other_geometries_are_rejected() {
+        let features = features(r##"{"type":"FeatureCollection","features":[
+            {"type":"Feature","geometry":{"type":"LineString","coordinates":[[0,0],[1,1]]},"properties":null},
+            {"type":"Feature","geometry":{"type":"Point","coordinates":[1]},"properties":null},
+            {"type":"Feature","geometry":null,"properties":null}
+        ]}"##);
+        for feature in &features {
+            assert!(feature_to_entity(feature).1.is_err());
+        }
+    }
+
+    #[test]
+    fn test_exported_feature_imports_as_the_same_ball() {
+        let features = features(r##"{"type":"FeatureCollection","features":[
+            {"type":"Feature","geometry":{"type":"Point","coordinates":[10.7,59.9,0.08]},"properties":{"color":"#00ff00ff","radius":0.08}}
+        ]}"##);
+        let ball_entity = feature_to_entity(&features[0]).1.unwrap();
+        let exported = entity_to_feature(&ball_entity).unwrap();
+        let imported = feature_to_entity(&exported).1.unwrap();
+        assert_eq!(imported.uuid, ball_entity.uuid);
+        assert_eq!(imported.color, ball_entity.color);
+        assert_eq!(imported.body, ball_entity.body);
+        assert!(imported.position.unwrap().distance_squared(&ball_entity.position.unwrap()) < 1e-8);
+    }
+}
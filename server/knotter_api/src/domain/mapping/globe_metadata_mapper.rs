@@ -0,0 +1,14 @@
+use shared::domain::dtos::globe_metadata_dto::{GlobeMetadataDto, ProvenanceDto};
+use crate::domain::models::globe_metadata_entity::GlobeMetadataEntity;
+
+// Metadata is only written by the server, so there is no mapping from the dto
+pub fn entity_to_dto(entity: &GlobeMetadataEntity) -> GlobeMetadataDto {
+    GlobeMetadataDto {
+        forked_from: entity.forked_from.as_ref().map(|provenance| ProvenanceDto {
+            globe_id: provenance.globe_id.clone(),
+            transaction_id: provenance.transaction_id.clone(),
+            forked_at: provenance.forked_at,
+            with_history: provenance.with_history,
+        }),
+    }
+}
@@ -18,6 +18,13 @@ mod tests {
             color: Some("red".to_string()),
             position: Some(PositionDto { x: 1.0, y: 2.0, z: 3.0 }),
             impulse: Some(ImpulseDto { x: 1.0, y: 2.0, z: 3.0 }),
+            created_at: None,
+            link: None,
+            body: None,
+            annotation: None,
+            appearance: None,
+            geo_position: None,
+            physics_profile: None,
         };
         
         let insert_ball_entity_in = dto_to_entity(&insert_ball_dto_in);
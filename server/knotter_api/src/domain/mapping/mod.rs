@@ -1,2 +1,7 @@
 pub mod ball_mapper;
-pub mod mapping_tests;
\ No newline at end of file
+pub mod mapping_tests;
+pub mod physics_profile_mapper;
+pub mod palette_mapper;
+pub mod surface_mapper;
+pub mod geojson_mapper;
+pub mod globe_metadata_mapper;
@@ -0,0 +1,16 @@
+use shared::domain::dtos::palette_dto::PaletteDto;
+use crate::domain::models::palette_entity::PaletteEntity;
+
+pub fn dto_to_entity(dto: &PaletteDto) -> PaletteEntity {
+    PaletteEntity {
+        colors: dto.colors.clone(),
+        is_enforced: dto.is_enforced,
+    }
+}
+
+pub fn entity_to_dto(entity: &PaletteEntity) -> PaletteDto {
+    PaletteDto {
+        colors: entity.colors.clone(),
+        is_enforced: entity.is_enforced,
+    }
+}
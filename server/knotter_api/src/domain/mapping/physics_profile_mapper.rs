@@ -0,0 +1,26 @@
+use shared::domain::dtos::physics_profile_dto::PhysicsProfileDto;
+use crate::domain::models::physics_profile_entity::PhysicsProfileEntity;
+
+pub fn dto_to_entity(dto: &PhysicsProfileDto) -> PhysicsProfileEntity {
+    PhysicsProfileEntity {
+        gravity: dto.gravity,
+        restitution: dto.restitution,
+        linear_damping: dto.linear_damping,
+        preserve_speed: dto.preserve_speed,
+        updated_at: dto.updated_at,
+        min_ball_radius: dto.min_ball_radius,
+        max_ball_radius: dto.max_ball_radius,
+    }
+}
+
+pub fn entity_to_dto(entity: &PhysicsProfileEntity) -> PhysicsProfileDto {
+    PhysicsProfileDto {
+        gravity: entity.gravity,
+        restitution: entity.restitution,
+        linear_damping: entity.linear_damping,
+        preserve_speed: entity.preserve_speed,
+        updated_at: entity.updated_at,
+        min_ball_radius: entity.min_ball_radius,
+        max_ball_radius: entity.max_ball_radius,
+    }
+}
\ No newline at end of file
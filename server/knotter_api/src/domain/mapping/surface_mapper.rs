@@ -0,0 +1,30 @@
+use shared::domain::dtos::surface_dto::{SurfaceDto, SurfaceTextureDto};
+use crate::domain::models::surface_entity::{SurfaceEntity, SurfaceTextureEntity};
+
+// The image stays as it is, a new one is only set by uploading it
+pub fn dto_to_entity(dto: &SurfaceDto, current: &SurfaceEntity) -> SurfaceEntity {
+    SurfaceEntity {
+        texture: match dto.texture {
+            SurfaceTextureDto::Plain => SurfaceTextureEntity::Plain,
+            SurfaceTextureDto::Earth => SurfaceTextureEntity::Earth,
+            SurfaceTextureDto::Grid => SurfaceTextureEntity::Grid,
+            SurfaceTextureDto::Checkerboard => SurfaceTextureEntity::Checkerboard,
+            SurfaceTextureDto::Uploaded => SurfaceTextureEntity::Uploaded,
+        },
+        image_version: current.image_version.clone(),
+        image_content_type: current.image_content_type.clone(),
+    }
+}
+
+pub fn entity_to_dto(entity: &SurfaceEntity) -> SurfaceDto {
+    SurfaceDto {
+        texture: match entity.texture {
+            SurfaceTextureEntity::Plain => SurfaceTextureDto::Plain,
+            SurfaceTextureEntity::Earth => SurfaceTextureDto::Earth,
+            SurfaceTextureEntity::Grid => SurfaceTextureDto::Grid,
+            SurfaceTextureEntity::Checkerboard => SurfaceTextureDto::Checkerboard,
+            SurfaceTextureEntity::Uploaded => SurfaceTextureDto::Uploaded,
+        },
+        image_version: entity.image_version.clone(),
+    }
+}
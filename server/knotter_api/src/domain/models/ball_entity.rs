@@ -2,8 +2,10 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use nalgebra::Vector3;
+use shared::domain::dtos::body_dto::DEFAULT_BALL_RADIUS;
+use crate::domain::models::physics_profile_entity::PhysicsProfileEntity;
 
-#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone)]
 pub struct BallEntity {
     pub is_fixed: bool,
     pub is_insert: bool,
@@ -11,6 +13,66 @@ pub struct BallEntity {
     pub color: Option<String>, 
     pub position: Option<PositionEntity>,
     pub impulse: Option<ImpulseEntity>,
+    // Only links have it, left out so log entries of balls are unchanged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<LinkEntity>,
+    // Left out for spheres with the default radius
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<BodyEntity>,
+    // Only annotations have it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<AnnotationEntity>,
+    // Left out for plain colored balls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub appearance: Option<AppearanceEntity>,
+    // Only changes of the physics profile have it, they are in the log but never alive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub physics_profile: Option<PhysicsProfileEntity>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct AppearanceEntity {
+    pub emissive: f32,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub transparency: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AnnotationEntity {
+    pub text: String,
+    pub font_size: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ShapeEntity {
+    #[default]
+    Sphere,
+    Cube,
+    Capsule,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BodyEntity {
+    pub shape: ShapeEntity,
+    pub radius: f32,
+    pub mass: Option<f32>,
+}
+
+impl Default for BodyEntity {
+    fn default() -> Self {
+        BodyEntity {
+            shape: ShapeEntity::Sphere,
+            radius: DEFAULT_BALL_RADIUS,
+            mass: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LinkEntity {
+    pub from_uuid: Uuid,
+    pub to_uuid: Uuid,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -34,7 +96,7 @@ impl PositionEntity {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct ImpulseEntity {
     pub x: f32,
     pub y: f32,
@@ -57,6 +119,28 @@ impl BallEntity {
             color: None,
             position: None,
             impulse: None,
+            link: None,
+            body: None,
+            annotation: None,
+            appearance: None,
+            physics_profile: None,
+        }
+    }
+
+    // A change of the physics profile as it is written to the log
+    pub fn new_physics_profile(physics_profile: PhysicsProfileEntity) -> Self {
+        BallEntity {
+            is_fixed: true,
+            physics_profile: Some(physics_profile),
+            ..BallEntity::new(Uuid::new_v4(), true)
         }
     }
+
+    pub fn radius(&self) -> f32 {
+        self.body.as_ref().map_or(DEFAULT_BALL_RADIUS, |body| body.radius)
+    }
+
+    pub fn is_link_to(&self, uuid: &Uuid) -> bool {
+        self.link.as_ref().is_some_and(|link| link.from_uuid == *uuid || link.to_uuid == *uuid)
+    }
 }
\ No newline at end of file
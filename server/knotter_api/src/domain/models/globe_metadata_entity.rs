@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ProvenanceEntity {
+    pub globe_id: String,
+    pub transaction_id: Option<String>,
+    pub forked_at: i64,
+    pub with_history: bool,
+}
+
+// Metadata of one globe, stored by globe id
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct GlobeMetadataEntity {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<ProvenanceEntity>,
+}
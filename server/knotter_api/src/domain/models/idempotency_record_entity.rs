@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+
+// How long a stored response is replayed for requests with the same Idempotency-Key
+pub const IDEMPOTENCY_KEY_TTL_SECONDS: i64 = 24 * 60 * 60;
+
+// The original response of a request sent with an Idempotency-Key
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IdempotencyRecordEntity {
+    pub created_at: i64, // seconds since epoch
+    pub request: String, // serialized log entry, used to detect reuse of a key for another request
+    pub status: u16,
+    pub content_type: String,
+    pub body: String,
+}
+
+impl IdempotencyRecordEntity {
+    pub fn new(request: String, status: u16, content_type: &str, body: String) -> Self {
+        IdempotencyRecordEntity {
+            created_at: Utc::now().timestamp(),
+            request,
+            status,
+            content_type: content_type.to_string(),
+            body,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Utc::now().timestamp() - self.created_at > IDEMPOTENCY_KEY_TTL_SECONDS
+    }
+}
@@ -1 +1,6 @@
-pub mod ball_entity;
\ No newline at end of file
+pub mod ball_entity;
+pub mod idempotency_record_entity;
+pub mod physics_profile_entity;
+pub mod palette_entity;
+pub mod surface_entity;
+pub mod globe_metadata_entity;
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+// Palette of one globe, stored by globe id
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct PaletteEntity {
+    pub colors: Vec<String>,
+    pub is_enforced: bool,
+}
+
+impl PaletteEntity {
+    // Any color is allowed unless the palette is enforced
+    pub fn allows(&self, color: &str) -> bool {
+        !self.is_enforced || self.colors.iter().any(|palette_color| palette_color.eq_ignore_ascii_case(color))
+    }
+}
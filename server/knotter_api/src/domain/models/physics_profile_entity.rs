@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use shared::domain::dtos::physics_profile_dto::{DEFAULT_MIN_BALL_RADIUS, DEFAULT_MAX_BALL_RADIUS};
+
+// Physics rules of one globe, stored by globe id
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct PhysicsProfileEntity {
+    pub gravity: f32,
+    pub restitution: f32,
+    pub linear_damping: f32,
+    pub preserve_speed: bool,
+    pub updated_at: Option<i64>, // nanoseconds since epoch
+    // Profiles stored before the bounds existed get the default ones
+    #[serde(default = "default_min_ball_radius")]
+    pub min_ball_radius: f32,
+    #[serde(default = "default_max_ball_radius")]
+    pub max_ball_radius: f32,
+}
+
+fn default_min_ball_radius() -> f32 {
+    DEFAULT_MIN_BALL_RADIUS
+}
+
+fn default_max_ball_radius() -> f32 {
+    DEFAULT_MAX_BALL_RADIUS
+}
+
+impl Default for PhysicsProfileEntity {
+    // Same rules as before globes had a profile
+    fn default() -> Self {
+        PhysicsProfileEntity {
+            gravity: 9.8,
+            restitution: 1.0,
+            linear_damping: 0.0,
+            preserve_speed: true,
+            updated_at: None,
+            min_ball_radius: DEFAULT_MIN_BALL_RADIUS,
+            max_ball_radius: DEFAULT_MAX_BALL_RADIUS,
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum SurfaceTextureEntity {
+    #[default]
+    Plain,
+    Earth,
+    Grid,
+    Checkerboard,
+    Uploaded,
+}
+
+// Surface of one globe, stored by globe id. The uploaded image is stored apart from it.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct SurfaceEntity {
+    pub texture: SurfaceTextureEntity,
+    pub image_version: Option<String>,
+    pub image_content_type: Option<String>,
+}
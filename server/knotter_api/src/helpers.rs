@@ -1,4 +1,10 @@
 use crate::domain::errors::my_error::MyError;
+use crate::domain::models::idempotency_record_entity::IdempotencyRecordEntity;
+use crate::domain::models::ball_entity::BallEntity;
+use crate::domain::models::physics_profile_entity::PhysicsProfileEntity;
+use crate::infrastructure::database::key_value_store::KeyValueStore;
+use actix_web::{http::header, http::StatusCode, HttpRequest, HttpResponse};
+use serde::{de::DeserializeOwned, Serialize};
 use regex::Regex;
 use chrono::{self, Utc};
 use rand::Rng;
@@ -26,9 +32,120 @@ pub fn process_globe_id(globe_id: &str) -> Result<String, MyError> {
     Ok(globe_id)
 }
 
+pub fn process_transaction_id(transaction_id: &str) -> Result<&str, MyError> {
+    if transaction_id.is_empty() || !transaction_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(MyError::ValidationError("transaction_id is not valid.".to_string()));
+    }
+
+    Ok(transaction_id)
+}
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+pub fn process_idempotency_key(req: &HttpRequest) -> Result<Option<String>, MyError> {
+    let Some(header_value) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+
+    let idempotency_key = header_value.to_str()
+        .map_err(|_| MyError::ValidationError("Idempotency-Key is not valid.".to_string()))?;
+    if idempotency_key.is_empty() || idempotency_key.len() > 255 {
+        return Err(MyError::ValidationError("Idempotency-Key should be between 1 and 255 characters".to_string()));
+    }
+
+    Ok(Some(idempotency_key.to_string()))
+}
+
+// Builds the response to send again for a retried request
+pub fn replay_idempotency_record(record: &IdempotencyRecordEntity, serialized_request: &str) -> Result<HttpResponse, MyError> {
+    let status = check_idempotency_record(record, serialized_request)?;
+
+    Ok(HttpResponse::build(status)
+        .content_type(record.content_type.as_str())
+        .insert_header(("Idempotent-Replayed", "true"))
+        .body(record.body.clone()))
+}
+
+// Same as replay_idempotency_record, but the stored JSON body is sent in the wire format the retry asked for
+pub fn replay_idempotency_record_as<T: Serialize + DeserializeOwned>(record: &IdempotencyRecordEntity, serialized_request: &str, wire_format: WireFormat) -> Result<HttpResponse, MyError> {
+    let status = check_idempotency_record(record, serialized_request)?;
+    let response_dto: T = serde_json::from_str(&record.body)?;
+
+    let mut response = encode_response(status, wire_format, &response_dto)?;
+    response.headers_mut().insert(
+        header::HeaderName::from_static("idempotent-replayed"),
+        header::HeaderValue::from_static("true"),
+    );
+    Ok(response)
+}
+
+fn check_idempotency_record(record: &IdempotencyRecordEntity, serialized_request: &str) -> Result<StatusCode, MyError> {
+    if record.request != serialized_request {
+        return Err(MyError::ValidationError("Idempotency-Key has already been used for a different request.".to_string()));
+    }
+
+    StatusCode::from_u16(record.status)
+        .map_err(|err| MyError::InternalServerError(err.to_string()))
+}
+
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+// Encoding of the shared DTOs on the wire.
+// MessagePack structs are encoded as arrays without field names, client and server use the same DTOs from shared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WireFormat {
+    Json,
+    MsgPack,
+}
+
+fn is_msgpack_media_type(value: &str) -> bool {
+    value.split(',').any(|media_type| {
+        let media_type = media_type.split(';').next().unwrap_or("").trim();
+        ["application/msgpack", "application/x-msgpack", "application/vnd.msgpack"]
+            .iter()
+            .any(|msgpack| media_type.eq_ignore_ascii_case(msgpack))
+    })
+}
+
+fn header_wire_format(req: &HttpRequest, header_name: header::HeaderName) -> WireFormat {
+    match req.headers().get(header_name).and_then(|value| value.to_str().ok()) {
+        Some(value) if is_msgpack_media_type(value) => WireFormat::MsgPack,
+        _ => WireFormat::Json,
+    }
+}
+
+// Wire format for the response, JSON unless the Accept header asks for MessagePack
+pub fn negotiate_wire_format(req: &HttpRequest) -> WireFormat {
+    header_wire_format(req, header::ACCEPT)
+}
+
+// Decodes a request body sent as JSON or, with a MessagePack Content-Type, as MessagePack
+pub fn decode_request_body<T: DeserializeOwned>(req: &HttpRequest, body: &[u8]) -> Result<T, MyError> {
+    match header_wire_format(req, header::CONTENT_TYPE) {
+        WireFormat::Json => Ok(serde_json::from_slice(body)?),
+        WireFormat::MsgPack => Ok(rmp_serde::from_slice(body)?),
+    }
+}
+
+pub fn encode_response<T: Serialize>(status: StatusCode, wire_format: WireFormat, value: &T) -> Result<HttpResponse, MyError> {
+    let (content_type, body) = match wire_format {
+        WireFormat::Json => ("application/json", serde_json::to_vec(value)?),
+        WireFormat::MsgPack => (MSGPACK_CONTENT_TYPE, rmp_serde::to_vec(value)?),
+    };
+
+    Ok(HttpResponse::build(status)
+        .content_type(content_type)
+        .insert_header((header::VARY, "Accept"))
+        .body(body))
+}
+
 pub fn generate_timestamp() -> String {
+    generate_timestamp_nanos().to_string()
+}
+
+pub fn generate_timestamp_nanos() -> i64 {
     let now = Utc::now();
-    (now.timestamp_subsec_nanos() as i64 + now.timestamp() * 1_000_000_000).to_string()
+    now.timestamp_subsec_nanos() as i64 + now.timestamp() * 1_000_000_000
 }
 
 pub fn generate_globe_id() -> String {
@@ -53,6 +170,30 @@ pub fn generate_globe_id() -> String {
     format!("{}{}{}{}", word1, digit1, digit2, word2)
 }
 
+// Generates globe ids until one has neither transactions nor metadata yet
+pub fn generate_unused_globe_id(key_value_store: &KeyValueStore) -> Result<String, MyError> {
+    loop {
+        let globe_id = generate_globe_id();
+        if !key_value_store.is_globe_id_used(&globe_id)? {
+            return Ok(globe_id);
+        }
+    }
+}
+
+// Profiles set before changes were written to the log are only kept as the latest profile.
+// A copied log without profile changes gets that profile as an entry at the time it was set, so the copy follows the same rules.
+pub fn insert_unlogged_physics_profile(ball_entities: &mut Vec<BallEntity>, transaction_ids: &[String], physics_profile: &PhysicsProfileEntity) {
+    let Some(updated_at) = physics_profile.updated_at else { return; };
+    if ball_entities.iter().any(|ball_entity| ball_entity.physics_profile.is_some()) {
+        return;
+    }
+    let position = transaction_ids.iter()
+        .position(|transaction_id| transaction_id.parse::<i64>().is_ok_and(|timestamp| timestamp > updated_at))
+        .unwrap_or(transaction_ids.len())
+        .min(ball_entities.len());
+    ball_entities.insert(position, BallEntity::new_physics_profile(physics_profile.clone()));
+}
+
 pub fn generate_word(vowels: &[char], consonants: &[char]) -> String {
     let mut rng = rand::thread_rng();
     let mut word = String::new();
@@ -65,3 +206,18 @@ pub fn generate_word(vowels: &[char], consonants: &[char]) -> String {
 
     word
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_msgpack_media_type() {
+        assert!(is_msgpack_media_type("application/msgpack"));
+        assert!(is_msgpack_media_type("application/json;q=0.5, application/x-msgpack"));
+        assert!(is_msgpack_media_type("Application/MsgPack; charset=binary"));
+        assert!(!is_msgpack_media_type("application/json"));
+        assert!(!is_msgpack_media_type("*/*"));
+    }
+}
\ No newline at end of file
@@ -1,20 +1,79 @@
-use std::{sync::Arc, fs, collections::HashMap};
+use std::{sync::{Arc, Mutex}, fs, collections::HashMap};
 use chrono::{self, Utc};
-use redb::{Database, ReadableTable, TableDefinition};
+use redb::{Database, ReadableTable, Table, TableDefinition, WriteTransaction};
 use crate::domain::errors::my_error::MyError;
 use uuid::Uuid;
 use crate::domain::models::ball_entity::BallEntity;
+use crate::domain::models::idempotency_record_entity::IdempotencyRecordEntity;
+use crate::domain::models::physics_profile_entity::PhysicsProfileEntity;
+use crate::domain::models::palette_entity::PaletteEntity;
+use crate::domain::models::surface_entity::SurfaceEntity;
+use crate::domain::models::globe_metadata_entity::GlobeMetadataEntity;
+use shared::domain::alive_set_hash::alive_set_hash;
 use log::{info, debug};
 use std::path::Path;
+use std::ops::Bound;
 
 pub const TABLE_LOG: TableDefinition<&str, &str> = TableDefinition::new("knotter_log");
+pub const TABLE_IDEMPOTENCY: TableDefinition<&str, &str> = TableDefinition::new("knotter_idempotency");
+pub const TABLE_PHYSICS_PROFILE: TableDefinition<&str, &str> = TableDefinition::new("knotter_physics_profile");
+pub const TABLE_PALETTE: TableDefinition<&str, &str> = TableDefinition::new("knotter_palette");
+pub const TABLE_SURFACE: TableDefinition<&str, &str> = TableDefinition::new("knotter_surface");
+pub const TABLE_SURFACE_IMAGE: TableDefinition<&str, &[u8]> = TableDefinition::new("knotter_surface_image");
+pub const TABLE_GLOBE_METADATA: TableDefinition<&str, &str> = TableDefinition::new("knotter_globe_metadata");
+
+// Alive objects by uuid, with the key of the log entry that inserted them
+pub type AliveObjects = HashMap<Uuid, (String, BallEntity)>;
+
+// The log replayed up to some transaction
+#[derive(Default)]
+pub struct LogSnapshot {
+    pub alive_objects: AliveObjects,
+    // With their keys, in the order of the log
+    pub physics_profile_changes: Vec<(String, BallEntity)>,
+    // Key of the last log entry read
+    pub last_key: Option<String>,
+}
+
+// A retried request gets the stored record of the first one instead of a new log entry
+pub enum LogWrite<T = String> {
+    Stored(T),
+    Replayed(IdempotencyRecordEntity),
+}
+
+// Everything a new globe starts with, see create_globe
+pub struct NewGlobe<'a> {
+    pub palette: &'a PaletteEntity,
+    pub surface: &'a SurfaceEntity,
+    pub surface_image: Option<&'a [u8]>,
+    pub globe_metadata: &'a GlobeMetadataEntity,
+    pub ball_entities: &'a [BallEntity],
+}
+
+// Builds the stored response from the transaction id of the new log entry
+pub type BuildIdempotencyRecord<'a> = &'a dyn Fn(&str) -> Result<IdempotencyRecordEntity, MyError>;
+
+// Builds the stored response from the transaction ids of the new log entries
+pub type BuildBatchIdempotencyRecord<'a> = &'a dyn Fn(&[String]) -> Result<IdempotencyRecordEntity, MyError>;
 
 pub struct KeyValueStore {
     db: Arc<Database>,
+    alive_set_cache: Mutex<HashMap<String, CachedAliveSet>>,
+}
+
+// Alive objects of a globe up to and including the log entry `last_key`,
+// so the alive set hash only has to replay the entries written since
+struct CachedAliveSet {
+    last_key: String,
+    alive_objects: HashMap<Uuid, BallEntity>,
 }
 
 pub trait KeyValueStoreTrait {
     fn get_alive_objects_map(&self, globe_id: &str) -> Result<HashMap<Uuid, BallEntity>, MyError>;
+    // The default profile if the globe has never had one set
+    fn get_physics_profile(&self, globe_id: &str) -> Result<PhysicsProfileEntity, MyError>;
+    // An empty palette that is not enforced if the globe has never had one set
+    fn get_palette(&self, globe_id: &str) -> Result<PaletteEntity, MyError>;
     // Add other methods here as needed...
 }
 
@@ -32,12 +91,7 @@ impl KeyValueStoreTrait for KeyValueStore {
         for item in iter {
             match item {
                 Ok((_key, value)) => {
-                    let data = Self::parse_log_json(value.value())?;
-                    if data.is_insert {
-                        map_alive_objects.insert(data.uuid, data);
-                    } else {
-                        map_alive_objects.remove(&data.uuid);
-                    }
+                    Self::apply_log_entry(&mut map_alive_objects, Self::parse_log_json(value.value())?);
                 }
                 Err(err) => {
                     return Err(MyError::DatabaseError(format!("Fetching of data failed: {}", err)))
@@ -48,37 +102,298 @@ impl KeyValueStoreTrait for KeyValueStore {
         Ok(map_alive_objects)
     }   
 
+    fn get_physics_profile(&self, globe_id: &str) -> Result<PhysicsProfileEntity, MyError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(TABLE_PHYSICS_PROFILE)?;
+
+        let physics_profile = match table.get(globe_id)? {
+            Some(value) => serde_json::from_str::<PhysicsProfileEntity>(value.value())?,
+            None => PhysicsProfileEntity::default(),
+        };
+
+        Ok(physics_profile)
+    }
+
+    fn get_palette(&self, globe_id: &str) -> Result<PaletteEntity, MyError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(TABLE_PALETTE)?;
+
+        let palette = match table.get(globe_id)? {
+            Some(value) => serde_json::from_str::<PaletteEntity>(value.value())?,
+            None => PaletteEntity::default(),
+        };
+
+        Ok(palette)
+    }
+
 }
 
 impl KeyValueStore {
     pub fn new(db: Arc<Database>) -> Self {
-        KeyValueStore { db }
+        KeyValueStore { db, alive_set_cache: Mutex::new(HashMap::new()) }
+    }
+
+    // Changes of the physics profile are in the log but never alive
+    fn apply_log_entry(map_alive_objects: &mut HashMap<Uuid, BallEntity>, data: BallEntity) {
+        if data.physics_profile.is_some() {
+            return;
+        }
+        if data.is_insert {
+            map_alive_objects.insert(data.uuid, data);
+        } else {
+            map_alive_objects.remove(&data.uuid);
+            // Links go with the balls they connect
+            map_alive_objects.retain(|_, alive_object| !alive_object.is_link_to(&data.uuid));
+        }
     }
     
-    pub fn add_delete_to_log(&self, globe_id: &str, serialized_data: &str) -> Result<(), MyError> {
-        let (key, _nanoseconds_since_epoch) = Self::generate_log_key(globe_id);
+    pub fn add_delete_to_log(&self, globe_id: &str, serialized_data: &str, idempotency_key: Option<&str>, validate: &dyn Fn() -> Result<(), MyError>, build_idempotency_record: BuildIdempotencyRecord) -> Result<LogWrite, MyError> {
+        self.add_to_log(globe_id, serialized_data, idempotency_key, validate, build_idempotency_record)
+    }
+
+    pub fn add_insert_to_log(&self, globe_id: &str, serialized_data: &str, idempotency_key: Option<&str>, validate: &dyn Fn() -> Result<(), MyError>, build_idempotency_record: BuildIdempotencyRecord) -> Result<LogWrite, MyError> {
+        debug!("KeyValueStore add_insert_to_log START");
+        let log_write = self.add_to_log(globe_id, serialized_data, idempotency_key, validate, build_idempotency_record)?;
+        debug!("KeyValueStore add_insert_to_log END");
+        Ok(log_write)
+    }
+
+    fn add_to_log(&self, globe_id: &str, serialized_data: &str, idempotency_key: Option<&str>, validate: &dyn Fn() -> Result<(), MyError>, build_idempotency_record: BuildIdempotencyRecord) -> Result<LogWrite, MyError> {
+        let write = |write_txn: &WriteTransaction| -> Result<String, MyError> {
+            let mut table = write_txn.open_table(TABLE_LOG)?;
+            let transaction_id = Self::allocate_log_timestamps(&table, globe_id, 1)?.remove(0);
+            table.insert(&*self.construct_log_key(globe_id, &transaction_id), serialized_data)?;
+            Ok(transaction_id)
+        };
+        self.write_to_log_once(globe_id, idempotency_key, validate, &write, &|transaction_id: &String| build_idempotency_record(transaction_id))
+    }
+
+    // Like add_batch_to_log, with the Idempotency-Key handled the same way as for a single entry
+    pub fn add_batch_to_log_once(&self, globe_id: &str, ball_entities: &[BallEntity], idempotency_key: Option<&str>, validate: &dyn Fn() -> Result<(), MyError>, build_idempotency_record: BuildBatchIdempotencyRecord) -> Result<LogWrite<Vec<String>>, MyError> {
+        let write = |write_txn: &WriteTransaction| Self::write_batch_to_log(write_txn, globe_id, ball_entities);
+        self.write_to_log_once(globe_id, idempotency_key, validate, &write, &|transaction_ids: &Vec<String>| build_idempotency_record(transaction_ids))
+    }
+
+    // The Idempotency-Key lookup, the validation, the log entries and the idempotency record all happen in one write transaction.
+    // Writers are serialized, so a concurrent retry waits and then finds the record instead of also missing it.
+    // The record is built from the transaction ids, which are only known inside the transaction.
+    fn write_to_log_once<T>(&self, globe_id: &str, idempotency_key: Option<&str>, validate: &dyn Fn() -> Result<(), MyError>, write: &dyn Fn(&WriteTransaction) -> Result<T, MyError>, build_idempotency_record: &dyn Fn(&T) -> Result<IdempotencyRecordEntity, MyError>) -> Result<LogWrite<T>, MyError> {
+        let write_txn = self.db.begin_write()?;
+        let written = {
+            let mut idempotency_table = write_txn.open_table(TABLE_IDEMPOTENCY)?;
+            if let Some(idempotency_key) = idempotency_key {
+                let key = self.construct_log_key(globe_id, idempotency_key);
+                let record = match idempotency_table.get(&*key)? {
+                    Some(value) => Some(serde_json::from_str::<IdempotencyRecordEntity>(value.value())?),
+                    None => None,
+                };
+                if let Some(record) = record.filter(|record| !record.is_expired()) {
+                    return Ok(LogWrite::Replayed(record));
+                }
+            }
+
+            validate()?;
+
+            let written = write(&write_txn)?;
+            if let Some(idempotency_key) = idempotency_key {
+                idempotency_table.insert(&*self.construct_log_key(globe_id, idempotency_key), &*serde_json::to_string(&build_idempotency_record(&written)?)?)?;
+            }
+            written
+        };
+        write_txn.commit()?;
+        Ok(LogWrite::Stored(written))
+    }
+
+    // The change is written to the log, so replays see the rules in force at every transaction.
+    // The latest profile is also kept by globe id for quick reads. Returns it with updated_at set to its transaction time.
+    pub fn set_physics_profile(&self, globe_id: &str, physics_profile: &PhysicsProfileEntity) -> Result<PhysicsProfileEntity, MyError> {
+        let write_txn = self.db.begin_write()?;
+        Self::write_batch_to_log(&write_txn, globe_id, &[BallEntity::new_physics_profile(physics_profile.clone())])?;
+        write_txn.commit()?;
+        self.get_physics_profile(globe_id)
+    }
+
+    pub fn set_palette(&self, globe_id: &str, palette: &PaletteEntity) -> Result<(), MyError> {
         let write_txn = self.db.begin_write()?;
         {
-            let mut table = write_txn.open_table(TABLE_LOG)?;
-            table.insert(&*key, serialized_data)?;
+            let mut table = write_txn.open_table(TABLE_PALETTE)?;
+            table.insert(globe_id, &*serde_json::to_string(palette)?)?;
         }
         write_txn.commit()?;
         Ok(())
     }
 
-    pub fn add_insert_to_log(&self, globe_id: &str, serialized_data: &str, timestamp: &str) -> Result<(), MyError> {
-        debug!("KeyValueStore add_insert_to_log START");
-        let key = self.construct_log_key(globe_id, timestamp);
+    // A plain surface if the globe has never had one set
+    pub fn get_surface(&self, globe_id: &str) -> Result<SurfaceEntity, MyError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(TABLE_SURFACE)?;
+
+        let surface = match table.get(globe_id)? {
+            Some(value) => serde_json::from_str::<SurfaceEntity>(value.value())?,
+            None => SurfaceEntity::default(),
+        };
+
+        Ok(surface)
+    }
+
+    pub fn set_surface(&self, globe_id: &str, surface: &SurfaceEntity) -> Result<(), MyError> {
         let write_txn = self.db.begin_write()?;
         {
-            let mut table = write_txn.open_table(TABLE_LOG)?;
-            table.insert(&*key, serialized_data)?;
+            let mut table = write_txn.open_table(TABLE_SURFACE)?;
+            table.insert(globe_id, &*serde_json::to_string(surface)?)?;
         }
         write_txn.commit()?;
-        debug!("KeyValueStore add_insert_to_log END");
         Ok(())
-    }  
+    }
+
+    // The image and the surface that refers to it are stored in one transaction
+    pub fn set_surface_image(&self, globe_id: &str, surface: &SurfaceEntity, image: &[u8]) -> Result<(), MyError> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(TABLE_SURFACE)?;
+            table.insert(globe_id, &*serde_json::to_string(surface)?)?;
+            let mut image_table = write_txn.open_table(TABLE_SURFACE_IMAGE)?;
+            image_table.insert(globe_id, image)?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    pub fn get_surface_image(&self, globe_id: &str) -> Result<Option<Vec<u8>>, MyError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(TABLE_SURFACE_IMAGE)?;
+
+        let image = table.get(globe_id)?.map(|value| value.value().to_vec());
+
+        Ok(image)
+    }
+
+    // Empty metadata if the globe has never had any set
+    pub fn get_globe_metadata(&self, globe_id: &str) -> Result<GlobeMetadataEntity, MyError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(TABLE_GLOBE_METADATA)?;
+
+        let globe_metadata = match table.get(globe_id)? {
+            Some(value) => serde_json::from_str::<GlobeMetadataEntity>(value.value())?,
+            None => GlobeMetadataEntity::default(),
+        };
+
+        Ok(globe_metadata)
+    }
+
+    // A globe id is taken once it has log entries or metadata. Created globes always get metadata,
+    // so an id stays taken even if the globe starts without entries.
+    pub fn is_globe_id_used(&self, globe_id: &str) -> Result<bool, MyError> {
+        let read_txn = self.db.begin_read()?;
+        let log_table = read_txn.open_table(TABLE_LOG)?;
+        let metadata_table = read_txn.open_table(TABLE_GLOBE_METADATA)?;
+        let used = Self::globe_id_used_in(&log_table, &metadata_table, globe_id)?;
+        Ok(used)
+    }
+
+    fn globe_id_used_in(log_table: &impl ReadableTable<&'static str, &'static str>, metadata_table: &impl ReadableTable<&'static str, &'static str>, globe_id: &str) -> Result<bool, MyError> {
+        if metadata_table.get(globe_id)?.is_some() {
+            return Ok(true);
+        }
+        let start = format!("{}--", globe_id);
+        let end = format!("{}--{}", globe_id, "\u{10ffff}");
+        let has_log_entries = log_table.range::<&str>(start.as_str()..end.as_str())?.next().is_some();
+        Ok(has_log_entries)
+    }
+
+    // Writes a whole new globe in one transaction under an id from generate_globe_id that is unused in it,
+    // so no other writer can take the id and a failure leaves nothing behind. Returns the id and the transaction ids of the entries.
+    pub fn create_globe(&self, generate_globe_id: &dyn Fn() -> String, new_globe: &NewGlobe) -> Result<(String, Vec<String>), MyError> {
+        let write_txn = self.db.begin_write()?;
+        let globe_id = {
+            let log_table = write_txn.open_table(TABLE_LOG)?;
+            let metadata_table = write_txn.open_table(TABLE_GLOBE_METADATA)?;
+            loop {
+                let globe_id = generate_globe_id();
+                if !Self::globe_id_used_in(&log_table, &metadata_table, &globe_id)? {
+                    break globe_id;
+                }
+            }
+        };
+        {
+            write_txn.open_table(TABLE_PALETTE)?.insert(&*globe_id, &*serde_json::to_string(new_globe.palette)?)?;
+            write_txn.open_table(TABLE_SURFACE)?.insert(&*globe_id, &*serde_json::to_string(new_globe.surface)?)?;
+            if let Some(image) = new_globe.surface_image {
+                write_txn.open_table(TABLE_SURFACE_IMAGE)?.insert(&*globe_id, image)?;
+            }
+            write_txn.open_table(TABLE_GLOBE_METADATA)?.insert(&*globe_id, &*serde_json::to_string(new_globe.globe_metadata)?)?;
+        }
+        let transaction_ids = Self::write_batch_to_log(&write_txn, &globe_id, new_globe.ball_entities)?;
+        write_txn.commit()?;
+        Ok((globe_id, transaction_ids))
+    }
+
+    pub fn remove_expired_idempotency_records(&self) -> Result<usize, MyError> {
+        let write_txn = self.db.begin_write()?;
+        let removed = {
+            let mut table = write_txn.open_table(TABLE_IDEMPOTENCY)?;
+            let drained = table.drain_filter::<&str, _>(.., |_key, value| {
+                serde_json::from_str::<IdempotencyRecordEntity>(value)
+                    .map(|record| record.is_expired())
+                    .unwrap_or(true)
+            })?;
+            drained.count()
+        };
+        write_txn.commit()?;
+        Ok(removed)
+    }
     
+    // Writes all entries in one transaction so either all or none are stored.
+    // Timestamps are consecutive to keep the order of the entries in the log.
+    pub fn add_batch_to_log(&self, globe_id: &str, ball_entities: &[BallEntity]) -> Result<Vec<String>, MyError> {
+        let write_txn = self.db.begin_write()?;
+        let timestamps = Self::write_batch_to_log(&write_txn, globe_id, ball_entities)?;
+        write_txn.commit()?;
+        Ok(timestamps)
+    }
+
+    // Changes of the physics profile get their transaction time as updated_at, and the last one becomes the latest profile
+    fn write_batch_to_log(write_txn: &WriteTransaction, globe_id: &str, ball_entities: &[BallEntity]) -> Result<Vec<String>, MyError> {
+        let mut table = write_txn.open_table(TABLE_LOG)?;
+        let mut physics_profile_table = write_txn.open_table(TABLE_PHYSICS_PROFILE)?;
+        let timestamps = Self::allocate_log_timestamps(&table, globe_id, ball_entities.len())?;
+        for (ball_entity, timestamp) in ball_entities.iter().zip(&timestamps) {
+            let mut ball_entity = ball_entity.clone();
+            if let Some(physics_profile) = &mut ball_entity.physics_profile {
+                physics_profile.updated_at = Some(timestamp.parse()
+                    .map_err(|_| MyError::InternalServerError(format!("Invalid timestamp: {}", timestamp)))?);
+                physics_profile_table.insert(globe_id, &*serde_json::to_string(physics_profile)?)?;
+            }
+            table.insert(&*format!("{}--{}", globe_id, timestamp), &*serde_json::to_string(&ball_entity)?)?;
+        }
+        Ok(timestamps)
+    }
+
+    // Consecutive timestamps from now, or from after the last entry of the globe if the clock has not passed it.
+    // Allocated inside the write transaction, so the keys are unused and no other writer can take them.
+    fn allocate_log_timestamps(table: &Table<&str, &str>, globe_id: &str, count: usize) -> Result<Vec<String>, MyError> {
+        let start = format!("{}--", globe_id);
+        let end = format!("{}--{}", globe_id, "\u{10ffff}");
+        let last_timestamp = match table.range::<&str>(start.as_str()..end.as_str())?.next_back().transpose()? {
+            Some((key, _)) => {
+                let key = key.value();
+                let timestamp = key.strip_prefix(start.as_str())
+                    .and_then(|timestamp| timestamp.parse::<i64>().ok())
+                    .ok_or(MyError::InternalServerError(format!("Invalid log key: {}", key)))?;
+                Some(timestamp)
+            },
+            None => None,
+        };
+
+        let now = Utc::now();
+        let now_nanos = now.timestamp_subsec_nanos() as i64 + now.timestamp() * 1_000_000_000;
+        let first_timestamp = last_timestamp.map_or(now_nanos, |last_timestamp| now_nanos.max(last_timestamp + 1));
+
+        Ok((0..count as i64)
+            .map(|offset| (first_timestamp + offset).to_string())
+            .collect())
+    }
+
     // This function now only constructs the key given a timestamp
     fn construct_log_key(&self, globe_id: &str, timestamp: &str) -> String {
         format!("{}--{}", globe_id, timestamp)
@@ -114,50 +429,47 @@ impl KeyValueStore {
         let txn = db.begin_write().unwrap();
         {
             let _table_log = txn.open_table(TABLE_LOG).unwrap();
+            let _table_idempotency = txn.open_table(TABLE_IDEMPOTENCY).unwrap();
+            let _table_physics_profile = txn.open_table(TABLE_PHYSICS_PROFILE).unwrap();
+            let _table_palette = txn.open_table(TABLE_PALETTE).unwrap();
+            let _table_surface = txn.open_table(TABLE_SURFACE).unwrap();
+            let _table_surface_image = txn.open_table(TABLE_SURFACE_IMAGE).unwrap();
+            let _table_globe_metadata = txn.open_table(TABLE_GLOBE_METADATA).unwrap();
         }
         txn.commit().unwrap();
 
         Ok(Arc::new(db))
     }
 
-    pub fn generate_log_key(globe_id: &str) -> (String, String) {
-        let now = Utc::now();
-        let nanoseconds_since_epoch = (now.timestamp_subsec_nanos() as i64 + now.timestamp() * 1_000_000_000).to_string();
-        let key = format!("{}--{}", globe_id, nanoseconds_since_epoch);
-
-        (key, nanoseconds_since_epoch)
-    }
- 
     fn parse_log_json(json_str: &str) -> Result<BallEntity, MyError> {
         serde_json::from_str(json_str).map_err(|err| MyError::JsonError(err.to_string()))
     }
 
-    pub fn get_log_data(&self, globe_id: &str, transaction_id: &str) -> Result<Vec<(String, String)>, MyError> {
+    // Returns up to `limit` log rows after the `after` transaction id (from the start if None),
+    // and whether there are more rows after the returned ones.
+    pub fn get_log_data(&self, globe_id: &str, after: Option<&str>, limit: usize) -> Result<(Vec<(String, String)>, bool), MyError> {
         let read_txn = self.db.begin_read()?;
         let table = read_txn.open_table(TABLE_LOG)?;
     
-        let mut start = format!("{}--", globe_id);
-        if transaction_id != "0" {
-            start = format!("{}{}", start, transaction_id);
-        }
-    
+        let start = match after {
+            Some(transaction_id) => Bound::Excluded(self.construct_log_key(globe_id, transaction_id)),
+            None => Bound::Included(format!("{}--", globe_id)),
+        };
         let end = format!("{}--{}", globe_id, "\u{10ffff}");
     
-        let range = table.range::<&str>(start.as_str()..end.as_str())?;
-    
-        let results: Vec<_> = if transaction_id == "0" {
-            // Collect only the first ten rows
-            range.take(10).collect()
-        } else {
-            // skip the first row, and then collect ten rows
-            range.skip(1).take(10).collect()
-        };
+        let range = table.range::<&str>((start.as_ref().map(|key| key.as_str()), Bound::Excluded(end.as_str())))?;
     
+        // Fetch one extra row to find out if there is more data after this page
         let mut response_data = Vec::new();
+        let mut has_more = false;
     
-        for item in results {
+        for item in range {
             match item {
                 Ok((key, value)) => {
+                    if response_data.len() == limit {
+                        has_more = true;
+                        break;
+                    }
                     response_data.push((key.value().to_string(), value.value().to_string()));
                 },
                 Err(err) => {
@@ -166,7 +478,87 @@ impl KeyValueStore {
             }
         }
     
-        Ok(response_data)
+        Ok((response_data, has_more))
     }
-    
+
+    // Replays the log up to and including the transaction `until`, or the whole log if None.
+    pub fn get_alive_objects_snapshot(&self, globe_id: &str, until: Option<&str>) -> Result<LogSnapshot, MyError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(TABLE_LOG)?;
+
+        let start = format!("{}--", globe_id);
+        let end = match until {
+            Some(transaction_id) => Bound::Included(self.construct_log_key(globe_id, transaction_id)),
+            None => Bound::Excluded(format!("{}--{}", globe_id, "\u{10ffff}")),
+        };
+
+        let range = table.range::<&str>((Bound::Included(start.as_str()), end.as_ref().map(|key| key.as_str())))?;
+
+        let mut log_snapshot = LogSnapshot::default();
+        for item in range {
+            match item {
+                Ok((key, value)) => {
+                    let data = Self::parse_log_json(value.value())?;
+                    if data.physics_profile.is_some() {
+                        log_snapshot.physics_profile_changes.push((key.value().to_string(), data));
+                    } else if data.is_insert {
+                        log_snapshot.alive_objects.insert(data.uuid, (key.value().to_string(), data));
+                    } else {
+                        log_snapshot.alive_objects.remove(&data.uuid);
+                        log_snapshot.alive_objects.retain(|_, (_, alive_object)| !alive_object.is_link_to(&data.uuid));
+                    }
+                    log_snapshot.last_key = Some(key.value().to_string());
+                },
+                Err(err) => {
+                    return Err(MyError::DatabaseError(format!("Fetching of data failed: {}", err)));
+                }
+            }
+        }
+
+        Ok(log_snapshot)
+    }
+
+    // Hash of the alive set up to and including the transaction `until`.
+    // Continues from the cached alive set of the globe when `until` is not before it, so polls do not replay the whole log.
+    pub fn get_alive_set_hash(&self, globe_id: &str, until: &str) -> Result<u64, MyError> {
+        let until_key = self.construct_log_key(globe_id, until);
+        // Taken out while replaying, a concurrent poll of the same globe replays from the start instead of waiting
+        let cached_alive_set = {
+            let mut alive_set_cache = self.lock_alive_set_cache()?;
+            match alive_set_cache.get(globe_id) {
+                Some(cached_alive_set) if cached_alive_set.last_key <= until_key => alive_set_cache.remove(globe_id),
+                _ => None,
+            }
+        };
+        let (start, mut map_alive_objects) = match cached_alive_set {
+            Some(cached_alive_set) => (Bound::Excluded(cached_alive_set.last_key), cached_alive_set.alive_objects),
+            None => (Bound::Included(format!("{}--", globe_id)), HashMap::new()),
+        };
+
+        {
+            let read_txn = self.db.begin_read()?;
+            let table = read_txn.open_table(TABLE_LOG)?;
+            let range = table.range::<&str>((start.as_ref().map(|key| key.as_str()), Bound::Included(until_key.as_str())))?;
+            for item in range {
+                let (_key, value) = item
+                    .map_err(|err| MyError::DatabaseError(format!("Fetching of data failed: {}", err)))?;
+                Self::apply_log_entry(&mut map_alive_objects, Self::parse_log_json(value.value())?);
+            }
+        }
+
+        let hash = alive_set_hash(map_alive_objects.keys());
+        let mut alive_set_cache = self.lock_alive_set_cache()?;
+        let is_newer = alive_set_cache.get(globe_id)
+            .is_none_or(|cached_alive_set| cached_alive_set.last_key < until_key);
+        if is_newer {
+            alive_set_cache.insert(globe_id.to_string(), CachedAliveSet { last_key: until_key, alive_objects: map_alive_objects });
+        }
+        Ok(hash)
+    }
+
+    fn lock_alive_set_cache(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, CachedAliveSet>>, MyError> {
+        self.alive_set_cache.lock()
+            .map_err(|err| MyError::InternalServerError(err.to_string()))
+    }
+
 }
@@ -0,0 +1,99 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Result};
+use std::cell::RefCell;
+use std::sync::Arc;
+use crate::domain::errors::my_error::MyError;
+use shared::domain::dtos::batch_request_dto::{BatchRequestDto, MAX_BATCH_SIZE};
+use shared::domain::dtos::batch_response_dto::{BatchResponseDto, BatchItemResultDto};
+use crate::domain::mapping::ball_mapper::dto_to_entity;
+use crate::domain::models::ball_entity::BallEntity;
+use crate::helpers::*;
+use actix_web::post;
+use crate::application::services::validation_service::ValidationService;
+use crate::infrastructure::database::key_value_store::{KeyValueStore, LogWrite};
+use crate::domain::models::idempotency_record_entity::IdempotencyRecordEntity;
+use log::debug;
+
+#[post("/{globe_id}/batch")]
+pub async fn handle_batch(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+    body: web::Bytes,
+    validation_service: web::Data<Arc<ValidationService>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+    let idempotency_key = process_idempotency_key(&req)?;
+    let wire_format = negotiate_wire_format(&req);
+    let batch_request_dto: BatchRequestDto = decode_request_body(&req, &body)?;
+    debug!("handle_batch START. globe_id={}, number of balls={}", globe_id, batch_request_dto.balls.len());
+
+    if batch_request_dto.balls.is_empty() {
+        return Err(MyError::ValidationError("Batch is empty.".to_string()));
+    }
+    if batch_request_dto.balls.len() > MAX_BATCH_SIZE {
+        return Err(MyError::ValidationError(format!("Batch can not have more than {} balls.", MAX_BATCH_SIZE)));
+    }
+
+    // A delete only needs the uuid, the same as a delete through delete_data
+    let ball_entities: Vec<BallEntity> = batch_request_dto.balls
+        .iter()
+        .map(|ball_dto| if ball_dto.is_insert {
+            dto_to_entity(ball_dto)
+        } else {
+            BallEntity::new(ball_dto.uuid, false)
+        })
+        .collect();
+
+    let serialized_data = serde_json::to_string(&ball_entities)?;
+    let build_response = |validation_results: Vec<Result<(), MyError>>, transaction_ids: Vec<Option<String>>| {
+        let committed = validation_results.iter().all(|result| result.is_ok());
+        let results = ball_entities
+            .iter()
+            .zip(validation_results)
+            .zip(transaction_ids)
+            .map(|((ball_entity, validation_result), transaction_id)| BatchItemResultDto {
+                uuid: ball_entity.uuid,
+                transaction_id,
+                error: validation_result.err().map(|err| err.to_string()),
+            })
+            .collect();
+
+        BatchResponseDto {
+            message: if committed { "Successfully committed batch.".to_string() } else { "Batch rejected, nothing was stored.".to_string() },
+            globe_id: globe_id.clone(),
+            committed,
+            results,
+        }
+    };
+    // Only committed batches are stored for the Idempotency-Key, a rejected one can be fixed and sent again
+    let build_idempotency_record = |transaction_ids: &[String]| -> Result<IdempotencyRecordEntity, MyError> {
+        let response = build_response(ball_entities.iter().map(|_| Ok(())).collect(), transaction_ids.iter().cloned().map(Some).collect());
+        Ok(IdempotencyRecordEntity::new(serialized_data.clone(), 200, "application/json", serde_json::to_string(&response)?))
+    };
+    // Filled by the validation inside the write transaction, so a rejection can tell which balls failed
+    let validation_results = RefCell::new(Vec::new());
+    let validate = || {
+        let results = validation_service.validate_batch(&ball_entities, &globe_id, key_value_store.as_ref().as_ref())?;
+        let committed = results.iter().all(|result| result.is_ok());
+        *validation_results.borrow_mut() = results;
+        if committed { Ok(()) } else { Err(MyError::ValidationError("Batch rejected.".to_string())) }
+    };
+
+    let response = match key_value_store.add_batch_to_log_once(&globe_id, &ball_entities, idempotency_key.as_deref(), &validate, &build_idempotency_record) {
+        Ok(LogWrite::Stored(transaction_ids)) => build_response(validation_results.take(), transaction_ids.into_iter().map(Some).collect()),
+        Ok(LogWrite::Replayed(record)) => {
+            debug!("handle_batch replaying response for Idempotency-Key {:?}", idempotency_key);
+            return replay_idempotency_record_as::<BatchResponseDto>(&record, &serialized_data, wire_format);
+        },
+        Err(MyError::ValidationError(_)) if validation_results.borrow().iter().any(|result| result.is_err()) => {
+            build_response(validation_results.take(), vec![None; ball_entities.len()])
+        },
+        Err(err) => return Err(err),
+    };
+    let committed = response.committed;
+
+    debug!("handle_batch response: {:?}", response);
+
+    let status = if committed { StatusCode::OK } else { StatusCode::BAD_REQUEST };
+    encode_response(status, wire_format, &response)
+}
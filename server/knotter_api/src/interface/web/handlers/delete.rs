@@ -1,30 +1,41 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
 use std::sync::Arc;
 use uuid::Uuid;
 use crate::domain::errors::my_error::MyError;
 use crate::helpers::*;
 use actix_web::delete;
 use crate::application::services::validation_service::ValidationService;
-use crate::infrastructure::database::key_value_store::KeyValueStore;
+use crate::infrastructure::database::key_value_store::{KeyValueStore, LogWrite};
 use crate::domain::models::ball_entity::BallEntity;
+use crate::domain::models::idempotency_record_entity::IdempotencyRecordEntity;
 use log::debug;
 
 #[delete("/{globe_id}/{object_uuid}")]
 async fn delete_data(
+    req: HttpRequest,
     path_info: web::Path<(String, Uuid)>,
     key_value_store: web::Data<Arc<KeyValueStore>>,
 ) -> Result<HttpResponse, MyError> {
     let (globe_id, object_uuid) = path_info.into_inner();
     debug!("delete_data START. globe_id={}, object_uuid={:?}", globe_id, object_uuid);
     let globe_id = process_globe_id(&globe_id)?;
-
-    ValidationService::validate_delete(&object_uuid, &globe_id, key_value_store.as_ref().as_ref())?;
+    let idempotency_key = process_idempotency_key(&req)?;
 
     let delete_ball_entity = BallEntity::new(object_uuid, false);
     let serialized_data = serde_json::to_string(&delete_ball_entity)?; 
 
+    let body = format!("Successfully deleted: Globe ID: {}, Object_uuid: {}", globe_id, object_uuid);
+    let build_idempotency_record = |_transaction_id: &str| -> Result<IdempotencyRecordEntity, MyError> {
+        Ok(IdempotencyRecordEntity::new(serialized_data.clone(), 200, "text/plain; charset=utf-8", body.clone()))
+    };
+    let validate = || ValidationService::validate_delete(&object_uuid, &globe_id, key_value_store.as_ref().as_ref());
+
     debug!("Before key_value_store.delete. globe_id={}, serialized_data={:?}", globe_id, serialized_data);
-    key_value_store.add_delete_to_log(&globe_id, &serialized_data)?;
+    // A retried request gets the original response instead of failing because the ball is already deleted
+    if let LogWrite::Replayed(record) = key_value_store.add_delete_to_log(&globe_id, &serialized_data, idempotency_key.as_deref(), &validate, &build_idempotency_record)? {
+        debug!("delete_data replaying response for Idempotency-Key {:?}", idempotency_key);
+        return replay_idempotency_record(&record, &serialized_data);
+    }
 
-    Ok(HttpResponse::Ok().body(format!("Successfully deleted: Globe ID: {}, Object_uuid: {}", globe_id, object_uuid)))
+    Ok(HttpResponse::Ok().content_type("text/plain; charset=utf-8").body(body))
 }
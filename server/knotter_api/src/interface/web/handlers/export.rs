@@ -0,0 +1,141 @@
+use actix_web::{http::StatusCode, web, HttpResponse, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::domain::errors::my_error::MyError;
+use crate::domain::models::ball_entity::BallEntity;
+use crate::domain::models::surface_entity::{SurfaceEntity, SurfaceTextureEntity};
+use crate::domain::models::physics_profile_entity::PhysicsProfileEntity;
+use crate::domain::models::palette_entity::PaletteEntity;
+use crate::domain::models::globe_metadata_entity::GlobeMetadataEntity;
+use crate::domain::mapping::{ball_mapper, physics_profile_mapper, palette_mapper, surface_mapper};
+use crate::application::services::gltf_exporter::{export_glb, GLB_CONTENT_TYPE};
+use shared::domain::dtos::globe_export_dto::{GlobeExportDto, GLOBE_EXPORT_VERSION};
+use shared::domain::dtos::ball_transaction_dto::BallTransactionDto;
+use shared::domain::dtos::batch_response_dto::{BatchResponseDto, BatchItemResultDto};
+use crate::helpers::*;
+use actix_web::get;
+use crate::application::services::validation_service::ValidationService;
+use crate::infrastructure::database::key_value_store::{KeyValueStore, KeyValueStoreTrait, NewGlobe};
+use log::debug;
+
+// The alive set as a binary glTF, moving balls where they were inserted
+#[get("/{globe_id}/export.glb")]
+async fn get_export_glb(
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+
+    let mut ball_entities: Vec<BallEntity> = key_value_store.get_alive_objects_map(&globe_id)?.into_values().collect();
+    ball_entities.sort_by_key(|ball_entity| ball_entity.uuid);
+
+    Ok(HttpResponse::Ok()
+        .content_type(GLB_CONTENT_TYPE)
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}.glb\"", globe_id)))
+        .body(export_glb(&globe_id, &ball_entities)?))
+}
+
+// The settings and the whole log of the globe as a versioned JSON document
+#[get("/{globe_id}/export")]
+async fn get_globe_export(
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+
+    let (log_data, _) = key_value_store.get_log_data(&globe_id, None, usize::MAX)?;
+    let transactions = log_data.iter()
+        .map(|(key, value)| {
+            let ball_entity: BallEntity = serde_json::from_str(value)?;
+            let transaction_id = get_after_dashdash(key)
+                .ok_or(MyError::ValidationError("Invalid transaction key format".to_string()))?;
+            Ok(BallTransactionDto { transaction_id: transaction_id.to_string(), ball_dto: ball_mapper::entity_to_dto(&ball_entity) })
+        })
+        .collect::<Result<Vec<_>, MyError>>()?;
+
+    let globe_export = GlobeExportDto {
+        version: GLOBE_EXPORT_VERSION,
+        globe_id: globe_id.clone(),
+        exported_at: generate_timestamp_nanos(),
+        physics_profile: physics_profile_mapper::entity_to_dto(&key_value_store.get_physics_profile(&globe_id)?),
+        palette: palette_mapper::entity_to_dto(&key_value_store.get_palette(&globe_id)?),
+        surface: surface_mapper::entity_to_dto(&key_value_store.get_surface(&globe_id)?),
+        transactions,
+    };
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}.json\"", globe_id)))
+        .json(globe_export))
+}
+
+// Exports hold the whole log, so imports get a larger body than the other requests
+pub const IMPORT_PAYLOAD_LIMIT: usize = 64 * 1024 * 1024;
+
+// Recreates an exported globe under a new id. The log is validated entry by entry in its order
+// and stored as one batch, so either the whole globe or nothing is created.
+// Globes with an uploaded surface get a plain one, the image is not in the document.
+// Registered as a resource with its own payload limit, see IMPORT_PAYLOAD_LIMIT.
+pub async fn import_globe(
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+    body: web::Bytes,
+    validation_service: web::Data<Arc<ValidationService>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_export: GlobeExportDto = serde_json::from_slice(&body)?;
+    debug!("import_globe START. globe_id={}, number of transactions={}", globe_export.globe_id, globe_export.transactions.len());
+
+    if globe_export.version != GLOBE_EXPORT_VERSION {
+        return Err(MyError::ValidationError(format!("Unsupported export version {}, expected {}.", globe_export.version, GLOBE_EXPORT_VERSION)));
+    }
+
+    let physics_profile = physics_profile_mapper::dto_to_entity(&globe_export.physics_profile);
+    validation_service.validate_physics_profile(&physics_profile)?;
+    let palette = palette_mapper::dto_to_entity(&globe_export.palette);
+    validation_service.validate_palette(&palette)?;
+    let mut surface = surface_mapper::dto_to_entity(&globe_export.surface, &SurfaceEntity::default());
+    if surface.texture == SurfaceTextureEntity::Uploaded {
+        surface.texture = SurfaceTextureEntity::Plain;
+    }
+    validation_service.validate_surface(&surface)?;
+
+    // Entries are checked against the profile in force when they were written, the default one until the first change.
+    // The palette may have changed since, so historical colors are only checked for their format.
+    let mut ball_entities: Vec<BallEntity> = globe_export.transactions.iter()
+        .map(|transaction| ball_mapper::dto_to_entity(&transaction.ball_dto))
+        .collect();
+    let transaction_ids: Vec<String> = globe_export.transactions.iter()
+        .map(|transaction| transaction.transaction_id.clone())
+        .collect();
+    insert_unlogged_physics_profile(&mut ball_entities, &transaction_ids, &physics_profile);
+    let results = validation_service.validate_batch_against(&ball_entities, HashMap::new(), &PhysicsProfileEntity::default(), &PaletteEntity::default());
+    let committed = results.iter().all(|result| result.is_ok());
+
+    let (globe_id, transaction_ids) = if committed {
+        let (globe_id, transaction_ids) = key_value_store.create_globe(&generate_globe_id, &NewGlobe {
+            palette: &palette,
+            surface: &surface,
+            surface_image: None,
+            globe_metadata: &GlobeMetadataEntity::default(),
+            ball_entities: &ball_entities,
+        })?;
+        (globe_id, transaction_ids)
+    } else {
+        (String::new(), Vec::new())
+    };
+    let mut transaction_ids = transaction_ids.into_iter();
+
+    let response = BatchResponseDto {
+        message: if committed { "Successfully imported globe.".to_string() } else { "Import rejected, nothing was stored.".to_string() },
+        globe_id,
+        committed,
+        results: ball_entities.iter().zip(results)
+            .map(|(ball_entity, result)| BatchItemResultDto {
+                uuid: ball_entity.uuid,
+                transaction_id: transaction_ids.next(),
+                error: result.err().map(|err| err.to_string()),
+            })
+            .collect(),
+    };
+
+    let status = if committed { StatusCode::OK } else { StatusCode::BAD_REQUEST };
+    Ok(HttpResponse::build(status).json(response))
+}
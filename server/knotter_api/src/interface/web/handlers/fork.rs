@@ -0,0 +1,93 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Result};
+use std::sync::Arc;
+use serde::Deserialize;
+use crate::domain::errors::my_error::MyError;
+use crate::domain::models::ball_entity::BallEntity;
+use crate::domain::models::globe_metadata_entity::{GlobeMetadataEntity, ProvenanceEntity};
+use crate::domain::mapping::globe_metadata_mapper::entity_to_dto;
+use shared::domain::dtos::get_new_globe_id_response_dto::GetNewGlobeIdResponse;
+use crate::helpers::*;
+use actix_web::{get, post};
+use crate::infrastructure::database::key_value_store::{KeyValueStore, KeyValueStoreTrait, NewGlobe};
+use log::debug;
+
+#[derive(Deserialize, Debug)]
+pub struct ForkQuery {
+    // Copy the whole log instead of only the alive set
+    history: Option<bool>,
+}
+
+// Copies the globe with its settings into a new globe that can be changed without affecting the original.
+// Copied entries get new transaction ids in their order, so moving balls start again from where they were inserted.
+#[post("/{globe_id}/fork")]
+async fn fork_globe(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    query: web::Query<ForkQuery>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+    let with_history = query.history.unwrap_or(false);
+    debug!("fork_globe START. globe_id={}, with_history={}", globe_id, with_history);
+
+    let physics_profile = key_value_store.get_physics_profile(&globe_id)?;
+    let (keys, mut ball_entities): (Vec<String>, Vec<BallEntity>) = if with_history {
+        let (log_data, _) = key_value_store.get_log_data(&globe_id, None, usize::MAX)?;
+        log_data.into_iter()
+            .map(|(key, value)| Ok((key, serde_json::from_str(&value)?)))
+            .collect::<Result<Vec<_>, MyError>>()?
+            .into_iter()
+            .unzip()
+    } else {
+        // In the order of the log, so links come after the balls they connect
+        let mut alive_objects: Vec<_> = key_value_store.get_alive_objects_snapshot(&globe_id, None)?.alive_objects.into_values().collect();
+        alive_objects.sort_by(|(key, _), (other_key, _)| key.cmp(other_key));
+        alive_objects.into_iter().unzip()
+    };
+    let transaction_id = keys.last()
+        .map(|key| get_after_dashdash(key)
+            .map(str::to_string)
+            .ok_or(MyError::ValidationError("Invalid transaction key format".to_string())))
+        .transpose()?;
+
+    // The copy starts with the rules in force now, or goes through the same changes with the history
+    if with_history {
+        let transaction_ids: Vec<String> = keys.iter()
+            .filter_map(|key| get_after_dashdash(key).map(str::to_string))
+            .collect();
+        insert_unlogged_physics_profile(&mut ball_entities, &transaction_ids, &physics_profile);
+    } else if physics_profile.updated_at.is_some() {
+        ball_entities.insert(0, BallEntity::new_physics_profile(physics_profile));
+    }
+
+    let (new_globe_id, _) = key_value_store.create_globe(&generate_globe_id, &NewGlobe {
+        palette: &key_value_store.get_palette(&globe_id)?,
+        surface: &key_value_store.get_surface(&globe_id)?,
+        surface_image: key_value_store.get_surface_image(&globe_id)?.as_deref(),
+        globe_metadata: &GlobeMetadataEntity {
+            forked_from: Some(ProvenanceEntity {
+                globe_id: globe_id.clone(),
+                transaction_id,
+                forked_at: generate_timestamp_nanos(),
+                with_history,
+            }),
+        },
+        ball_entities: &ball_entities,
+    })?;
+
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &GetNewGlobeIdResponse { new_globe_id })
+}
+
+// Where the globe was forked from, if it was
+#[get("/{globe_id}/metadata")]
+async fn get_globe_metadata(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+
+    let globe_metadata = key_value_store.get_globe_metadata(&globe_id)?;
+
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &entity_to_dto(&globe_metadata))
+}
@@ -0,0 +1,90 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Result};
+use std::sync::Arc;
+use crate::domain::errors::my_error::MyError;
+use shared::domain::dtos::batch_request_dto::MAX_BATCH_SIZE;
+use shared::domain::dtos::batch_response_dto::{BatchResponseDto, BatchItemResultDto};
+use shared::domain::dtos::geojson_dto::{FeatureCollectionDto, GEOJSON_CONTENT_TYPE};
+use crate::domain::mapping::geojson_mapper::{feature_to_entity, entity_to_feature};
+use crate::domain::models::ball_entity::BallEntity;
+use crate::helpers::*;
+use actix_web::{get, post};
+use crate::application::services::validation_service::ValidationService;
+use crate::infrastructure::database::key_value_store::{KeyValueStore, KeyValueStoreTrait};
+use log::debug;
+
+// The alive balls as GeoJSON points, moving balls where they were inserted
+#[get("/{globe_id}/geojson")]
+async fn get_geojson(
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+
+    let mut ball_entities: Vec<BallEntity> = key_value_store.get_alive_objects_map(&globe_id)?.into_values().collect();
+    // Same order on every export
+    ball_entities.sort_by_key(|ball_entity| ball_entity.uuid);
+    let feature_collection = FeatureCollectionDto {
+        collection_type: "FeatureCollection".to_string(),
+        features: ball_entities.iter().filter_map(entity_to_feature).collect(),
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(GEOJSON_CONTENT_TYPE)
+        .body(serde_json::to_vec(&feature_collection)?))
+}
+
+// Inserts the points of a GeoJSON FeatureCollection as one batch, so either all or none are stored.
+// The results are in the order of the features.
+#[post("/{globe_id}/geojson")]
+async fn post_geojson(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+    body: web::Bytes,
+    validation_service: web::Data<Arc<ValidationService>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+    let wire_format = negotiate_wire_format(&req);
+    let feature_collection: FeatureCollectionDto = serde_json::from_slice(&body)?;
+    debug!("post_geojson START. globe_id={}, number of features={}", globe_id, feature_collection.features.len());
+
+    if feature_collection.features.is_empty() {
+        return Err(MyError::ValidationError("FeatureCollection has no features.".to_string()));
+    }
+    if feature_collection.features.len() > MAX_BATCH_SIZE {
+        return Err(MyError::ValidationError(format!("FeatureCollection can not have more than {} features.", MAX_BATCH_SIZE)));
+    }
+
+    let converted: Vec<_> = feature_collection.features.iter().map(feature_to_entity).collect();
+    let ball_entities: Vec<BallEntity> = converted.iter()
+        .filter_map(|(_, ball_entity)| ball_entity.as_ref().ok().cloned())
+        .collect();
+    let mut validation_results = validation_service.validate_batch(&ball_entities, &globe_id, key_value_store.as_ref().as_ref())?.into_iter();
+    // Features that are not points are not validated as balls
+    let results: Vec<(uuid::Uuid, Result<(), MyError>)> = converted.into_iter()
+        .map(|(uuid, ball_entity)| (uuid, ball_entity.and_then(|_| validation_results.next().unwrap_or(Ok(())))))
+        .collect();
+    let committed = results.iter().all(|(_, result)| result.is_ok());
+
+    let mut transaction_ids = if committed {
+        key_value_store.add_batch_to_log(&globe_id, &ball_entities)?
+    } else {
+        Vec::new()
+    }.into_iter();
+
+    let response = BatchResponseDto {
+        message: if committed { "Successfully imported features.".to_string() } else { "Import rejected, nothing was stored.".to_string() },
+        globe_id,
+        committed,
+        results: results.into_iter()
+            .map(|(uuid, result)| BatchItemResultDto {
+                uuid,
+                transaction_id: transaction_ids.next(),
+                error: result.err().map(|err| err.to_string()),
+            })
+            .collect(),
+    };
+
+    let status = if committed { StatusCode::OK } else { StatusCode::BAD_REQUEST };
+    encode_response(status, wire_format, &response)
+}
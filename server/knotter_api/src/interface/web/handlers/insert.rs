@@ -1,4 +1,4 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Result};
 
 use std::sync::Arc;
 use crate::domain::errors::my_error::MyError;
@@ -8,7 +8,8 @@ use crate::domain::mapping::ball_mapper::dto_to_entity;
 use crate::helpers::*;
 use actix_web::post;
 use crate::application::services::validation_service::ValidationService;
-use crate::infrastructure::database::key_value_store::KeyValueStore;
+use crate::infrastructure::database::key_value_store::{KeyValueStore, LogWrite};
+use crate::domain::models::idempotency_record_entity::IdempotencyRecordEntity;
 use log::debug;
 /* 
 #[post("/{globe_id}")]
@@ -38,34 +39,49 @@ async fn gvtest_insert(item: Bytes) -> Result<String, MyError> {
 
 #[post("/{globe_id}")]
 pub async fn handle_insert(
+    req: HttpRequest,
     globe_id: web::Path<String>,
     key_value_store: web::Data<Arc<KeyValueStore>>,
-    data: web::Json<InsertBallDto>,
+    body: web::Bytes,
     validation_service: web::Data<Arc<ValidationService>>,
 ) -> Result<HttpResponse, MyError> {
-    debug!("handle_insert START. globe_id={}, data={:?}", globe_id, data);
+    debug!("handle_insert START. globe_id={}", globe_id);
     let globe_id = process_globe_id(&globe_id)?;
+    let idempotency_key = process_idempotency_key(&req)?;
+    let wire_format = negotiate_wire_format(&req);
     debug!("handle_insert 1");
-    let insert_ball_dto: InsertBallDto = data.into_inner();
+    let insert_ball_dto: InsertBallDto = decode_request_body(&req, &body)?;
     debug!("insert_ball_dto {:?}", insert_ball_dto);
     debug!("handle_insert 2");
     let ball_entity = dto_to_entity(&insert_ball_dto);
     debug!("ball_entity {:?}", ball_entity);
     debug!("handle_insert 3");
-    validation_service.validate_insert(&ball_entity, &globe_id, key_value_store.as_ref().as_ref())?;
-    debug!("handle_insert 4");
     let serialized_data = serde_json::to_string(&ball_entity)?; 
-    debug!("handle_insert 5");
-    let timestamp = generate_timestamp();
-    key_value_store.add_insert_to_log(&globe_id, &serialized_data, &timestamp)?;
-    debug!("handle_insert 6");
-    let response = InsertBallResponseDto {
+    let build_response = |transaction_id: &str| InsertBallResponseDto {
         message: "Successfully inserted.".to_string(),
-        globe_id,
-        transaction_id: timestamp,
+        globe_id: globe_id.clone(),
+        transaction_id: transaction_id.to_string(),
+    };
+    debug!("handle_insert 4");
+    // Stored as JSON, a replay encodes it in the format the retry asks for
+    let build_idempotency_record = |transaction_id: &str| -> Result<IdempotencyRecordEntity, MyError> {
+        let json_body = serde_json::to_string(&build_response(transaction_id))?;
+        Ok(IdempotencyRecordEntity::new(serialized_data.clone(), 200, "application/json", json_body))
     };
+    let validate = || validation_service.validate_insert(&ball_entity, &globe_id, key_value_store.as_ref().as_ref());
+    // A retried request gets the original response instead of being inserted again
+    let transaction_id = match key_value_store.add_insert_to_log(&globe_id, &serialized_data, idempotency_key.as_deref(), &validate, &build_idempotency_record)? {
+        LogWrite::Stored(transaction_id) => transaction_id,
+        LogWrite::Replayed(record) => {
+            debug!("handle_insert replaying response for Idempotency-Key {:?}", idempotency_key);
+            return replay_idempotency_record_as::<InsertBallResponseDto>(&record, &serialized_data, wire_format);
+        }
+    };
+    debug!("handle_insert 5");
+    let response = build_response(&transaction_id);
+    debug!("handle_insert 6");
     
     debug!("handle_insert response: {:?}", response);
 
-    Ok(HttpResponse::Ok().json(response))
+    encode_response(StatusCode::OK, wire_format, &response)
 }
\ No newline at end of file
@@ -1,4 +1,12 @@
 pub mod insert;
 pub mod delete;
 pub mod query;
-pub mod health_check;
\ No newline at end of file
+pub mod new_globe;
+pub mod health_check;
+pub mod batch;
+pub mod physics;
+pub mod palette;
+pub mod surface;
+pub mod geojson;
+pub mod export;
+pub mod fork;
@@ -0,0 +1,42 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Result};
+use std::sync::Arc;
+use serde::Deserialize;
+use crate::domain::errors::my_error::MyError;
+use crate::domain::models::globe_metadata_entity::GlobeMetadataEntity;
+use crate::domain::models::palette_entity::PaletteEntity;
+use crate::domain::models::surface_entity::SurfaceEntity;
+use crate::application::services::globe_templates::template_balls;
+use shared::domain::dtos::globe_template_dto::GlobeTemplateDto;
+use shared::domain::dtos::get_new_globe_id_response_dto::GetNewGlobeIdResponse;
+use crate::helpers::*;
+use actix_web::post;
+use crate::infrastructure::database::key_value_store::{KeyValueStore, NewGlobe};
+use log::debug;
+
+#[derive(Deserialize, Debug)]
+pub struct NewGlobeQuery {
+    template: Option<GlobeTemplateDto>,
+}
+
+// Creates a globe seeded with the balls of a template, or an empty one that already has its id taken.
+// GET new_globe_id only hands out an id and writes nothing.
+#[post("/new_globe")]
+async fn create_new_globe(
+    req: HttpRequest,
+    query: web::Query<NewGlobeQuery>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+) -> Result<HttpResponse, MyError> {
+    let template = query.template.unwrap_or_default();
+    debug!("create_new_globe START. template={}", template.name());
+
+    let ball_entities = template_balls(template);
+    let (new_globe_id, _) = key_value_store.create_globe(&generate_globe_id, &NewGlobe {
+        palette: &PaletteEntity::default(),
+        surface: &SurfaceEntity::default(),
+        surface_image: None,
+        globe_metadata: &GlobeMetadataEntity::default(),
+        ball_entities: &ball_entities,
+    })?;
+
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &GetNewGlobeIdResponse { new_globe_id })
+}
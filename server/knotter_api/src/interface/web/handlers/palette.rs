@@ -0,0 +1,45 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Result};
+use std::sync::Arc;
+use crate::domain::errors::my_error::MyError;
+use shared::domain::dtos::palette_dto::PaletteDto;
+use crate::domain::mapping::palette_mapper::{dto_to_entity, entity_to_dto};
+use crate::helpers::*;
+use actix_web::{get, put};
+use crate::application::services::validation_service::ValidationService;
+use crate::infrastructure::database::key_value_store::{KeyValueStore, KeyValueStoreTrait};
+use log::debug;
+
+// Palette of the globe, an empty one if none has been set
+#[get("/{globe_id}/palette")]
+async fn get_palette(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+
+    let palette = key_value_store.get_palette(&globe_id)?;
+
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &entity_to_dto(&palette))
+}
+
+// Replaces the palette of the globe. Objects already on the globe keep their colors.
+#[put("/{globe_id}/palette")]
+async fn put_palette(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+    body: web::Bytes,
+    validation_service: web::Data<Arc<ValidationService>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+    let palette_dto: PaletteDto = decode_request_body(&req, &body)?;
+    debug!("put_palette START. globe_id={}, palette={:?}", globe_id, palette_dto);
+
+    let palette = dto_to_entity(&palette_dto);
+    validation_service.validate_palette(&palette)?;
+
+    key_value_store.set_palette(&globe_id, &palette)?;
+
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &entity_to_dto(&palette))
+}
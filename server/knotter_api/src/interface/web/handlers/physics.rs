@@ -0,0 +1,46 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Result};
+use std::sync::Arc;
+use crate::domain::errors::my_error::MyError;
+use shared::domain::dtos::physics_profile_dto::PhysicsProfileDto;
+use crate::domain::mapping::physics_profile_mapper::{dto_to_entity, entity_to_dto};
+use crate::helpers::*;
+use actix_web::{get, put};
+use crate::application::services::validation_service::ValidationService;
+use crate::infrastructure::database::key_value_store::{KeyValueStore, KeyValueStoreTrait};
+use log::debug;
+
+// Physics rules of the globe, the default profile if none has been set
+#[get("/{globe_id}/physics")]
+async fn get_physics_profile(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+
+    let physics_profile = key_value_store.get_physics_profile(&globe_id)?;
+
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &entity_to_dto(&physics_profile))
+}
+
+// Replaces the physics rules of the globe. The change is a log entry, updated_at is its transaction time
+// and clients switch to the new rules at that time.
+#[put("/{globe_id}/physics")]
+async fn put_physics_profile(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+    body: web::Bytes,
+    validation_service: web::Data<Arc<ValidationService>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+    let physics_profile_dto: PhysicsProfileDto = decode_request_body(&req, &body)?;
+    debug!("put_physics_profile START. globe_id={}, physics_profile={:?}", globe_id, physics_profile_dto);
+
+    let physics_profile = dto_to_entity(&physics_profile_dto);
+    validation_service.validate_physics_profile(&physics_profile)?;
+
+    let physics_profile = key_value_store.set_physics_profile(&globe_id, &physics_profile)?;
+
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &entity_to_dto(&physics_profile))
+}
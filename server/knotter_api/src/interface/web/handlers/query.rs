@@ -1,4 +1,4 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Result};
 use std::sync::Arc;
 use serde_json;
 use crate::domain::errors::my_error::MyError;
@@ -8,22 +8,41 @@ use actix_web::get;
 use crate::infrastructure::database::key_value_store::KeyValueStore;
 use shared::domain::dtos::ball_transaction_dto::BallTransactionDto;
 use shared::domain::dtos::get_new_globe_id_response_dto::GetNewGlobeIdResponse;
+use shared::domain::dtos::get_snapshot_response_dto::GetSnapshotResponseDto;
+use shared::domain::dtos::geo_position_dto::GeoPositionDto;
+use shared::domain::alive_set_hash::alive_set_hash;
 use crate::domain::models::ball_entity::BallEntity;
 use crate::domain::mapping::ball_mapper::entity_to_dto;
 
-use crate::helpers;
+use serde::Deserialize;
 
-#[get("/{globe_id}/{transaction_id}")]
+const DEFAULT_LOG_PAGE_SIZE: usize = 100;
+const MAX_LOG_PAGE_SIZE: usize = 1000;
+
+#[derive(Deserialize, Debug)]
+pub struct LogQuery {
+    after: Option<String>,
+    limit: Option<usize>,
+}
+
+#[get("/{globe_id}")]
 async fn get_data_by_globe_id(
-    path_info: web::Path<(String, String)>,
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    query: web::Query<LogQuery>,
     key_value_store: web::Data<Arc<KeyValueStore>>,
 ) -> Result<HttpResponse, MyError> {
-    let (globe_id, transaction_id) = (path_info.0.clone(), path_info.1.clone());
-    //debug!("get_data_by_globe_id START: globe_id: {:?} transaction_id: {:?}", globe_id, transaction_id);
+    //debug!("get_data_by_globe_id START: globe_id: {:?} query: {:?}", globe_id, query);
 
     let processed_globe_id = process_globe_id(&globe_id)?;
+    let after = query.after.as_deref().map(process_transaction_id).transpose()?;
+    let limit = query.limit.unwrap_or(DEFAULT_LOG_PAGE_SIZE);
+    if limit == 0 {
+        return Err(MyError::ValidationError("limit must be greater than 0.".to_string()));
+    }
+    let limit = limit.min(MAX_LOG_PAGE_SIZE);
 
-    let results = key_value_store.get_log_data(&processed_globe_id, &transaction_id)?;
+    let (results, has_more) = key_value_store.get_log_data(&processed_globe_id, after, limit)?;
     //debug!("results: {:?}", results);
 
     let ball_transactions: Vec<_> = results
@@ -35,37 +54,118 @@ async fn get_data_by_globe_id(
                 .map_err(|err| MyError::JsonError(err.to_string()))?;
             //debug!("ball_entity: {:?}", ball_entity);
 
-            let ball_dto = entity_to_dto(&ball_entity);
-            //debug!("ball_dto: {:?}", ball_dto);
-
-            let transaction_id = get_after_dashdash(&key)
-                .ok_or(MyError::ValidationError("Invalid transaction key format".to_string()))?;
-            
-            Ok(BallTransactionDto {
-                transaction_id: transaction_id.to_string(),
-                ball_dto,
-            })
+            to_ball_transaction_dto(&key, &ball_entity)
         })
         .collect::<Result<Vec<_>, MyError>>()?;  // Handle potential errors during mapping
 
-    Ok(HttpResponse::Ok().json(GetBallTransactionsByGlobeIdResponseDto { ball_transactions }))
+    // Keep the client's cursor if there was nothing new
+    let next_cursor = ball_transactions
+        .last()
+        .map(|ball_transaction| ball_transaction.transaction_id.clone())
+        .or(after.map(|transaction_id| transaction_id.to_string()));
+
+    // Only on the last page, a client still paging has not seen the whole alive set.
+    // Computed at next_cursor so later transactions do not count.
+    let alive_set_hash = if has_more {
+        None
+    } else {
+        match &next_cursor {
+            Some(next_cursor) => Some(key_value_store.get_alive_set_hash(&processed_globe_id, next_cursor)?),
+            None => Some(alive_set_hash(&[])),
+        }
+    };
+
+    let response = GetBallTransactionsByGlobeIdResponseDto {
+        ball_transactions,
+        next_cursor,
+        has_more,
+        globe_epoch: get_globe_epoch(&key_value_store, &processed_globe_id)?,
+        server_time: generate_timestamp_nanos(),
+        alive_set_hash,
+    };
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &response)
+}
+
+// The alive balls as the transactions that inserted them, for clients that start over instead of reading the whole log
+#[get("/{globe_id}/snapshot")]
+async fn get_snapshot_by_globe_id(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+) -> Result<HttpResponse, MyError> {
+    let processed_globe_id = process_globe_id(&globe_id)?;
+
+    let log_snapshot = key_value_store.get_alive_objects_snapshot(&processed_globe_id, None)?;
+
+    // With the profile changes, so the clients simulate with the rules in force at every step
+    let mut ball_transactions = log_snapshot.alive_objects
+        .values()
+        .chain(log_snapshot.physics_profile_changes.iter())
+        .map(|(key, ball_entity)| to_ball_transaction_dto(key, ball_entity))
+        .collect::<Result<Vec<_>, MyError>>()?;
+    ball_transactions.sort_by(|a, b| a.transaction_id.cmp(&b.transaction_id));
+
+    let next_cursor = log_snapshot.last_key
+        .as_deref()
+        .map(|key| get_after_dashdash(key)
+            .map(|transaction_id| transaction_id.to_string())
+            .ok_or(MyError::ValidationError("Invalid transaction key format".to_string())))
+        .transpose()?;
+
+    let response = GetSnapshotResponseDto {
+        alive_set_hash: alive_set_hash(log_snapshot.alive_objects.keys()),
+        ball_transactions,
+        next_cursor,
+        globe_epoch: get_globe_epoch(&key_value_store, &processed_globe_id)?,
+        server_time: generate_timestamp_nanos(),
+    };
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &response)
+}
+
+fn to_ball_transaction_dto(key: &str, ball_entity: &BallEntity) -> Result<BallTransactionDto, MyError> {
+    let mut ball_dto = entity_to_dto(ball_entity);
+    ball_dto.geo_position = ball_dto.position.as_ref().map(GeoPositionDto::from_position);
+    //debug!("ball_dto: {:?}", ball_dto);
+
+    let transaction_id = get_after_dashdash(key)
+        .ok_or(MyError::ValidationError("Invalid transaction key format".to_string()))?;
+
+    // The transaction id is the server timestamp of the insert.
+    // Links can tie moving balls together, so they also join the simulation at a step.
+    if ball_dto.is_insert && (!ball_dto.is_fixed || ball_dto.link.is_some()) {
+        ball_dto.created_at = Some(parse_transaction_timestamp(transaction_id)?);
+    }
+
+    Ok(BallTransactionDto {
+        transaction_id: transaction_id.to_string(),
+        ball_dto,
+    })
+}
+
+// The globe starts with its first transaction
+fn get_globe_epoch(key_value_store: &KeyValueStore, globe_id: &str) -> Result<Option<i64>, MyError> {
+    let (first_results, _) = key_value_store.get_log_data(globe_id, None, 1)?;
+    first_results
+        .first()
+        .map(|(key, _)| {
+            get_after_dashdash(key)
+                .ok_or(MyError::ValidationError("Invalid transaction key format".to_string()))
+                .and_then(parse_transaction_timestamp)
+        })
+        .transpose()
+}
+
+fn parse_transaction_timestamp(transaction_id: &str) -> Result<i64, MyError> {
+    transaction_id.parse()
+        .map_err(|_| MyError::InternalServerError(format!("Invalid transaction timestamp: {}", transaction_id)))
 }
 
 #[get("/new_globe_id")]
 async fn get_new_globe_id(
+    req: HttpRequest,
     key_value_store: web::Data<Arc<KeyValueStore>>,
 ) -> Result<HttpResponse, MyError> {
-    //Generate new globe_ids until not allready exist
-    let mut new_globe_id = String::new();
-    let mut ok = false;
-    while !ok {
-        let temp_globe_id = helpers::generate_globe_id();
-        let results = key_value_store.get_log_data(&temp_globe_id, "0")?;
-        if results.is_empty() {
-            new_globe_id = temp_globe_id;
-            ok = true;
-        }
-    }
+    let new_globe_id = generate_unused_globe_id(&key_value_store)?;
     
-    Ok(HttpResponse::Ok().json(GetNewGlobeIdResponse { new_globe_id}))
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &GetNewGlobeIdResponse { new_globe_id})
 }
@@ -0,0 +1,84 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Result};
+use std::sync::Arc;
+use crate::domain::errors::my_error::MyError;
+use shared::domain::dtos::surface_dto::SurfaceDto;
+use crate::domain::mapping::surface_mapper::{dto_to_entity, entity_to_dto};
+use crate::domain::models::surface_entity::SurfaceTextureEntity;
+use crate::helpers::*;
+use actix_web::{get, put};
+use crate::application::services::validation_service::ValidationService;
+use crate::infrastructure::database::key_value_store::KeyValueStore;
+use log::debug;
+
+// Surface of the globe, plain if none has been set
+#[get("/{globe_id}/surface")]
+async fn get_surface(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+
+    let surface = key_value_store.get_surface(&globe_id)?;
+
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &entity_to_dto(&surface))
+}
+
+// Picks the texture of the globe. The uploaded one can only be picked after an image is uploaded.
+#[put("/{globe_id}/surface")]
+async fn put_surface(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+    body: web::Bytes,
+    validation_service: web::Data<Arc<ValidationService>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+    let surface_dto: SurfaceDto = decode_request_body(&req, &body)?;
+    debug!("put_surface START. globe_id={}, surface={:?}", globe_id, surface_dto);
+
+    let surface = dto_to_entity(&surface_dto, &key_value_store.get_surface(&globe_id)?);
+    validation_service.validate_surface(&surface)?;
+
+    key_value_store.set_surface(&globe_id, &surface)?;
+
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &entity_to_dto(&surface))
+}
+
+// The uploaded image, not found if none has been uploaded
+#[get("/{globe_id}/surface/image")]
+async fn get_surface_image(
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+
+    let surface = key_value_store.get_surface(&globe_id)?;
+    let content_type = surface.image_content_type.ok_or(MyError::NotFound)?;
+    let image = key_value_store.get_surface_image(&globe_id)?.ok_or(MyError::NotFound)?;
+
+    Ok(HttpResponse::Ok().content_type(content_type).body(image))
+}
+
+// Stores an equirectangular PNG or JPEG as the body and puts it on the globe
+#[put("/{globe_id}/surface/image")]
+async fn put_surface_image(
+    req: HttpRequest,
+    globe_id: web::Path<String>,
+    key_value_store: web::Data<Arc<KeyValueStore>>,
+    body: web::Bytes,
+    validation_service: web::Data<Arc<ValidationService>>,
+) -> Result<HttpResponse, MyError> {
+    let globe_id = process_globe_id(&globe_id)?;
+    debug!("put_surface_image START. globe_id={}, bytes={}", globe_id, body.len());
+
+    let content_type = validation_service.validate_surface_image(&body)?;
+    let mut surface = key_value_store.get_surface(&globe_id)?;
+    surface.texture = SurfaceTextureEntity::Uploaded;
+    surface.image_version = Some(generate_timestamp());
+    surface.image_content_type = Some(content_type.to_string());
+
+    key_value_store.set_surface_image(&globe_id, &surface, &body)?;
+
+    encode_response(StatusCode::OK, negotiate_wire_format(&req), &entity_to_dto(&surface))
+}
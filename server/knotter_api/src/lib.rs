@@ -10,16 +10,41 @@ mod helpers;
 // ... existing module declarations ...
 use actix_cors::Cors;
 use actix_web::{web, App, HttpServer};
-use interface::web::handlers::query::get_new_globe_id;
+use interface::web::handlers::query::{get_new_globe_id, get_snapshot_by_globe_id};
 
 use std::sync::Arc;
+use std::time::Duration;
+use log::{debug, error};
 use crate::interface::web::handlers::delete::delete_data;
 use crate::interface::web::handlers::health_check::healthcheck;
 use crate::interface::web::handlers::insert::handle_insert;
+use crate::interface::web::handlers::batch::handle_batch;
+use crate::interface::web::handlers::physics::{get_physics_profile, put_physics_profile};
+use crate::interface::web::handlers::palette::{get_palette, put_palette};
+use crate::interface::web::handlers::geojson::{get_geojson, post_geojson};
+use crate::interface::web::handlers::export::{get_export_glb, get_globe_export, import_globe, IMPORT_PAYLOAD_LIMIT};
+use crate::interface::web::handlers::new_globe::create_new_globe;
+use crate::interface::web::handlers::fork::{fork_globe, get_globe_metadata};
+use crate::interface::web::handlers::surface::{get_surface, put_surface, get_surface_image, put_surface_image};
 //use crate::interface::web::handlers::insert::gvtest_insert;
 use crate::interface::web::handlers::query::get_data_by_globe_id;
 use crate::infrastructure::database::key_value_store::KeyValueStore;
 use crate::application::services::validation_service::ValidationService;
+use crate::application::services::gltf_exporter::export_glb;
+use crate::infrastructure::database::key_value_store::KeyValueStoreTrait;
+use crate::helpers::process_globe_id;
+
+// Writes the alive set of a globe in the database to a .glb file, the server must not have the database open
+pub fn export_globe_to_glb(globe_id: &str, output_path: &str) -> std::io::Result<()> {
+    let to_io_error = |err: crate::domain::errors::my_error::MyError| std::io::Error::other(err.to_string());
+    let globe_id = process_globe_id(globe_id).map_err(to_io_error)?;
+    let db = KeyValueStore::setup_database(false).map_err(to_io_error)?;
+    let key_value_store = KeyValueStore::new(db);
+
+    let mut ball_entities: Vec<_> = key_value_store.get_alive_objects_map(&globe_id).map_err(to_io_error)?.into_values().collect();
+    ball_entities.sort_by_key(|ball_entity| ball_entity.uuid);
+    std::fs::write(output_path, export_glb(&globe_id, &ball_entities).map_err(to_io_error)?)
+}
 
 pub async fn run_server(is_test_mode: bool) -> std::io::Result<()> {
     let db = KeyValueStore::setup_database(is_test_mode)
@@ -28,18 +53,55 @@ pub async fn run_server(is_test_mode: bool) -> std::io::Result<()> {
     let key_value_store = Arc::new(KeyValueStore::new(db));
     let validation_service = Arc::new(ValidationService::new());
 
+    // Remove stored idempotency responses that are too old to be replayed
+    let cleanup_key_value_store = key_value_store.clone();
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(Duration::from_secs(60 * 60));
+        loop {
+            interval.tick().await;
+            match cleanup_key_value_store.remove_expired_idempotency_records() {
+                Ok(removed) => debug!("Removed {} expired idempotency records", removed),
+                Err(err) => error!("Removing expired idempotency records failed: {}", err),
+            }
+        }
+    });
+
     HttpServer::new(move || {
         let cors = Cors::permissive();
         App::new()
             .wrap(cors)
             .app_data(web::Data::new(key_value_store.clone()))
             .app_data(web::Data::new(validation_service.clone()))
+            // Insert and batch read the raw body to support MessagePack, keep the limit of the JSON extractor
+            .app_data(web::PayloadConfig::new(2 * 1024 * 1024))
+            // Must come before the insert, which takes any single segment POST
+            .service(web::resource("/import")
+                .app_data(web::PayloadConfig::new(IMPORT_PAYLOAD_LIMIT))
+                .route(web::post().to(import_globe)))
+            .service(create_new_globe)
             .service(handle_insert)
+            .service(handle_batch)
             //.service(gvtest_insert)
             .service(delete_data)
             .service(healthcheck)
-            .service(get_data_by_globe_id)
             .service(get_new_globe_id)
+            .service(get_snapshot_by_globe_id)
+            .service(get_physics_profile)
+            .service(put_physics_profile)
+            .service(get_palette)
+            .service(put_palette)
+            .service(get_surface)
+            .service(put_surface)
+            .service(get_surface_image)
+            .service(put_surface_image)
+            .service(get_geojson)
+            .service(post_geojson)
+            .service(get_export_glb)
+            .service(get_globe_export)
+            .service(fork_globe)
+            .service(get_globe_metadata)
+            // Must come after the other single segment GET routes
+            .service(get_data_by_globe_id)
     })
     //.bind("127.0.0.1:8080")?
     .bind("0.0.0.0:8080")?
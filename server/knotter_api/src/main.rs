@@ -1,4 +1,4 @@
-use knotter_api::run_server;
+use knotter_api::{run_server, export_globe_to_glb};
 use std::env;
 use log::{debug};
 use env_logger::Env;
@@ -8,6 +8,13 @@ async fn main() -> std::io::Result<()> {
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
     let args: Vec<String> = env::args().collect();
     debug!("args: {:?}", args);
+    // knotter_api export-glb <globe_id> <output file>
+    if args.get(1).is_some_and(|command| command == "export-glb") {
+        let (Some(globe_id), Some(output_path)) = (args.get(2), args.get(3)) else {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Usage: knotter_api export-glb <globe_id> <output file>"));
+        };
+        return export_globe_to_glb(globe_id, output_path);
+    }
     let is_test_mode = args.contains(&"--test-mode".to_string());
     
 
@@ -4,6 +4,21 @@ use std::process::Command;
 use tokio;
 use shared::domain::dtos::insert_ball_response_dto::InsertBallResponseDto;
 use shared::domain::dtos::get_ball_transactions_by_globeid_response_dto::GetBallTransactionsByGlobeIdResponseDto;
+use shared::domain::dtos::batch_response_dto::BatchResponseDto;
+use shared::domain::dtos::ball_dto::BallDto;
+use shared::domain::dtos::position_dto::PositionDto;
+use shared::domain::dtos::geo_position_dto::GeoPositionDto;
+use shared::domain::dtos::get_snapshot_response_dto::GetSnapshotResponseDto;
+use shared::domain::alive_set_hash::alive_set_hash;
+use shared::domain::dtos::physics_profile_dto::PhysicsProfileDto;
+use shared::domain::dtos::link_dto::LinkDto;
+use shared::domain::dtos::annotation_dto::AnnotationDto;
+use shared::domain::dtos::palette_dto::PaletteDto;
+use shared::domain::dtos::surface_dto::{SurfaceDto, SurfaceTextureDto};
+use shared::domain::dtos::geojson_dto::FeatureCollectionDto;
+use shared::domain::dtos::globe_export_dto::GlobeExportDto;
+use shared::domain::dtos::globe_metadata_dto::GlobeMetadataDto;
+use shared::domain::dtos::get_new_globe_id_response_dto::GetNewGlobeIdResponse;
 
 const BASE_URL: &str = "http://127.0.0.1:8080";
 
@@ -113,7 +128,7 @@ async fn test_set_and_retrieve_data() {
     assert_eq!(insert_response_data.message, "Successfully inserted.".to_string());
 
     // Now, retrieve the data
-    let query_resp = client.get(&format!("{}/{globe_id}/{transaction_id}", BASE_URL, globe_id = globe_id, transaction_id = "0"))
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
         .send()
         .await
         .expect("Failed to send GET request");
@@ -175,7 +190,7 @@ async fn test_delete_data() {
     assert_eq!(insert_response_data.message, "Successfully inserted.".to_string());
 
     // retrieve the data
-    let query_resp = client.get(&format!("{}/{globe_id}/{transaction_id}", BASE_URL, globe_id = globe_id, transaction_id = "0"))
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
         .send()
         .await
         .expect("Failed to send GET request");
@@ -196,7 +211,7 @@ async fn test_delete_data() {
     assert_eq!(query_resp.status(), StatusCode::OK);
 
     // retrieve the data
-    let query_resp = client.get(&format!("{}/{globe_id}/{transaction_id}", BASE_URL, globe_id = globe_id, transaction_id = "0"))
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
         .send()
         .await
         .expect("Failed to send GET request");
@@ -231,4 +246,1528 @@ async fn test_get_new_globe_id() {
 
     let new_globe_id_response: serde_json::Value = resp.json().await.expect("Failed to deserialize response");
     assert!(!new_globe_id_response["new_globe_id"].to_string().is_empty());
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_paginate_data() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    // Insert three fixed balls far enough apart
+    let globe_id = "bepo33kusa".to_string();
+    for x in [-1.05, 1.05, 0.0] {
+        let json_data = serde_json::json!({
+            "is_fixed": true,
+            "is_insert": true,
+            "uuid": uuid::Uuid::new_v4(),
+            "color": "#ff0000ff",
+            "position": {
+                "x": x,
+                "y": if x == 0.0 { 1.05 } else { 0.0 },
+                "z": 0.0
+            },
+            "impulse": serde_json::Value::Null
+        });
+
+        let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+            .json(&json_data)
+            .send()
+            .await
+            .expect("Failed to send POST request");
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    // First page
+    let query_resp = client.get(&format!("{}/{globe_id}?limit=2", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    assert_eq!(query_resp.status(), StatusCode::OK);
+
+    let first_page: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+
+    assert_eq!(first_page.ball_transactions.len(), 2);
+    assert!(first_page.has_more);
+    assert_eq!(first_page.next_cursor.as_ref(), Some(&first_page.ball_transactions[1].transaction_id));
+
+    // Second page continues after the cursor
+    let cursor = first_page.next_cursor.unwrap();
+    let query_resp = client.get(&format!("{}/{globe_id}?after={after}&limit=2", BASE_URL, globe_id = globe_id, after = cursor))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    assert_eq!(query_resp.status(), StatusCode::OK);
+
+    let second_page: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+
+    assert_eq!(second_page.ball_transactions.len(), 1);
+    assert!(!second_page.has_more);
+    assert!(second_page.ball_transactions[0].transaction_id > cursor);
+
+    // Every page has the timestamp of the globe's first transaction as epoch
+    let globe_epoch: i64 = first_page.ball_transactions[0].transaction_id.parse().expect("Transaction id is not a timestamp");
+    assert_eq!(first_page.globe_epoch, Some(globe_epoch));
+    assert_eq!(second_page.globe_epoch, Some(globe_epoch));
+    assert!(second_page.server_time >= globe_epoch);
+
+    // Nothing new keeps the cursor
+    let last_cursor = second_page.next_cursor.unwrap();
+    let query_resp = client.get(&format!("{}/{globe_id}?after={after}", BASE_URL, globe_id = globe_id, after = last_cursor))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    let empty_page: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+
+    assert!(empty_page.ball_transactions.is_empty());
+    assert!(!empty_page.has_more);
+    assert_eq!(empty_page.next_cursor, Some(last_cursor));
+
+    // A limit of zero is rejected
+    let query_resp = client.get(&format!("{}/{globe_id}?limit=0", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    assert_eq!(query_resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_batch_data() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "fira44nole".to_string();
+    let first_uuid = uuid::Uuid::new_v4();
+    let second_uuid = uuid::Uuid::new_v4();
+
+    // The second ball is too close to the first one, so nothing is stored
+    let json_data = serde_json::json!({
+        "balls": [
+            {
+                "is_fixed": true,
+                "is_insert": true,
+                "uuid": first_uuid,
+                "color": "#ff0000ff",
+                "position": { "x": -1.05, "y": 0.0, "z": 0.0 },
+                "impulse": serde_json::Value::Null
+            },
+            {
+                "is_fixed": true,
+                "is_insert": true,
+                "uuid": second_uuid,
+                "color": "#00ff00ff",
+                "position": { "x": -1.05, "y": 0.05, "z": 0.0 },
+                "impulse": serde_json::Value::Null
+            }
+        ]
+    });
+
+    let resp = client.post(&format!("{}/{globe_id}/batch", BASE_URL, globe_id = globe_id))
+        .json(&json_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let batch_response_data: BatchResponseDto = resp.json().await.expect("Failed to deserialize response");
+    assert!(!batch_response_data.committed);
+    assert!(batch_response_data.results[0].error.is_none());
+    assert!(batch_response_data.results[1].error.is_some());
+
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    assert!(query_response_data.ball_transactions.is_empty());
+
+    // Insert two balls far apart and delete the first one in the same batch
+    let json_data = serde_json::json!({
+        "balls": [
+            {
+                "is_fixed": true,
+                "is_insert": true,
+                "uuid": first_uuid,
+                "color": "#ff0000ff",
+                "position": { "x": -1.05, "y": 0.0, "z": 0.0 },
+                "impulse": serde_json::Value::Null
+            },
+            {
+                "is_fixed": true,
+                "is_insert": true,
+                "uuid": second_uuid,
+                "color": "#00ff00ff",
+                "position": { "x": 1.05, "y": 0.0, "z": 0.0 },
+                "impulse": serde_json::Value::Null
+            },
+            {
+                "is_fixed": false,
+                "is_insert": false,
+                "uuid": first_uuid
+            }
+        ]
+    });
+
+    let resp = client.post(&format!("{}/{globe_id}/batch", BASE_URL, globe_id = globe_id))
+        .json(&json_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+
+    if resp.status() != StatusCode::OK {
+        let error_message: String = resp.text().await.expect("Failed to read response text");
+        panic!("Received an error: {}", error_message);
+    }
+
+    let batch_response_data: BatchResponseDto = resp.json().await.expect("Failed to deserialize response");
+    assert!(batch_response_data.committed);
+    assert!(batch_response_data.results.iter().all(|result| result.transaction_id.is_some()));
+
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(query_response_data.ball_transactions.len(), 3);
+    assert!(!query_response_data.ball_transactions[2].ball_dto.is_insert);
+
+    // A ball placed and taken back in the same frame is sent as an insert and then a delete.
+    // The entries are applied in order, so the delete only works after the insert.
+    let third_uuid = uuid::Uuid::new_v4();
+    let insert_third = serde_json::json!({
+        "is_fixed": true,
+        "is_insert": true,
+        "uuid": third_uuid,
+        "color": "#0000ffff",
+        "position": { "x": 0.0, "y": 1.05, "z": 0.0 },
+        "impulse": serde_json::Value::Null
+    });
+    let delete_third = serde_json::json!({
+        "is_fixed": false,
+        "is_insert": false,
+        "uuid": third_uuid
+    });
+
+    let resp = client.post(&format!("{}/{globe_id}/batch", BASE_URL, globe_id = globe_id))
+        .json(&serde_json::json!({ "balls": [delete_third, insert_third] }))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let batch_response_data: BatchResponseDto = resp.json().await.expect("Failed to deserialize response");
+    assert!(!batch_response_data.committed);
+    assert!(batch_response_data.results[0].error.is_some());
+
+    let resp = client.post(&format!("{}/{globe_id}/batch", BASE_URL, globe_id = globe_id))
+        .json(&serde_json::json!({ "balls": [insert_third, delete_third] }))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+
+    if resp.status() != StatusCode::OK {
+        let error_message: String = resp.text().await.expect("Failed to read response text");
+        panic!("Received an error: {}", error_message);
+    }
+    let batch_response_data: BatchResponseDto = resp.json().await.expect("Failed to deserialize response");
+    assert!(batch_response_data.committed);
+
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(query_response_data.ball_transactions.len(), 5);
+    assert!(query_response_data.ball_transactions[3].ball_dto.is_insert);
+    assert!(!query_response_data.ball_transactions[4].ball_dto.is_insert);
+}
+
+#[tokio::test]
+async fn test_idempotency_key() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "hune55tiba".to_string();
+    let uuid = uuid::Uuid::new_v4();
+    let json_data = serde_json::json!({
+        "is_fixed": true,
+        "is_insert": true,
+        "uuid": uuid,
+        "color": "#ff0000ff",
+        "position": {
+            "x": -1.05,
+            "y": 0.0,
+            "z": 0.0
+        },
+        "impulse": serde_json::Value::Null
+    });
+
+    // Send the same insert twice, the retry gets the original response
+    let mut transaction_ids = Vec::new();
+    for _ in 0..2 {
+        let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+            .header("Idempotency-Key", "insert-key-1")
+            .json(&json_data)
+            .send()
+            .await
+            .expect("Failed to send POST request");
+
+        if resp.status() != StatusCode::OK {
+            let error_message: String = resp.text().await.expect("Failed to read response text");
+            panic!("Received an error: {}", error_message);
+        }
+
+        let insert_response_data: InsertBallResponseDto = resp.json().await.expect("Failed to deserialize response");
+        transaction_ids.push(insert_response_data.transaction_id);
+    }
+    assert_eq!(transaction_ids[0], transaction_ids[1]);
+
+    // Reusing the key for another request is rejected
+    let other_json_data = serde_json::json!({
+        "is_fixed": true,
+        "is_insert": true,
+        "uuid": uuid::Uuid::new_v4(),
+        "color": "#ff0000ff",
+        "position": {
+            "x": 1.05,
+            "y": 0.0,
+            "z": 0.0
+        },
+        "impulse": serde_json::Value::Null
+    });
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .header("Idempotency-Key", "insert-key-1")
+        .json(&other_json_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // Send the same delete twice
+    for _ in 0..2 {
+        let resp = client.delete(&format!("{}/{globe_id}/{uuid}", BASE_URL, globe_id = globe_id, uuid = uuid))
+            .header("Idempotency-Key", "delete-key-1")
+            .send()
+            .await
+            .expect("Failed to send DELETE request");
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(query_response_data.ball_transactions.len(), 2);
+
+    // Concurrent retries of the same insert store it once
+    let concurrent_json_data = serde_json::json!({
+        "is_fixed": true,
+        "is_insert": true,
+        "uuid": uuid::Uuid::new_v4(),
+        "color": "#ff0000ff",
+        "position": {
+            "x": 0.0,
+            "y": 1.05,
+            "z": 0.0
+        },
+        "impulse": serde_json::Value::Null
+    });
+    let send_insert = || client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .header("Idempotency-Key", "insert-key-2")
+        .json(&concurrent_json_data)
+        .send();
+    let (first_resp, second_resp) = tokio::join!(send_insert(), send_insert());
+    let first_resp = first_resp.expect("Failed to send POST request");
+    let second_resp = second_resp.expect("Failed to send POST request");
+    assert_eq!(first_resp.status(), StatusCode::OK);
+    assert_eq!(second_resp.status(), StatusCode::OK);
+    let first_response_data: InsertBallResponseDto = first_resp.json().await.expect("Failed to deserialize response");
+    let second_response_data: InsertBallResponseDto = second_resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(first_response_data.transaction_id, second_response_data.transaction_id);
+
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(query_response_data.ball_transactions.len(), 3);
+
+    // A retried batch gets the original response instead of being stored again
+    let batch_json_data = serde_json::json!({
+        "balls": [
+            {
+                "is_fixed": true,
+                "is_insert": true,
+                "uuid": uuid::Uuid::new_v4(),
+                "color": "#ff0000ff",
+                "position": { "x": 0.0, "y": -1.05, "z": 0.0 },
+                "impulse": serde_json::Value::Null
+            },
+            {
+                "is_fixed": true,
+                "is_insert": true,
+                "uuid": uuid::Uuid::new_v4(),
+                "color": "#ff0000ff",
+                "position": { "x": 0.0, "y": 0.0, "z": 1.05 },
+                "impulse": serde_json::Value::Null
+            }
+        ]
+    });
+    let mut batch_transaction_ids = Vec::new();
+    for _ in 0..2 {
+        let resp = client.post(&format!("{}/{globe_id}/batch", BASE_URL, globe_id = globe_id))
+            .header("Idempotency-Key", "batch-key-1")
+            .json(&batch_json_data)
+            .send()
+            .await
+            .expect("Failed to send POST request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let batch_response_data: BatchResponseDto = resp.json().await.expect("Failed to deserialize response");
+        assert!(batch_response_data.committed);
+        batch_transaction_ids.push(batch_response_data.results.into_iter().map(|result| result.transaction_id).collect::<Vec<_>>());
+    }
+    assert_eq!(batch_transaction_ids[0], batch_transaction_ids[1]);
+
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(query_response_data.ball_transactions.len(), 5);
+}
+
+
+#[tokio::test]
+async fn test_msgpack_data() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "kosa66mibe".to_string();
+    let ball_dto = BallDto {
+        is_fixed: true,
+        is_insert: true,
+        uuid: uuid::Uuid::new_v4(),
+        color: Some("#ff0000ff".to_string()),
+        position: Some(PositionDto { x: -1.05, y: 0.0, z: 0.0 }),
+        impulse: None,
+        created_at: None,
+        link: None,
+        body: None,
+        annotation: None,
+        appearance: None,
+        geo_position: None,
+        physics_profile: None,
+    };
+
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .header("Content-Type", "application/msgpack")
+        .header("Accept", "application/msgpack")
+        .body(rmp_serde::to_vec(&ball_dto).expect("Failed to serialize request"))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+
+    if resp.status() != StatusCode::OK {
+        let error_message: String = resp.text().await.expect("Failed to read response text");
+        panic!("Received an error: {}", error_message);
+    }
+    assert_eq!(resp.headers()["Content-Type"], "application/msgpack");
+
+    let body = resp.bytes().await.expect("Failed to read response");
+    let insert_response_data: InsertBallResponseDto = rmp_serde::from_slice(&body).expect("Failed to deserialize response");
+    assert_eq!(insert_response_data.globe_id, globe_id);
+
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .header("Accept", "application/msgpack")
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    assert_eq!(query_resp.headers()["Content-Type"], "application/msgpack");
+    let body = query_resp.bytes().await.expect("Failed to read response");
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = rmp_serde::from_slice(&body).expect("Failed to deserialize response");
+    assert_eq!(query_response_data.ball_transactions.len(), 1);
+    // Returned balls also have their latitude and longitude
+    let ball_dto = BallDto {
+        geo_position: Some(GeoPositionDto::from_position(ball_dto.position.as_ref().unwrap())),
+        ..ball_dto
+    };
+    assert_eq!(query_response_data.ball_transactions[0].ball_dto, ball_dto);
+
+    // Without Accept the same log is sent as JSON
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(query_response_data.ball_transactions[0].ball_dto, ball_dto);
+}
+
+#[tokio::test]
+async fn test_moving_ball_created_at() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "mivo77dera".to_string();
+    let json_data = serde_json::json!({
+        "is_fixed": false,
+        "is_insert": true,
+        "uuid": uuid::Uuid::new_v4(),
+        "color": "#ff0000ff",
+        "position": {
+            "x": -1.05,
+            "y": 0.0,
+            "z": 0.0
+        },
+        "impulse": {
+            "x": 0.0,
+            "y": 0.0,
+            "z": 0.0001
+        },
+        // Only the server sets created_at
+        "created_at": 42
+    });
+
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .json(&json_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+
+    if resp.status() != StatusCode::OK {
+        let error_message: String = resp.text().await.expect("Failed to read response text");
+        panic!("Received an error: {}", error_message);
+    }
+
+    let insert_response_data: InsertBallResponseDto = resp.json().await.expect("Failed to deserialize response");
+
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    let created_at: i64 = insert_response_data.transaction_id.parse().expect("Transaction id is not a timestamp");
+    assert_eq!(query_response_data.ball_transactions[0].ball_dto.created_at, Some(created_at));
+    assert_eq!(query_response_data.globe_epoch, Some(created_at));
+}
+
+#[tokio::test]
+async fn test_snapshot_and_alive_set_hash() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "hasu45selo".to_string();
+    let uuids: Vec<uuid::Uuid> = (0..3).map(|_| uuid::Uuid::new_v4()).collect();
+    for (i, uuid) in uuids.iter().enumerate() {
+        let json_data = serde_json::json!({
+            "is_fixed": true,
+            "is_insert": true,
+            "uuid": uuid,
+            "color": "#ff0000ff",
+            // Spread along the equator so no two balls are too close
+            "position": {
+                "x": -1.05 * (i as f32).cos(),
+                "y": 0.0,
+                "z": 1.05 * (i as f32).sin()
+            },
+            "velocity": serde_json::Value::Null
+        });
+
+        let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+            .json(&json_data)
+            .send()
+            .await
+            .expect("Failed to send POST request");
+
+        if resp.status() != StatusCode::OK {
+            let error_message: String = resp.text().await.expect("Failed to read response text");
+            panic!("Received an error: {}", error_message);
+        }
+    }
+
+    // delete the first ball
+    let resp = client.delete(&format!("{}/{globe_id}/{uuid}", BASE_URL, globe_id = globe_id, uuid = uuids[0]))
+        .send()
+        .await
+        .expect("Failed to send DELETE request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let expected_hash = alive_set_hash(&uuids[1..]);
+
+    // The hash is only on the last page
+    let query_resp = client.get(&format!("{}/{globe_id}?limit=2", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let first_page: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    assert!(first_page.has_more);
+    assert_eq!(first_page.alive_set_hash, None);
+
+    let query_resp = client.get(&format!("{}/{globe_id}?after={after}", BASE_URL, globe_id = globe_id, after = first_page.next_cursor.unwrap()))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let last_page: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    assert!(!last_page.has_more);
+    assert_eq!(last_page.alive_set_hash, Some(expected_hash));
+
+    // The snapshot has only the alive balls, and continues where the log ends
+    let snapshot_resp = client.get(&format!("{}/{globe_id}/snapshot", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    assert_eq!(snapshot_resp.status(), StatusCode::OK);
+    let snapshot: GetSnapshotResponseDto = snapshot_resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(snapshot.alive_set_hash, expected_hash);
+    assert_eq!(snapshot.next_cursor, last_page.next_cursor);
+    let snapshot_uuids: Vec<uuid::Uuid> = snapshot.ball_transactions.iter().map(|ball_transaction| ball_transaction.ball_dto.uuid).collect();
+    assert_eq!(snapshot_uuids, uuids[1..].to_vec());
+}
+
+#[tokio::test]
+async fn test_physics_profile() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "fusi23kalo".to_string();
+
+    // A globe without a profile has the default one
+    let resp = client.get(&format!("{}/{globe_id}/physics", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let physics_profile: PhysicsProfileDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(physics_profile, PhysicsProfileDto::default());
+
+    let new_physics_profile = PhysicsProfileDto {
+        gravity: 3.0,
+        restitution: 0.5,
+        linear_damping: 0.2,
+        preserve_speed: false,
+        updated_at: None,
+        min_ball_radius: 0.03,
+        max_ball_radius: 0.12,
+    };
+    let resp = client.put(&format!("{}/{globe_id}/physics", BASE_URL, globe_id = globe_id))
+        .json(&new_physics_profile)
+        .send()
+        .await
+        .expect("Failed to send PUT request");
+    if resp.status() != StatusCode::OK {
+        let error_message: String = resp.text().await.expect("Failed to read response text");
+        panic!("Received an error: {}", error_message);
+    }
+    let stored_physics_profile: PhysicsProfileDto = resp.json().await.expect("Failed to deserialize response");
+    assert!(stored_physics_profile.updated_at.is_some());
+    assert_eq!(PhysicsProfileDto { updated_at: None, ..stored_physics_profile.clone() }, new_physics_profile);
+
+    let resp = client.get(&format!("{}/{globe_id}/physics", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let physics_profile: PhysicsProfileDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(physics_profile, stored_physics_profile);
+
+    // The change is in the log at the time it was made
+    let resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(query_response_data.ball_transactions.len(), 1);
+    let ball_transaction = &query_response_data.ball_transactions[0];
+    assert_eq!(ball_transaction.ball_dto.physics_profile.as_ref(), Some(&stored_physics_profile));
+    assert_eq!(ball_transaction.transaction_id, stored_physics_profile.updated_at.unwrap().to_string());
+    assert_eq!(query_response_data.alive_set_hash, Some(0));
+
+    // Out of range values are rejected and the stored profile is kept
+    let invalid_physics_profile = PhysicsProfileDto { gravity: -1.0, ..new_physics_profile };
+    let resp = client.put(&format!("{}/{globe_id}/physics", BASE_URL, globe_id = globe_id))
+        .json(&invalid_physics_profile)
+        .send()
+        .await
+        .expect("Failed to send PUT request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_links() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "lino34beka".to_string();
+    let ball_uuids = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4()];
+    for (ball_uuid, position) in ball_uuids.iter().zip([(1.05, 0.0), (0.0, 1.05)]) {
+        let json_data = serde_json::json!({
+            "is_fixed": true,
+            "is_insert": true,
+            "uuid": ball_uuid,
+            "color": "#ff0000ff",
+            "position": {
+                "x": position.0,
+                "y": position.1,
+                "z": 0.0
+            },
+            "velocity": serde_json::Value::Null
+        });
+
+        let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+            .json(&json_data)
+            .send()
+            .await
+            .expect("Failed to send POST request");
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    let link_uuid = uuid::Uuid::new_v4();
+    let link_data = serde_json::json!({
+        "is_fixed": true,
+        "is_insert": true,
+        "uuid": link_uuid,
+        "color": "#00ff00ff",
+        "link": {
+            "from_uuid": ball_uuids[0],
+            "to_uuid": ball_uuids[1]
+        }
+    });
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .json(&link_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    if resp.status() != StatusCode::OK {
+        let error_message: String = resp.text().await.expect("Failed to read response text");
+        panic!("Received an error: {}", error_message);
+    }
+
+    // A link to a ball that does not exist is rejected
+    let invalid_link_data = serde_json::json!({
+        "is_fixed": true,
+        "is_insert": true,
+        "uuid": uuid::Uuid::new_v4(),
+        "color": "#00ff00ff",
+        "link": {
+            "from_uuid": ball_uuids[0],
+            "to_uuid": uuid::Uuid::new_v4()
+        }
+    });
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .json(&invalid_link_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let snapshot_resp = client.get(&format!("{}/{globe_id}/snapshot", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let snapshot: GetSnapshotResponseDto = snapshot_resp.json().await.expect("Failed to deserialize response");
+    let link_dto = snapshot.ball_transactions.iter()
+        .find(|ball_transaction| ball_transaction.ball_dto.uuid == link_uuid)
+        .map(|ball_transaction| ball_transaction.ball_dto.clone())
+        .expect("Link is missing from the snapshot");
+    assert_eq!(link_dto.link, Some(LinkDto { from_uuid: ball_uuids[0], to_uuid: ball_uuids[1] }));
+
+    // Deleting a ball also deletes its links
+    let resp = client.delete(&format!("{}/{globe_id}/{uuid}", BASE_URL, globe_id = globe_id, uuid = ball_uuids[0]))
+        .send()
+        .await
+        .expect("Failed to send DELETE request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let snapshot_resp = client.get(&format!("{}/{globe_id}/snapshot", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let snapshot: GetSnapshotResponseDto = snapshot_resp.json().await.expect("Failed to deserialize response");
+    let snapshot_uuids: Vec<uuid::Uuid> = snapshot.ball_transactions.iter().map(|ball_transaction| ball_transaction.ball_dto.uuid).collect();
+    assert_eq!(snapshot_uuids, vec![ball_uuids[1]]);
+}
+
+#[tokio::test]
+async fn test_annotations() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "tabu56rino".to_string();
+    let annotation_uuid = uuid::Uuid::new_v4();
+    let annotation_data = serde_json::json!({
+        "is_fixed": true,
+        "is_insert": true,
+        "uuid": annotation_uuid,
+        "color": "#000000ff",
+        "position": {
+            "x": 0.0,
+            "y": 1.02,
+            "z": 0.0
+        },
+        "annotation": {
+            "text": "Nordpolen ❄",
+            "font_size": 24.0
+        }
+    });
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .json(&annotation_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    if resp.status() != StatusCode::OK {
+        let error_message: String = resp.text().await.expect("Failed to read response text");
+        panic!("Received an error: {}", error_message);
+    }
+
+    // Empty text is rejected
+    let empty_annotation_data = serde_json::json!({
+        "is_fixed": true,
+        "is_insert": true,
+        "uuid": uuid::Uuid::new_v4(),
+        "color": "#000000ff",
+        "position": {
+            "x": 0.0,
+            "y": -1.02,
+            "z": 0.0
+        },
+        "annotation": {
+            "text": "  ",
+            "font_size": 24.0
+        }
+    });
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .json(&empty_annotation_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(query_response_data.ball_transactions.len(), 1);
+    assert_eq!(query_response_data.ball_transactions[0].ball_dto.annotation, Some(AnnotationDto { text: "Nordpolen ❄".to_string(), font_size: 24.0 }));
+
+    // Annotations are deleted like balls
+    let resp = client.delete(&format!("{}/{globe_id}/{uuid}", BASE_URL, globe_id = globe_id, uuid = annotation_uuid))
+        .send()
+        .await
+        .expect("Failed to send DELETE request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let snapshot_resp = client.get(&format!("{}/{globe_id}/snapshot", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let snapshot: GetSnapshotResponseDto = snapshot_resp.json().await.expect("Failed to deserialize response");
+    assert!(snapshot.ball_transactions.is_empty());
+}
+
+#[tokio::test]
+async fn test_palette() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "dobe67fagu".to_string();
+
+    // A globe without a palette allows any color
+    let resp = client.get(&format!("{}/{globe_id}/palette", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let palette: PaletteDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(palette, PaletteDto::default());
+
+    let new_palette = PaletteDto {
+        colors: vec!["#ff0000ff".to_string(), "#0000ffff".to_string()],
+        is_enforced: true,
+    };
+    let resp = client.put(&format!("{}/{globe_id}/palette", BASE_URL, globe_id = globe_id))
+        .json(&new_palette)
+        .send()
+        .await
+        .expect("Failed to send PUT request");
+    if resp.status() != StatusCode::OK {
+        let error_message: String = resp.text().await.expect("Failed to read response text");
+        panic!("Received an error: {}", error_message);
+    }
+
+    let resp = client.get(&format!("{}/{globe_id}/palette", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let palette: PaletteDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(palette, new_palette);
+
+    // The enforced palette decides which colors new balls can have
+    let ball_in_palette = BallDto {
+        is_fixed: true,
+        is_insert: true,
+        uuid: uuid::Uuid::new_v4(),
+        color: Some("#FF0000FF".to_string()),
+        position: Some(PositionDto { x: 0.0, y: 0.0, z: 1.05 }),
+        impulse: None,
+        created_at: None,
+        link: None,
+        body: None,
+        annotation: None,
+        appearance: None,
+        geo_position: None,
+        physics_profile: None,
+    };
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .json(&ball_in_palette)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let ball_outside_palette = BallDto {
+        uuid: uuid::Uuid::new_v4(),
+        color: Some("#00ff00ff".to_string()),
+        position: Some(PositionDto { x: 0.0, y: 0.0, z: -1.05 }),
+        ..ball_in_palette
+    };
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .json(&ball_outside_palette)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // An enforced palette without colors is rejected
+    let invalid_palette = PaletteDto { colors: Vec::new(), is_enforced: true };
+    let resp = client.put(&format!("{}/{globe_id}/palette", BASE_URL, globe_id = globe_id))
+        .json(&invalid_palette)
+        .send()
+        .await
+        .expect("Failed to send PUT request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_geo_positions() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "gero78lami".to_string();
+    // Without an altitude the ball rests on the surface
+    let ball_data = serde_json::json!({
+        "is_fixed": true,
+        "is_insert": true,
+        "uuid": uuid::Uuid::new_v4(),
+        "color": "#ff0000ff",
+        "geo_position": {
+            "latitude": 59.9,
+            "longitude": 10.7
+        }
+    });
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .json(&ball_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    if resp.status() != StatusCode::OK {
+        let error_message: String = resp.text().await.expect("Failed to read response text");
+        panic!("Received an error: {}", error_message);
+    }
+
+    // Too high over the surface
+    let high_ball_data = serde_json::json!({
+        "is_fixed": true,
+        "is_insert": true,
+        "uuid": uuid::Uuid::new_v4(),
+        "color": "#ff0000ff",
+        "geo_position": {
+            "latitude": -33.9,
+            "longitude": 18.4,
+            "altitude": 0.5
+        }
+    });
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .json(&high_ball_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // Latitude out of range
+    let invalid_ball_data = serde_json::json!({
+        "is_fixed": true,
+        "is_insert": true,
+        "uuid": uuid::Uuid::new_v4(),
+        "color": "#ff0000ff",
+        "geo_position": {
+            "latitude": 120.0,
+            "longitude": 0.0
+        }
+    });
+    let resp = client.post(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .json(&invalid_ball_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // The ball comes back with both its position and its latitude and longitude
+    let query_resp = client.get(&format!("{}/{globe_id}", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let query_response_data: GetBallTransactionsByGlobeIdResponseDto = query_resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(query_response_data.ball_transactions.len(), 1);
+    let ball_dto = &query_response_data.ball_transactions[0].ball_dto;
+    let position = ball_dto.position.as_ref().expect("Position is missing");
+    assert!(position.y > 0.0 && position.x > 0.0 && position.z < 0.0);
+    let geo_position = ball_dto.geo_position.as_ref().expect("Latitude and longitude are missing");
+    assert!((geo_position.latitude - 59.9).abs() < 0.01);
+    assert!((geo_position.longitude - 10.7).abs() < 0.01);
+    assert!((geo_position.altitude.unwrap() - 0.05).abs() < 0.001);
+}
+
+#[tokio::test]
+async fn test_surface() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "suna89pilo".to_string();
+    // A globe starts out plain
+    let resp = client.get(&format!("{}/{globe_id}/surface", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let surface: SurfaceDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(surface, SurfaceDto::default());
+
+    // The uploaded texture needs an image first
+    let resp = client.put(&format!("{}/{globe_id}/surface", BASE_URL, globe_id = globe_id))
+        .json(&SurfaceDto { texture: SurfaceTextureDto::Uploaded, image_version: None })
+        .send()
+        .await
+        .expect("Failed to send PUT request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = client.put(&format!("{}/{globe_id}/surface", BASE_URL, globe_id = globe_id))
+        .json(&SurfaceDto { texture: SurfaceTextureDto::Grid, image_version: None })
+        .send()
+        .await
+        .expect("Failed to send PUT request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // Only the header of a 64x32 PNG is read
+    let mut image = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 13];
+    image.extend_from_slice(b"IHDR");
+    image.extend_from_slice(&64u32.to_be_bytes());
+    image.extend_from_slice(&32u32.to_be_bytes());
+    let resp = client.put(&format!("{}/{globe_id}/surface/image", BASE_URL, globe_id = globe_id))
+        .body(image.clone())
+        .send()
+        .await
+        .expect("Failed to send PUT request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let surface: SurfaceDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(surface.texture, SurfaceTextureDto::Uploaded);
+    assert!(surface.image_version.is_some());
+
+    let resp = client.get(&format!("{}/{globe_id}/surface/image", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["Content-Type"], "image/png");
+    assert_eq!(resp.bytes().await.expect("Failed to read response").to_vec(), image);
+
+    // Square images are not equirectangular
+    let mut square_image = image[..16].to_vec();
+    square_image.extend_from_slice(&32u32.to_be_bytes());
+    square_image.extend_from_slice(&32u32.to_be_bytes());
+    let resp = client.put(&format!("{}/{globe_id}/surface/image", BASE_URL, globe_id = globe_id))
+        .body(square_image)
+        .send()
+        .await
+        .expect("Failed to send PUT request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // Other globes have no image
+    let resp = client.get(&format!("{}/{globe_id}/surface/image", BASE_URL, globe_id = "mura90tevo"))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_geojson() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "gejo12sona".to_string();
+    // The line is reported and nothing is stored
+    let features = serde_json::json!([
+        {
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [10.7, 59.9] },
+            "properties": { "name": "Oslo", "marker-color": "#ff0000" }
+        },
+        {
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [-74.0, 40.7] },
+            "properties": { "color": "#0000ffff" }
+        },
+        {
+            "type": "Feature",
+            "geometry": { "type": "LineString", "coordinates": [[10.7, 59.9], [-74.0, 40.7]] },
+            "properties": null
+        }
+    ]);
+    let resp = client.post(&format!("{}/{globe_id}/geojson", BASE_URL, globe_id = globe_id))
+        .json(&serde_json::json!({ "type": "FeatureCollection", "features": features }))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let import_response: BatchResponseDto = resp.json().await.expect("Failed to deserialize response");
+    assert!(!import_response.committed);
+    assert_eq!(import_response.results.len(), 3);
+    assert!(import_response.results[0].error.is_none() && import_response.results[1].error.is_none());
+    assert!(import_response.results[2].error.is_some());
+
+    let resp = client.post(&format!("{}/{globe_id}/geojson", BASE_URL, globe_id = globe_id))
+        .json(&serde_json::json!({ "type": "FeatureCollection", "features": features.as_array().unwrap()[..2] }))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let import_response: BatchResponseDto = resp.json().await.expect("Failed to deserialize response");
+    assert!(import_response.committed);
+    assert!(import_response.results.iter().all(|result| result.transaction_id.is_some()));
+
+    let resp = client.get(&format!("{}/{globe_id}/geojson", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["Content-Type"], "application/geo+json");
+    let feature_collection: FeatureCollectionDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(feature_collection.features.len(), 2);
+    let oslo = feature_collection.features.iter()
+        .find(|feature| feature.properties.as_ref().unwrap().uuid == Some(import_response.results[0].uuid))
+        .expect("Imported point is missing");
+    let properties = oslo.properties.as_ref().unwrap();
+    assert_eq!(properties.color, Some("#ff0000ff".to_string()));
+    assert_eq!(properties.is_fixed, Some(true));
+    let coordinates = oslo.geometry.as_ref().unwrap().coordinates.as_array().unwrap();
+    assert!((coordinates[0].as_f64().unwrap() - 10.7).abs() < 0.01);
+    assert!((coordinates[1].as_f64().unwrap() - 59.9).abs() < 0.01);
+}
+
+#[tokio::test]
+async fn test_export_glb() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "gabe34fori".to_string();
+    let features = serde_json::json!([
+        { "type": "Feature", "geometry": { "type": "Point", "coordinates": [10.7, 59.9] }, "properties": null },
+        { "type": "Feature", "geometry": { "type": "Point", "coordinates": [-74.0, 40.7] }, "properties": { "radius": 0.1 } }
+    ]);
+    let resp = client.post(&format!("{}/{globe_id}/geojson", BASE_URL, globe_id = globe_id))
+        .json(&serde_json::json!({ "type": "FeatureCollection", "features": features }))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = client.get(&format!("{}/{globe_id}/export.glb", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["Content-Type"], "model/gltf-binary");
+    let glb = resp.bytes().await.expect("Failed to read response");
+    assert_eq!(&glb[0..4], b"glTF");
+    assert_eq!(u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize, glb.len());
+    let json_length = u32::from_le_bytes(glb[12..16].try_into().unwrap()) as usize;
+    let gltf: serde_json::Value = serde_json::from_slice(&glb[20..20 + json_length]).expect("Failed to parse glTF JSON");
+    // The globe and the two balls
+    assert_eq!(gltf["nodes"].as_array().unwrap().len(), 3);
+    assert_eq!(gltf["scenes"][0]["extras"]["globe_id"], globe_id);
+}
+
+#[tokio::test]
+async fn test_globe_export_and_import() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "dabe45fori".to_string();
+    let resp = client.put(&format!("{}/{globe_id}/surface", BASE_URL, globe_id = globe_id))
+        .json(&SurfaceDto { texture: SurfaceTextureDto::Grid, image_version: None })
+        .send()
+        .await
+        .expect("Failed to send PUT request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let features = serde_json::json!([
+        { "type": "Feature", "geometry": { "type": "Point", "coordinates": [10.7, 59.9] }, "properties": null },
+        { "type": "Feature", "geometry": { "type": "Point", "coordinates": [-74.0, 40.7] }, "properties": null }
+    ]);
+    let resp = client.post(&format!("{}/{globe_id}/geojson", BASE_URL, globe_id = globe_id))
+        .json(&serde_json::json!({ "type": "FeatureCollection", "features": features }))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    let import_response: BatchResponseDto = resp.json().await.expect("Failed to deserialize response");
+    let deleted_uuid = import_response.results[0].uuid;
+    let resp = client.delete(&format!("{}/{globe_id}/{uuid}", BASE_URL, globe_id = globe_id, uuid = deleted_uuid))
+        .send()
+        .await
+        .expect("Failed to send DELETE request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // Settings changed after the balls were inserted do not apply to them
+    let resp = client.put(&format!("{}/{globe_id}/palette", BASE_URL, globe_id = globe_id))
+        .json(&PaletteDto { colors: vec!["#123456ff".to_string()], is_enforced: true })
+        .send()
+        .await
+        .expect("Failed to send PUT request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = client.put(&format!("{}/{globe_id}/physics", BASE_URL, globe_id = globe_id))
+        .json(&PhysicsProfileDto { max_ball_radius: 0.04, ..PhysicsProfileDto::default() })
+        .send()
+        .await
+        .expect("Failed to send PUT request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = client.get(&format!("{}/{globe_id}/export", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let globe_export: GlobeExportDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(globe_export.globe_id, globe_id);
+    assert_eq!(globe_export.surface.texture, SurfaceTextureDto::Grid);
+    assert_eq!(globe_export.transactions.len(), 4);
+    assert!(!globe_export.transactions[2].ball_dto.is_insert);
+    assert!(globe_export.transactions[3].ball_dto.physics_profile.is_some());
+
+    // Other versions are not imported
+    let mut old_export = globe_export.clone();
+    old_export.version = 0;
+    let resp = client.post(&format!("{}/import", BASE_URL))
+        .json(&old_export)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = client.post(&format!("{}/import", BASE_URL))
+        .json(&globe_export)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let import_response: BatchResponseDto = resp.json().await.expect("Failed to deserialize response");
+    assert!(import_response.committed);
+    assert_ne!(import_response.globe_id, globe_id);
+
+    // Same history under the new id
+    let resp = client.get(&format!("{}/{globe_id}/export", BASE_URL, globe_id = import_response.globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let imported: GlobeExportDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(imported.surface.texture, SurfaceTextureDto::Grid);
+    let uuids = |globe_export: &GlobeExportDto| globe_export.transactions.iter()
+        .map(|transaction| (transaction.ball_dto.uuid, transaction.ball_dto.is_insert))
+        .collect::<Vec<_>>();
+    assert_eq!(uuids(&imported), uuids(&globe_export));
+    assert_eq!(imported.palette, globe_export.palette);
+
+    // Documents larger than the body of other requests can be imported
+    let mut large_export = serde_json::to_value(&globe_export).expect("Failed to serialize export");
+    large_export["padding"] = serde_json::Value::String("x".repeat(3 * 1024 * 1024));
+    let resp = client.post(&format!("{}/import", BASE_URL))
+        .json(&large_export)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_fork() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    let globe_id = "febu56fori".to_string();
+    let features = serde_json::json!([
+        { "type": "Feature", "geometry": { "type": "Point", "coordinates": [10.7, 59.9] }, "properties": null },
+        { "type": "Feature", "geometry": { "type": "Point", "coordinates": [-74.0, 40.7] }, "properties": null }
+    ]);
+    let resp = client.post(&format!("{}/{globe_id}/geojson", BASE_URL, globe_id = globe_id))
+        .json(&serde_json::json!({ "type": "FeatureCollection", "features": features }))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    let import_response: BatchResponseDto = resp.json().await.expect("Failed to deserialize response");
+    let resp = client.delete(&format!("{}/{globe_id}/{uuid}", BASE_URL, globe_id = globe_id, uuid = import_response.results[0].uuid))
+        .send()
+        .await
+        .expect("Failed to send DELETE request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // Only the alive set is copied by default
+    let resp = client.post(&format!("{}/{globe_id}/fork", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let fork: GetNewGlobeIdResponse = resp.json().await.expect("Failed to deserialize response");
+    assert_ne!(fork.new_globe_id, globe_id);
+    let resp = client.get(&format!("{}/{globe_id}/export", BASE_URL, globe_id = fork.new_globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let forked: GlobeExportDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(forked.transactions.len(), 1);
+    assert_eq!(forked.transactions[0].ball_dto.uuid, import_response.results[1].uuid);
+
+    let resp = client.get(&format!("{}/{globe_id}/metadata", BASE_URL, globe_id = fork.new_globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let globe_metadata: GlobeMetadataDto = resp.json().await.expect("Failed to deserialize response");
+    let provenance = globe_metadata.forked_from.expect("Provenance is missing");
+    assert_eq!(provenance.globe_id, globe_id);
+    assert!(!provenance.with_history);
+
+    // The whole log on request
+    let resp = client.post(&format!("{}/{globe_id}/fork?history=true", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    let fork: GetNewGlobeIdResponse = resp.json().await.expect("Failed to deserialize response");
+    let resp = client.get(&format!("{}/{globe_id}/export", BASE_URL, globe_id = fork.new_globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let forked: GlobeExportDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(forked.transactions.len(), 3);
+
+    // The original has no metadata
+    let resp = client.get(&format!("{}/{globe_id}/metadata", BASE_URL, globe_id = globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let globe_metadata: GlobeMetadataDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(globe_metadata, GlobeMetadataDto::default());
+}
+
+#[tokio::test]
+async fn test_new_globe_from_template() {
+    // Start the service in a test mode
+    let server_process = Command::new("cargo")
+        .args(&["run", "--", "--test-mode"])
+        .spawn()
+        .expect("Failed to start the server");
+
+    let server = TestServer { process: server_process };
+
+    wait_for_server_ready(&format!("{}/health", BASE_URL), 10).await.expect("Server not ready");
+
+    let client = reqwest::Client::new();
+
+    // Handing out an id writes nothing, even with a template
+    let resp = client.get(&format!("{}/new_globe_id?template=grid", BASE_URL))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let new_globe: GetNewGlobeIdResponse = resp.json().await.expect("Failed to deserialize response");
+    let resp = client.get(&format!("{}/{globe_id}/export", BASE_URL, globe_id = new_globe.new_globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let globe_export: GlobeExportDto = resp.json().await.expect("Failed to deserialize response");
+    assert!(globe_export.transactions.is_empty());
+
+    let resp = client.post(&format!("{}/new_globe?template=grid", BASE_URL))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let new_globe: GetNewGlobeIdResponse = resp.json().await.expect("Failed to deserialize response");
+    let resp = client.get(&format!("{}/{globe_id}/export", BASE_URL, globe_id = new_globe.new_globe_id))
+        .send()
+        .await
+        .expect("Failed to send GET request");
+    let globe_export: GlobeExportDto = resp.json().await.expect("Failed to deserialize response");
+    assert_eq!(globe_export.transactions.len(), 2 + 5 * 12);
+    assert!(globe_export.transactions.iter().all(|transaction| transaction.ball_dto.is_fixed));
+
+    let resp = client.post(&format!("{}/new_globe?template=nothing", BASE_URL))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,29 @@
+use uuid::Uuid;
+
+// Hash of the uuids of the alive balls of a globe. Server and clients compute it the same way
+// to find out if a client has diverged from the server.
+// Order independent, the sum of a hash per uuid, so it can be computed from any collection.
+pub fn alive_set_hash<'a>(uuids: impl IntoIterator<Item = &'a Uuid>) -> u64 {
+    uuids
+        .into_iter()
+        .fold(0u64, |hash, uuid| hash.wrapping_add(uuid_hash(uuid)))
+}
+
+// splitmix64 of the two halves, the same on every platform unlike std's hashers
+fn uuid_hash(uuid: &Uuid) -> u64 {
+    let value = uuid.as_u128();
+    let mut z = ((value >> 64) as u64 ^ value as u64).wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[test]
+fn test_alive_set_hash_is_order_independent() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let reversed: Vec<Uuid> = uuids.iter().rev().cloned().collect();
+
+    assert_eq!(alive_set_hash(&uuids), alive_set_hash(&reversed));
+    assert_ne!(alive_set_hash(&uuids), alive_set_hash(&uuids[..2]));
+    assert_eq!(alive_set_hash(&[]), 0);
+}
\ No newline at end of file
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+// Longest text of an annotation, in characters
+pub const MAX_ANNOTATION_LENGTH: usize = 280;
+pub const MIN_FONT_SIZE: f32 = 8.0;
+pub const MAX_FONT_SIZE: f32 = 72.0;
+pub const DEFAULT_FONT_SIZE: f32 = 24.0;
+// The anchor is on the globe surface or at most this far above it
+pub const MAX_ANCHOR_HEIGHT: f32 = 0.05;
+
+// Text pinned to the globe at the position of the object, in its color
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct AnnotationDto {
+    pub text: String,
+    pub font_size: f32,
+}
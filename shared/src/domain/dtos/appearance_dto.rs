@@ -0,0 +1,47 @@
+use serde::{Serialize, Deserialize};
+
+// Largest values the server accepts, all values must be at least 0
+pub const MAX_EMISSIVE: f32 = 10.0;
+pub const MAX_METALLIC: f32 = 1.0;
+pub const MAX_ROUGHNESS: f32 = 1.0;
+// Balls can not be made invisible
+pub const MAX_TRANSPARENCY: f32 = 0.9;
+
+pub const DEFAULT_ROUGHNESS: f32 = 0.5;
+
+// How the surface of a ball looks. Balls without one look like plain colored balls.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+pub struct AppearanceDto {
+    // Glow in the color of the ball, 0 for none. Glowing balls light up what is near them.
+    #[serde(default)]
+    pub emissive: f32,
+    #[serde(default)]
+    pub metallic: f32,
+    #[serde(default = "default_roughness")]
+    pub roughness: f32,
+    // 0 is opaque
+    #[serde(default)]
+    pub transparency: f32,
+}
+
+fn default_roughness() -> f32 {
+    DEFAULT_ROUGHNESS
+}
+
+impl Default for AppearanceDto {
+    fn default() -> Self {
+        AppearanceDto {
+            emissive: 0.0,
+            metallic: 0.0,
+            roughness: DEFAULT_ROUGHNESS,
+            transparency: 0.0,
+        }
+    }
+}
+
+#[test]
+fn test_deserialization_appearancedto() {
+    let payload = r#"{"emissive":4.0}"#;
+    let deserialized: AppearanceDto = serde_json::from_str(payload).unwrap();
+    assert_eq!(deserialized, AppearanceDto { emissive: 4.0, ..AppearanceDto::default() });
+}
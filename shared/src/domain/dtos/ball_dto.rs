@@ -2,6 +2,12 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use crate::domain::dtos::impulse_dto::ImpulseDto;
 use crate::domain::dtos::position_dto::PositionDto;
+use crate::domain::dtos::link_dto::LinkDto;
+use crate::domain::dtos::body_dto::BodyDto;
+use crate::domain::dtos::annotation_dto::AnnotationDto;
+use crate::domain::dtos::appearance_dto::AppearanceDto;
+use crate::domain::dtos::geo_position_dto::GeoPositionDto;
+use crate::domain::dtos::physics_profile_dto::PhysicsProfileDto;
 
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone)]
 pub struct BallDto {
@@ -11,4 +17,28 @@ pub struct BallDto {
     pub color: Option<String>, 
     pub position: Option<PositionDto>,
     pub impulse: Option<ImpulseDto>,
+    // Server timestamp in nanoseconds when a moving ball was inserted, the clients start simulating it from then.
+    // Set by the server, ignored on insert.
+    #[serde(default)]
+    pub created_at: Option<i64>,
+    // Set when the object is a link between two balls instead of a ball. Links have no position or impulse.
+    #[serde(default)]
+    pub link: Option<LinkDto>,
+    // None is a sphere with the default radius
+    #[serde(default)]
+    pub body: Option<BodyDto>,
+    // Set when the object is a text annotation anchored at position. Annotations have no impulse.
+    #[serde(default)]
+    pub annotation: Option<AnnotationDto>,
+    // None looks like a plain colored ball
+    #[serde(default)]
+    pub appearance: Option<AppearanceDto>,
+    // Latitude and longitude instead of position, used on insert when position is None.
+    // Set by the server on the objects it returns.
+    #[serde(default)]
+    pub geo_position: Option<GeoPositionDto>,
+    // Set when the entry is a change of the physics rules instead of an object. It is never alive.
+    // Written by the server, clients switch to it at the step of its transaction.
+    #[serde(default)]
+    pub physics_profile: Option<PhysicsProfileDto>,
 }
\ No newline at end of file
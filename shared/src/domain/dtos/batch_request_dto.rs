@@ -0,0 +1,12 @@
+use serde::{Serialize, Deserialize};
+use crate::domain::dtos::ball_dto::BallDto;
+
+// Most balls one batch can carry
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+// Inserts and deletes to apply to a globe in one request.
+// Uses is_insert on each BallDto to tell inserts from deletes, like the transaction log.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BatchRequestDto {
+    pub balls: Vec<BallDto>,
+}
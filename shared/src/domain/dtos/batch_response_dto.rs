@@ -0,0 +1,19 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchResponseDto {
+    pub message: String,
+    pub globe_id: String,
+    // False if any item failed validation. Then nothing was stored.
+    pub committed: bool,
+    // One result per item, in request order.
+    pub results: Vec<BatchItemResultDto>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchItemResultDto {
+    pub uuid: Uuid,
+    pub transaction_id: Option<String>,
+    pub error: Option<String>,
+}
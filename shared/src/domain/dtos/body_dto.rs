@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+// Radius of balls sent without a body
+pub const DEFAULT_BALL_RADIUS: f32 = 0.05;
+// Limits for the ball radius bounds of a globe
+pub const MIN_BALL_RADIUS: f32 = 0.01;
+pub const MAX_BALL_RADIUS: f32 = 0.2;
+pub const MAX_BALL_MASS: f32 = 100.0;
+
+// Sizes of the shapes relative to the radius, so every shape fits inside the sphere with the radius.
+// The cube corners touch the sphere, the capsule is as long as the sphere and half as wide.
+pub const CUBE_HALF_SIZE: f32 = 0.577_350_3;
+pub const CAPSULE_RADIUS: f32 = 0.5;
+pub const CAPSULE_HALF_HEIGHT: f32 = 0.5;
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ShapeDto {
+    #[default]
+    Sphere,
+    Cube,
+    Capsule,
+}
+
+// Size, mass and shape of a ball. The surface and distance checks use the sphere with the radius.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct BodyDto {
+    pub shape: ShapeDto,
+    pub radius: f32,
+    // None gives the mass of the shape with the default density
+    #[serde(default)]
+    pub mass: Option<f32>,
+}
+
+impl Default for BodyDto {
+    fn default() -> Self {
+        BodyDto {
+            shape: ShapeDto::Sphere,
+            radius: DEFAULT_BALL_RADIUS,
+            mass: None,
+        }
+    }
+}
+
+#[test]
+fn test_deserialization_bodydto() {
+    let payload = r#"{"shape":"cube","radius":0.08}"#;
+    let deserialized: BodyDto = serde_json::from_str(payload).unwrap();
+    assert_eq!(deserialized, BodyDto { shape: ShapeDto::Cube, radius: 0.08, mass: None });
+}
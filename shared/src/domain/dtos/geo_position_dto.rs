@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use crate::domain::dtos::position_dto::PositionDto;
+
+pub const GLOBE_RADIUS: f32 = 1.0;
+
+// Position on the globe in degrees, the y axis goes through the poles and longitude 0 is along the x axis.
+// Longitude grows toward -z, so east is to the right seen from outside with north up.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GeoPositionDto {
+    pub latitude: f32,
+    pub longitude: f32,
+    // Height of the center over the surface. None rests a ball on the surface.
+    #[serde(default)]
+    pub altitude: Option<f32>,
+}
+
+impl GeoPositionDto {
+    // None when the latitude is not between -90 and 90, or a value is not a number
+    pub fn to_position(&self, radius: f32) -> Option<PositionDto> {
+        let altitude = self.altitude.unwrap_or(radius);
+        if !(-90.0..=90.0).contains(&self.latitude) || !self.longitude.is_finite() || !altitude.is_finite() {
+            return None;
+        }
+        let latitude = self.latitude.to_radians();
+        let longitude = self.longitude.to_radians();
+        let distance = GLOBE_RADIUS + altitude;
+        Some(PositionDto {
+            x: distance * latitude.cos() * longitude.cos(),
+            y: distance * latitude.sin(),
+            z: -distance * latitude.cos() * longitude.sin(),
+        })
+    }
+
+    // Longitude is between -180 and 180
+    pub fn from_position(position: &PositionDto) -> GeoPositionDto {
+        let distance = (position.x * position.x + position.y * position.y + position.z * position.z).sqrt();
+        let latitude = if distance > 0.0 { (position.y / distance).clamp(-1.0, 1.0).asin() } else { 0.0 };
+        GeoPositionDto {
+            latitude: latitude.to_degrees(),
+            longitude: (-position.z).atan2(position.x).to_degrees(),
+            altitude: Some(distance - GLOBE_RADIUS),
+        }
+    }
+}
+
+#[test]
+fn test_geo_position_round_trip() {
+    let geo_position = GeoPositionDto { latitude: 59.9, longitude: 10.7, altitude: Some(0.05) };
+    let position = geo_position.to_position(0.08).unwrap();
+    assert!(((position.x * position.x + position.y * position.y + position.z * position.z).sqrt() - 1.05).abs() < 1e-5);
+    let round_trip = GeoPositionDto::from_position(&position);
+    assert!((round_trip.latitude - 59.9).abs() < 1e-3);
+    assert!((round_trip.longitude - 10.7).abs() < 1e-3);
+    assert!((round_trip.altitude.unwrap() - 0.05).abs() < 1e-5);
+}
+
+#[test]
+fn test_geo_position_rests_on_surface() {
+    let north_pole = GeoPositionDto { latitude: 90.0, longitude: 0.0, altitude: None };
+    let position = north_pole.to_position(0.05).unwrap();
+    assert!(position.x.abs() < 1e-6 && (position.y - 1.05).abs() < 1e-6 && position.z.abs() < 1e-6);
+    let east = GeoPositionDto { latitude: 0.0, longitude: 90.0, altitude: None }.to_position(0.05).unwrap();
+    assert!((east.z + 1.05).abs() < 1e-6);
+    assert_eq!(GeoPositionDto { latitude: 91.0, longitude: 0.0, altitude: None }.to_position(0.05), None);
+}
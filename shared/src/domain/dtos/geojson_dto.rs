@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const GEOJSON_CONTENT_TYPE: &str = "application/geo+json";
+
+// GeoJSON of the balls on a globe. Only the parts knotter uses are typed, other members are ignored.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FeatureCollectionDto {
+    #[serde(rename = "type")]
+    pub collection_type: String, // FeatureCollection
+    pub features: Vec<FeatureDto>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FeatureDto {
+    #[serde(rename = "type")]
+    pub feature_type: String, // Feature
+    pub geometry: Option<GeometryDto>,
+    #[serde(default)]
+    pub properties: Option<FeaturePropertiesDto>,
+}
+
+// Kept untyped so a feature that is not a point is reported on its own instead of failing the whole file.
+// A point is [longitude, latitude] in degrees, with the altitude over the surface as an optional third value.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GeometryDto {
+    #[serde(rename = "type")]
+    pub geometry_type: String,
+    pub coordinates: serde_json::Value,
+}
+
+// Every property is optional on import. Points without a color are white, without is_fixed they are fixed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct FeaturePropertiesDto {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+    // #RRGGBBAA
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    // #RRGGBB from simplestyle, used when there is no color
+    #[serde(default, rename = "marker-color", skip_serializing_if = "Option::is_none")]
+    pub marker_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_fixed: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f32>,
+    // Impulse of a moving ball, [x, y, z] along the surface
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impulse: Option<[f32; 3]>,
+}
+
+#[test]
+fn test_deserialization_featurecollectiondto() {
+    let payload = r##"{"type":"FeatureCollection","features":[
+        {"type":"Feature","geometry":{"type":"Point","coordinates":[10.7,59.9]},"properties":{"name":"Oslo","marker-color":"#ff0000"}},
+        {"type":"Feature","geometry":{"type":"LineString","coordinates":[[0,0],[1,1]]},"properties":null}
+    ]}"##;
+    let deserialized: FeatureCollectionDto = serde_json::from_str(payload).unwrap();
+    assert_eq!(deserialized.features.len(), 2);
+    assert_eq!(deserialized.features[0].properties.as_ref().unwrap().marker_color, Some("#ff0000".to_string()));
+    assert_eq!(deserialized.features[1].properties, None);
+}
@@ -4,4 +4,14 @@ use crate::domain::dtos::ball_transaction_dto::BallTransactionDto;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetBallTransactionsByGlobeIdResponseDto {
     pub ball_transactions: Vec<BallTransactionDto>,
-}
\ No newline at end of file
+    // Transaction id to pass as `after` in the next request. None if the globe has no transactions yet.
+    pub next_cursor: Option<String>,
+    // True if there are more transactions after next_cursor.
+    pub has_more: bool,
+    // Timestamp in nanoseconds of the globe's first transaction. The shared simulation clock counts fixed timesteps from here.
+    pub globe_epoch: Option<i64>,
+    // Server time in nanoseconds when the response was made, lets clients agree on what "now" is.
+    pub server_time: i64,
+    // alive_set_hash of the globe at next_cursor. Only on the last page.
+    pub alive_set_hash: Option<u64>,
+}
@@ -0,0 +1,13 @@
+use serde::{Serialize, Deserialize};
+use crate::domain::dtos::ball_transaction_dto::BallTransactionDto;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetSnapshotResponseDto {
+    // The insert transactions of the alive balls, oldest first
+    pub ball_transactions: Vec<BallTransactionDto>,
+    // Transaction id to continue reading the log from. None if the globe has no transactions yet.
+    pub next_cursor: Option<String>,
+    pub globe_epoch: Option<i64>,
+    pub server_time: i64,
+    pub alive_set_hash: u64,
+}
\ No newline at end of file
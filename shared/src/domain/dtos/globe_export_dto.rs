@@ -0,0 +1,42 @@
+use serde::{Serialize, Deserialize};
+use crate::domain::dtos::ball_transaction_dto::BallTransactionDto;
+use crate::domain::dtos::physics_profile_dto::PhysicsProfileDto;
+use crate::domain::dtos::palette_dto::PaletteDto;
+use crate::domain::dtos::surface_dto::SurfaceDto;
+
+// Changes when documents of an older version can no longer be imported as they are
+pub const GLOBE_EXPORT_VERSION: u32 = 1;
+
+// A globe with its settings and whole history, for backups and moving globes between servers.
+// Uploaded surface images are not part of it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GlobeExportDto {
+    pub version: u32,
+    pub globe_id: String,
+    // Server time of the export
+    pub exported_at: i64,
+    pub physics_profile: PhysicsProfileDto,
+    pub palette: PaletteDto,
+    pub surface: SurfaceDto,
+    // The log in the order it was written, deletes included
+    pub transactions: Vec<BallTransactionDto>,
+}
+
+#[test]
+fn test_deserialization_globeexportdto() {
+    let payload = r##"{
+        "version": 1,
+        "globe_id": "guni12guni",
+        "exported_at": 1700000000000000000,
+        "physics_profile": { "gravity": 9.8, "restitution": 1.0, "linear_damping": 0.0, "preserve_speed": true },
+        "palette": { "colors": ["#ff0000ff"], "is_enforced": false },
+        "surface": { "texture": "earth" },
+        "transactions": [
+            { "transaction_id": "1700000000000000000", "ball_dto": { "is_fixed": true, "is_insert": true, "uuid": "a55018a3-a5fd-408b-876b-7bec638cdda1", "color": "#ff0000ff", "position": { "x": 0.0, "y": 0.0, "z": 1.05 }, "impulse": null } }
+        ]
+    }"##;
+    let deserialized: GlobeExportDto = serde_json::from_str(payload).unwrap();
+    assert_eq!(deserialized.version, GLOBE_EXPORT_VERSION);
+    assert_eq!(deserialized.transactions.len(), 1);
+    assert_eq!(deserialized.physics_profile, PhysicsProfileDto::default());
+}
@@ -0,0 +1,20 @@
+use serde::{Serialize, Deserialize};
+
+// Where a forked globe was copied from
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ProvenanceDto {
+    pub globe_id: String,
+    // Last transaction of the original globe in the copy. None if the original was empty.
+    pub transaction_id: Option<String>,
+    // Server time of the fork
+    pub forked_at: i64,
+    // True if the whole log was copied, false if only the alive set
+    pub with_history: bool,
+}
+
+// Facts about a globe that are not part of its log
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct GlobeMetadataDto {
+    #[serde(default)]
+    pub forked_from: Option<ProvenanceDto>,
+}
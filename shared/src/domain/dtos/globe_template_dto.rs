@@ -0,0 +1,45 @@
+use serde::{Serialize, Deserialize};
+
+// Seed data a new globe can start from, given as ?template= to POST new_globe
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum GlobeTemplateDto {
+    #[default]
+    Empty,
+    // Moving balls circling the globe between fixed balls at the poles
+    Orbit,
+    // Fixed balls on a latitude and longitude grid
+    Grid,
+    // Fixed balls at random places
+    Scatter,
+}
+
+impl GlobeTemplateDto {
+    pub const ALL: [GlobeTemplateDto; 4] = [GlobeTemplateDto::Empty, GlobeTemplateDto::Orbit, GlobeTemplateDto::Grid, GlobeTemplateDto::Scatter];
+
+    // Value of the template query parameter
+    pub fn name(&self) -> &'static str {
+        match self {
+            GlobeTemplateDto::Empty => "empty",
+            GlobeTemplateDto::Orbit => "orbit",
+            GlobeTemplateDto::Grid => "grid",
+            GlobeTemplateDto::Scatter => "scatter",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GlobeTemplateDto::Empty => "Empty",
+            GlobeTemplateDto::Orbit => "Orbit demo",
+            GlobeTemplateDto::Grid => "Grid",
+            GlobeTemplateDto::Scatter => "Random scatter",
+        }
+    }
+}
+
+#[test]
+fn test_globetemplatedto_name_is_serialized_name() {
+    for template in GlobeTemplateDto::ALL {
+        assert_eq!(serde_json::to_string(&template).unwrap(), format!("\"{}\"", template.name()));
+    }
+}
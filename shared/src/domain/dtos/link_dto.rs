@@ -0,0 +1,9 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+// Link between two balls. The link is an object of its own in the log, with its own uuid.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct LinkDto {
+    pub from_uuid: Uuid,
+    pub to_uuid: Uuid,
+}
\ No newline at end of file
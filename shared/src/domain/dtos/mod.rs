@@ -6,4 +6,19 @@ pub mod position_dto;
 pub mod impulse_dto;
 pub mod ball_dto;
 pub mod ball_transaction_dto;
-pub mod get_new_globe_id_response_dto;
\ No newline at end of file
+pub mod get_new_globe_id_response_dto;
+pub mod batch_request_dto;
+pub mod batch_response_dto;
+pub mod get_snapshot_response_dto;
+pub mod physics_profile_dto;
+pub mod link_dto;
+pub mod body_dto;
+pub mod annotation_dto;
+pub mod palette_dto;
+pub mod appearance_dto;
+pub mod geo_position_dto;
+pub mod surface_dto;
+pub mod geojson_dto;
+pub mod globe_export_dto;
+pub mod globe_metadata_dto;
+pub mod globe_template_dto;
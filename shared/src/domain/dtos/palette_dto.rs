@@ -0,0 +1,25 @@
+use serde::{Serialize, Deserialize};
+
+// A palette fills the color swatches of the client
+pub const MAX_PALETTE_COLORS: usize = 8;
+
+// Colors chosen for a globe. When enforced, the server only accepts new objects in these colors.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct PaletteDto {
+    pub colors: Vec<String>, // #RRGGBBAA
+    pub is_enforced: bool,
+}
+
+impl PaletteDto {
+    // Colors are compared without case, #ff0000ff and #FF0000FF are the same color
+    pub fn contains(&self, color: &str) -> bool {
+        self.colors.iter().any(|palette_color| palette_color.eq_ignore_ascii_case(color))
+    }
+}
+
+#[test]
+fn test_palettedto_contains_color_in_any_case() {
+    let palette = PaletteDto { colors: vec!["#FF0000FF".to_string()], is_enforced: true };
+    assert!(palette.contains("#ff0000ff"));
+    assert!(!palette.contains("#00ff00ff"));
+}
@@ -0,0 +1,52 @@
+use serde::{Serialize, Deserialize};
+use crate::domain::dtos::body_dto::DEFAULT_BALL_RADIUS;
+
+// Largest values the server accepts, all values must be at least 0
+pub const MAX_GRAVITY: f32 = 50.0;
+pub const MAX_RESTITUTION: f32 = 1.0;
+pub const MAX_LINEAR_DAMPING: f32 = 10.0;
+
+// Radius bounds of new balls on globes that have not changed them
+pub const DEFAULT_MIN_BALL_RADIUS: f32 = DEFAULT_BALL_RADIUS / 2.0;
+pub const DEFAULT_MAX_BALL_RADIUS: f32 = DEFAULT_BALL_RADIUS * 2.0;
+
+// Rules every viewer of a globe simulates the moving balls with
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct PhysicsProfileDto {
+    // Acceleration toward the center of the globe
+    pub gravity: f32,
+    pub restitution: f32,
+    pub linear_damping: f32,
+    // Balls leave a collision with the speed they came in with
+    pub preserve_speed: bool,
+    // Server time the profile was changed, set by the server. None for the default profile.
+    #[serde(default)]
+    pub updated_at: Option<i64>,
+    // Radius of new balls must be within these
+    #[serde(default = "default_min_ball_radius")]
+    pub min_ball_radius: f32,
+    #[serde(default = "default_max_ball_radius")]
+    pub max_ball_radius: f32,
+}
+
+fn default_min_ball_radius() -> f32 {
+    DEFAULT_MIN_BALL_RADIUS
+}
+
+fn default_max_ball_radius() -> f32 {
+    DEFAULT_MAX_BALL_RADIUS
+}
+
+impl Default for PhysicsProfileDto {
+    fn default() -> Self {
+        PhysicsProfileDto {
+            gravity: 9.8,
+            restitution: 1.0,
+            linear_damping: 0.0,
+            preserve_speed: true,
+            updated_at: None,
+            min_ball_radius: DEFAULT_MIN_BALL_RADIUS,
+            max_ball_radius: DEFAULT_MAX_BALL_RADIUS,
+        }
+    }
+}
\ No newline at end of file
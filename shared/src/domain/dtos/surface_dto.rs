@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+// Uploads are read as the raw request body, which the server limits to this size
+pub const MAX_SURFACE_IMAGE_BYTES: usize = 2 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SurfaceTextureDto {
+    #[default]
+    Plain,
+    Earth,
+    Grid,
+    Checkerboard,
+    // The equirectangular image uploaded to /{globe_id}/surface/image
+    Uploaded,
+}
+
+// Texture on the globe. Earth, grid and checkerboard come with the client.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone)]
+pub struct SurfaceDto {
+    pub texture: SurfaceTextureDto,
+    // Set by the server when an image is uploaded, changes with every upload so clients load it again
+    #[serde(default)]
+    pub image_version: Option<String>,
+}
+
+#[test]
+fn test_deserialization_surfacedto() {
+    let payload = r#"{"texture":"checkerboard"}"#;
+    let deserialized: SurfaceDto = serde_json::from_str(payload).unwrap();
+    assert_eq!(deserialized, SurfaceDto { texture: SurfaceTextureDto::Checkerboard, image_version: None });
+}
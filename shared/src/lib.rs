@@ -1,3 +1,4 @@
 pub mod domain {
     pub mod dtos;
+    pub mod alive_set_hash;
 }